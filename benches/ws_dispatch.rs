@@ -0,0 +1,54 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use solana_trader_client_rust::connections::frame::Frame;
+use solana_trader_client_rust::provider::enums;
+use solana_trader_proto::api;
+
+const TRADES: &str = include_str!("frames/trades.json");
+const PUMP_FUN_SWAPS: &str = include_str!("frames/pump_fun_swaps.json");
+
+// The previous dispatch path: parse the frame into a `Value`, clone `params.result` out of it and
+// deserialize the clone.
+fn value_tree<T: DeserializeOwned>(text: String) -> Option<T> {
+    let value: Value = serde_json::from_str(&text).ok()?;
+    value.get("params")?.get("subscription")?.as_str()?;
+    let result = value.get("params")?.get("result")?.clone();
+    enums::from_value(result).ok()
+}
+
+fn single_pass<T: DeserializeOwned>(text: String) -> Option<T> {
+    match Frame::parse(text)? {
+        Frame::Notification(notification) => notification.decode().ok(),
+        Frame::Response { .. } => None,
+    }
+}
+
+fn bench_frame<T: DeserializeOwned>(c: &mut Criterion, name: &str, frame: &str) {
+    assert!(
+        single_pass::<T>(frame.to_string()).is_some(),
+        "{} frame must decode",
+        name
+    );
+
+    let mut group = c.benchmark_group(name);
+    group.bench_function(BenchmarkId::new("value_tree", frame.len()), |b| {
+        b.iter_batched(|| frame.to_string(), value_tree::<T>, BatchSize::SmallInput)
+    });
+    group.bench_function(BenchmarkId::new("single_pass", frame.len()), |b| {
+        b.iter_batched(
+            || frame.to_string(),
+            single_pass::<T>,
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+fn dispatch(c: &mut Criterion) {
+    bench_frame::<api::GetTradesStreamResponse>(c, "trades_stream", TRADES);
+    bench_frame::<api::GetPumpFunSwapsStreamResponse>(c, "pump_fun_swaps_stream", PUMP_FUN_SWAPS);
+}
+
+criterion_group!(benches, dispatch);
+criterion_main!(benches);
@@ -1,11 +1,16 @@
 pub mod constants;
+pub mod package;
+pub mod policy;
+pub mod signer;
 pub mod signing;
+pub mod transaction;
 
-use std::{env, str::FromStr};
+use std::{env, str::FromStr, sync::Arc};
 
 use anyhow::{anyhow, Result};
 use constants::{LOCAL, MAINNET_NY, MAINNET_PUMP_NY, MAINNET_PUMP_UK, MAINNET_UK, TESTNET};
 use dotenv::dotenv;
+use signer::TransactionSigner;
 use solana_sdk::{bs58::decode, pubkey::Pubkey, signature::Keypair};
 
 pub fn http_endpoint(base_url: &str, secure: bool) -> String {
@@ -44,6 +49,7 @@ pub struct BaseConfig {
     pub auth_header: String,
     pub keypair: Option<Keypair>,
     pub public_key: Option<Pubkey>,
+    pub signer: Option<Arc<dyn TransactionSigner>>,
 }
 
 impl BaseConfig {
@@ -81,10 +87,15 @@ impl BaseConfig {
             None
         };
 
+        let signer = keypair
+            .as_ref()
+            .map(|kp| Arc::new(kp.insecure_clone()) as Arc<dyn TransactionSigner>);
+
         Ok(Self {
             keypair,
             auth_header,
             public_key,
+            signer,
         })
     }
 }
@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_trader_proto::api;
+
+use crate::common::policy::{lookup_table_addresses, SigningPolicy};
+use crate::common::signer::TransactionSigner;
+use crate::common::signing::{sign_all_for_submit, SignedTransaction, SubmitParams};
+use crate::common::transaction::TraderTransaction;
+use crate::provider::utils::IntoTransactionMessage;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackagedTransaction {
+    pub content: String,
+    pub is_cleanup: bool,
+}
+
+// Everything needed to sign a set of server-built transactions away from the client
+// that built them, and to submit the result later through any transport.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnsignedTransactionPackage {
+    pub transactions: Vec<PackagedTransaction>,
+    pub address_lookup_tables: HashMap<String, Vec<String>>,
+    pub signers: Vec<String>,
+    pub submit_opts: SubmitParams,
+    pub use_bundle: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedTransactionPackage {
+    pub transactions: Vec<SignedTransaction>,
+    pub submit_opts: SubmitParams,
+    pub use_bundle: bool,
+}
+
+impl UnsignedTransactionPackage {
+    pub fn new<T: IntoTransactionMessage>(
+        txs: Vec<T>,
+        submit_opts: SubmitParams,
+        use_bundle: bool,
+    ) -> Self {
+        let transactions = txs
+            .into_iter()
+            .map(|tx| {
+                let tx_message = tx.into_transaction_message();
+                PackagedTransaction {
+                    content: tx_message.content,
+                    is_cleanup: tx_message.is_cleanup,
+                }
+            })
+            .collect();
+
+        Self {
+            transactions,
+            address_lookup_tables: HashMap::new(),
+            signers: Vec::new(),
+            submit_opts,
+            use_bundle,
+        }
+    }
+
+    pub fn with_address_lookup_tables(
+        mut self,
+        address_lookup_tables: &HashMap<String, api::PublicKeys>,
+    ) -> Self {
+        self.address_lookup_tables = lookup_table_addresses(address_lookup_tables);
+        self
+    }
+
+    pub fn with_signers(mut self, signers: &[Pubkey]) -> Self {
+        self.signers = signers.iter().map(|pk| pk.to_string()).collect();
+        self
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| anyhow!("Failed to serialize transaction package: {}", e))
+    }
+
+    pub fn from_json(data: &str) -> Result<Self> {
+        serde_json::from_str(data)
+            .map_err(|e| anyhow!("Failed to parse transaction package: {}", e))
+    }
+}
+
+impl SignedTransactionPackage {
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| anyhow!("Failed to serialize signed transaction package: {}", e))
+    }
+
+    pub fn from_json(data: &str) -> Result<Self> {
+        serde_json::from_str(data)
+            .map_err(|e| anyhow!("Failed to parse signed transaction package: {}", e))
+    }
+}
+
+impl IntoTransactionMessage for PackagedTransaction {
+    fn into_transaction_message(self) -> api::TransactionMessage {
+        api::TransactionMessage {
+            content: self.content,
+            is_cleanup: self.is_cleanup,
+        }
+    }
+}
+
+// Signs a package without any network access. `blockhash` is required unless the
+// package's submit options carry a durable nonce. `policy` is checked like in
+// `sign_and_submit`, resolving lookup table accounts from the package.
+pub async fn sign_package(
+    package: &UnsignedTransactionPackage,
+    signer: &dyn TransactionSigner,
+    blockhash: Option<&str>,
+    policy: Option<&SigningPolicy>,
+) -> Result<SignedTransactionPackage> {
+    let signer_key = signer.public_key().to_string();
+    if !package.signers.is_empty() && !package.signers.contains(&signer_key) {
+        return Err(anyhow!(
+            "Signer {} is not one of the package's intended signers",
+            signer_key
+        ));
+    }
+
+    let blockhash = match (&package.submit_opts.durable_nonce, blockhash) {
+        (Some(nonce), _) => nonce.nonce_hash.to_string(),
+        (None, Some(blockhash)) => blockhash.to_string(),
+        (None, None) => {
+            return Err(anyhow!(
+                "A blockhash is required to sign a package without a durable nonce"
+            ))
+        }
+    };
+
+    if let Some(policy) = policy {
+        for tx in &package.transactions {
+            policy.check_with_lookup_tables(
+                &TraderTransaction::decode(tx.clone())?,
+                &signer.public_key(),
+                Some(&package.address_lookup_tables),
+            )?;
+        }
+    }
+
+    let transactions = sign_all_for_submit(
+        &package.transactions,
+        signer,
+        &blockhash,
+        &package.submit_opts,
+    )
+    .await?;
+
+    Ok(SignedTransactionPackage {
+        transactions,
+        submit_opts: package.submit_opts.clone(),
+        use_bundle: package.use_bundle,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use solana_sdk::{
+        hash::Hash,
+        message::{v0, Message, VersionedMessage},
+        signature::{Keypair, Signature},
+        signer::Signer,
+        system_instruction,
+        transaction::{Transaction, VersionedTransaction},
+    };
+
+    #[tokio::test]
+    async fn test_package_round_trip() -> Result<()> {
+        let keypair = Keypair::new();
+        let payer = keypair.pubkey();
+        let message = Message::new(
+            &[system_instruction::transfer(
+                &payer,
+                &Pubkey::new_unique(),
+                1,
+            )],
+            Some(&payer),
+        );
+        let tx = api::TransactionMessage {
+            content: STANDARD.encode(bincode::serialize(&Transaction::new_unsigned(message))?),
+            is_cleanup: false,
+        };
+
+        let package = UnsignedTransactionPackage::new(vec![tx], SubmitParams::default(), false)
+            .with_signers(&[payer]);
+        let package = UnsignedTransactionPackage::from_json(&package.to_json()?)?;
+
+        let blockhash = Hash::new_unique();
+        let signed = sign_package(&package, &keypair, Some(&blockhash.to_string()), None).await?;
+        let signed = SignedTransactionPackage::from_json(&signed.to_json()?)?;
+
+        let tx: VersionedTransaction =
+            bincode::deserialize(&STANDARD.decode(&signed.transactions[0].content)?)?;
+        assert_eq!(*tx.message.recent_blockhash(), blockhash);
+        assert!(tx.verify_with_results().iter().all(|ok| *ok));
+
+        assert!(sign_package(
+            &package,
+            &Keypair::new(),
+            Some(&blockhash.to_string()),
+            None
+        )
+        .await
+        .is_err());
+
+        let policy = SigningPolicy {
+            max_sol_transfer_lamports: Some(0),
+            ..Default::default()
+        };
+        assert!(sign_package(
+            &package,
+            &keypair,
+            Some(&blockhash.to_string()),
+            Some(&policy)
+        )
+        .await
+        .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_v0_package_with_two_signers() -> Result<()> {
+        let payer = Keypair::new();
+        let authority = Keypair::new();
+        let instruction = system_instruction::transfer(&authority.pubkey(), &payer.pubkey(), 1);
+        let message =
+            v0::Message::try_compile(&payer.pubkey(), &[instruction], &[], Hash::default())?;
+        let tx = VersionedTransaction {
+            signatures: vec![Signature::default(); 2],
+            message: VersionedMessage::V0(message),
+        };
+        let tx = api::TransactionMessage {
+            content: STANDARD.encode(bincode::serialize(&tx)?),
+            is_cleanup: false,
+        };
+
+        let blockhash = Hash::new_unique().to_string();
+        let package = UnsignedTransactionPackage::new(vec![tx], SubmitParams::default(), false);
+        let partially = sign_package(&package, &authority, Some(&blockhash), None).await?;
+
+        // the second signer adds its signature next to the first instead of replacing it
+        let txs = partially
+            .transactions
+            .into_iter()
+            .map(|tx| api::TransactionMessage {
+                content: tx.content,
+                is_cleanup: tx.is_cleanup,
+            })
+            .collect();
+        let package = UnsignedTransactionPackage::new(txs, SubmitParams::default(), false);
+        let signed = sign_package(&package, &payer, Some(&blockhash), None).await?;
+
+        let tx: VersionedTransaction =
+            bincode::deserialize(&STANDARD.decode(&signed.transactions[0].content)?)?;
+        assert_eq!(tx.signatures.len(), 2);
+        assert_eq!(tx.message.static_account_keys()[0], payer.pubkey());
+        assert!(tx.verify_with_results().iter().all(|ok| *ok));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,563 @@
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use solana_sdk::pubkey::Pubkey;
+use solana_trader_proto::api;
+use thiserror::Error;
+
+use crate::common::constants::{
+    BLOXROUTE_TIP_ADDRESSES, COMPUTE_BUDGET_PROGRAM, JITO_TIP_ADDRESSES, SYSTEM_PROGRAM,
+    TOKEN_2022_PROGRAM, TOKEN_PROGRAM,
+};
+use crate::common::transaction::TraderTransaction;
+use crate::provider::utils::IntoTransactionMessage;
+
+const SYSTEM_CREATE_ACCOUNT: u32 = 0;
+const SYSTEM_TRANSFER: u32 = 2;
+const SYSTEM_CREATE_ACCOUNT_WITH_SEED: u32 = 3;
+const SYSTEM_TRANSFER_WITH_SEED: u32 = 11;
+const COMPUTE_UNIT_PRICE: u8 = 3;
+const TOKEN_SET_AUTHORITY: u8 = 6;
+const TOKEN_CLOSE_ACCOUNT: u8 = 9;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PolicyViolation {
+    #[error("program {0} is not allowed")]
+    ProgramNotAllowed(String),
+    #[error("SOL transfers of {total} lamports exceed the limit of {limit}")]
+    SolTransferExceeded { total: u64, limit: u64 },
+    #[error("tips of {total} lamports exceed the limit of {limit}")]
+    TipExceeded { total: u64, limit: u64 },
+    #[error("compute unit price {price} exceeds the limit of {limit}")]
+    ComputeUnitPriceExceeded { price: u64, limit: u64 },
+    #[error("wallet account {0} is writable but not expected to be")]
+    UnexpectedWritable(String),
+    #[error("{0} writable accounts are loaded from lookup tables that were not provided")]
+    UnresolvedWritableLookups(usize),
+    #[error("SetAuthority on {account} assigns authority to foreign {new_authority}")]
+    ForeignSetAuthority {
+        account: String,
+        new_authority: String,
+    },
+    #[error("CloseAccount on {account} sends its balance to foreign {destination}")]
+    ForeignCloseAccount {
+        account: String,
+        destination: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("transaction rejected by signing policy: {}", join_violations(.violations))]
+pub struct PolicyError {
+    pub violations: Vec<PolicyViolation>,
+}
+
+fn join_violations(violations: &[PolicyViolation]) -> String {
+    violations
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+// Checked against server-built transactions before they are modified or signed.
+// Accounts loaded through address lookup tables are only resolved when the tables are supplied;
+// otherwise checks that need an address treat them as foreign, and `expected_writable` rejects
+// any writable lookup entry. The clients' `sign_and_submit` has no tables to supply, so use
+// `sign_and_submit_with_lookup_tables` or a transaction package for transactions that use them.
+#[derive(Debug, Clone)]
+pub struct SigningPolicy {
+    // System and ComputeBudget are always allowed
+    pub allowed_programs: Option<HashSet<Pubkey>>,
+    pub max_sol_transfer_lamports: Option<u64>,
+    pub max_tip_lamports: Option<u64>,
+    pub max_compute_unit_price: Option<u64>,
+    // accounts owned by the signing wallet, e.g. its token accounts; the signer is implied
+    pub wallet_accounts: HashSet<Pubkey>,
+    // wallet accounts a transaction may write to; the signer is implied, None skips the check
+    pub expected_writable: Option<HashSet<Pubkey>>,
+    pub forbid_foreign_authority_changes: bool,
+}
+
+impl Default for SigningPolicy {
+    fn default() -> Self {
+        Self {
+            allowed_programs: None,
+            max_sol_transfer_lamports: None,
+            max_tip_lamports: None,
+            max_compute_unit_price: None,
+            wallet_accounts: HashSet::new(),
+            expected_writable: None,
+            forbid_foreign_authority_changes: true,
+        }
+    }
+}
+
+// Converts the lookup tables of a swap instructions response to the form the policy checks take.
+pub fn lookup_table_addresses(
+    address_lookup_tables: &HashMap<String, api::PublicKeys>,
+) -> HashMap<String, Vec<String>> {
+    address_lookup_tables
+        .iter()
+        .map(|(key, accounts)| (key.clone(), accounts.pks.clone()))
+        .collect()
+}
+
+impl SigningPolicy {
+    pub fn check_transactions<T: IntoTransactionMessage + Clone>(
+        &self,
+        txs: &[T],
+        signer: &Pubkey,
+        lookup_tables: Option<&HashMap<String, Vec<String>>>,
+    ) -> anyhow::Result<()> {
+        for tx in txs {
+            self.check_with_lookup_tables(
+                &TraderTransaction::decode(tx.clone())?,
+                signer,
+                lookup_tables,
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn check(&self, tx: &TraderTransaction, signer: &Pubkey) -> Result<(), PolicyError> {
+        self.check_with_lookup_tables(tx, signer, None)
+    }
+
+    // `lookup_tables` maps each table address to its addresses, as in a transaction package.
+    pub fn check_with_lookup_tables(
+        &self,
+        tx: &TraderTransaction,
+        signer: &Pubkey,
+        lookup_tables: Option<&HashMap<String, Vec<String>>>,
+    ) -> Result<(), PolicyError> {
+        let loaded = lookup_tables.and_then(|tables| resolve_lookups(tx, tables));
+        let keys: Vec<Pubkey> = tx
+            .static_account_keys()
+            .iter()
+            .copied()
+            .chain(loaded.iter().flatten().copied())
+            .collect();
+        let key_at = |index: u8| keys.get(index as usize);
+        let is_ours = |key: Option<&Pubkey>| {
+            key.is_some_and(|key| key == signer || self.wallet_accounts.contains(key))
+        };
+        let display = |index: u8| match key_at(index) {
+            Some(key) => key.to_string(),
+            None => format!("<lookup table account #{}>", index),
+        };
+
+        let mut violations = Vec::new();
+        let mut sol_out = 0u64;
+        let mut tips = 0u64;
+
+        for ix in tx.instructions() {
+            let Some(program_id) = key_at(ix.program_id_index) else {
+                violations.push(PolicyViolation::ProgramNotAllowed(display(
+                    ix.program_id_index,
+                )));
+                continue;
+            };
+
+            if let Some(allowed) = &self.allowed_programs {
+                let implicit = [SYSTEM_PROGRAM, COMPUTE_BUDGET_PROGRAM]
+                    .iter()
+                    .any(|p| Pubkey::from_str(p).is_ok_and(|p| p == *program_id));
+                if !implicit && !allowed.contains(program_id) {
+                    violations.push(PolicyViolation::ProgramNotAllowed(program_id.to_string()));
+                }
+            }
+
+            let account = |i: usize| ix.accounts.get(i).copied();
+
+            match program_id.to_string().as_str() {
+                SYSTEM_PROGRAM => {
+                    let Some((funders, to, lamports)) = system_outflow(&ix.data, &ix.accounts)
+                    else {
+                        continue;
+                    };
+                    if !funders.iter().any(|from| is_ours(key_at(*from))) {
+                        continue;
+                    }
+
+                    sol_out = sol_out.saturating_add(lamports);
+                    let to = key_at(to).map(|key| key.to_string()).unwrap_or_default();
+                    if BLOXROUTE_TIP_ADDRESSES.contains(&to.as_str())
+                        || JITO_TIP_ADDRESSES.contains(&to.as_str())
+                    {
+                        tips = tips.saturating_add(lamports);
+                    }
+                }
+                COMPUTE_BUDGET_PROGRAM => {
+                    if ix.data.first() != Some(&COMPUTE_UNIT_PRICE) {
+                        continue;
+                    }
+                    if let (Some(price), Some(limit)) =
+                        (read_u64(&ix.data, 1), self.max_compute_unit_price)
+                    {
+                        if price > limit {
+                            violations
+                                .push(PolicyViolation::ComputeUnitPriceExceeded { price, limit });
+                        }
+                    }
+                }
+                TOKEN_PROGRAM | TOKEN_2022_PROGRAM if self.forbid_foreign_authority_changes => {
+                    match ix.data.first() {
+                        // data: tag, authority type, COption<Pubkey>
+                        Some(&TOKEN_SET_AUTHORITY) => {
+                            let new_authority = match ix.data.get(2) {
+                                Some(1) => ix
+                                    .data
+                                    .get(3..35)
+                                    .and_then(|bytes| Pubkey::try_from(bytes).ok()),
+                                _ => None,
+                            };
+                            let ours = new_authority.is_some_and(|key| is_ours(Some(&key)));
+                            if !ours {
+                                violations.push(PolicyViolation::ForeignSetAuthority {
+                                    account: account(0).map(display).unwrap_or_default(),
+                                    new_authority: new_authority
+                                        .map(|key| key.to_string())
+                                        .unwrap_or_else(|| "none".to_string()),
+                                });
+                            }
+                        }
+                        // accounts: account, destination, owner
+                        Some(&TOKEN_CLOSE_ACCOUNT) => {
+                            let destination = account(1);
+                            if !destination.is_some_and(|d| is_ours(key_at(d))) {
+                                violations.push(PolicyViolation::ForeignCloseAccount {
+                                    account: account(0).map(display).unwrap_or_default(),
+                                    destination: destination.map(display).unwrap_or_default(),
+                                });
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(limit) = self.max_sol_transfer_lamports {
+            if sol_out > limit {
+                violations.push(PolicyViolation::SolTransferExceeded {
+                    total: sol_out,
+                    limit,
+                });
+            }
+        }
+        if let Some(limit) = self.max_tip_lamports {
+            if tips > limit {
+                violations.push(PolicyViolation::TipExceeded { total: tips, limit });
+            }
+        }
+
+        if let Some(expected) = &self.expected_writable {
+            for (i, key) in keys.iter().enumerate() {
+                if key != signer
+                    && self.wallet_accounts.contains(key)
+                    && tx.is_writable(i)
+                    && !expected.contains(key)
+                {
+                    violations.push(PolicyViolation::UnexpectedWritable(key.to_string()));
+                }
+            }
+
+            // a wallet account could hide behind any of these, so fail closed
+            if loaded.is_none() {
+                let unresolved: usize = tx
+                    .address_table_lookups()
+                    .iter()
+                    .map(|lookup| lookup.writable_indexes.len())
+                    .sum();
+                if unresolved > 0 {
+                    violations.push(PolicyViolation::UnresolvedWritableLookups(unresolved));
+                }
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(PolicyError { violations })
+        }
+    }
+}
+
+// Loaded writable addresses followed by loaded readonly ones, or None unless every table is
+// supplied and every index is in range.
+fn resolve_lookups(
+    tx: &TraderTransaction,
+    lookup_tables: &HashMap<String, Vec<String>>,
+) -> Option<Vec<Pubkey>> {
+    let lookups = tx.address_table_lookups();
+    let load = |writable: bool| -> Option<Vec<Pubkey>> {
+        let mut keys = Vec::new();
+        for lookup in lookups {
+            let table = lookup_tables.get(&lookup.account_key.to_string())?;
+            let indexes = if writable {
+                &lookup.writable_indexes
+            } else {
+                &lookup.readonly_indexes
+            };
+            for index in indexes {
+                keys.push(Pubkey::from_str(table.get(*index as usize)?).ok()?);
+            }
+        }
+        Some(keys)
+    };
+
+    let mut keys = load(true)?;
+    keys.extend(load(false)?);
+    Some(keys)
+}
+
+// Lamports a System instruction takes from its funding account: the accounts that can fund it
+// (the source, or for TransferWithSeed also its base), the recipient and the amount.
+fn system_outflow(data: &[u8], accounts: &[u8]) -> Option<(Vec<u8>, u8, u64)> {
+    let account = |i: usize| accounts.get(i).copied();
+    match read_u32(data, 0)? {
+        SYSTEM_CREATE_ACCOUNT | SYSTEM_TRANSFER => {
+            Some((vec![account(0)?], account(1)?, read_u64(data, 4)?))
+        }
+        // data: tag, base, u64-prefixed seed, lamports, space, owner
+        SYSTEM_CREATE_ACCOUNT_WITH_SEED => {
+            let seed_len = usize::try_from(read_u64(data, 36)?).ok()?;
+            let lamports = read_u64(data, 44usize.checked_add(seed_len)?)?;
+            Some((vec![account(0)?], account(1)?, lamports))
+        }
+        // accounts: from, base, to
+        SYSTEM_TRANSFER_WITH_SEED => Some((
+            vec![account(0)?, account(1)?],
+            account(2)?,
+            read_u64(data, 4)?,
+        )),
+        _ => None,
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    data.get(offset..offset + 8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{
+        compute_budget::ComputeBudgetInstruction,
+        hash::Hash,
+        instruction::{AccountMeta, Instruction},
+        message::{v0, Message, VersionedMessage},
+        system_instruction,
+        transaction::{Transaction, VersionedTransaction},
+    };
+
+    fn legacy(instructions: &[Instruction], payer: &Pubkey) -> TraderTransaction {
+        TraderTransaction::Legacy {
+            transaction: Transaction::new_unsigned(Message::new(instructions, Some(payer))),
+            is_cleanup: false,
+        }
+    }
+
+    #[test]
+    fn test_policy_allows_expected_transaction() {
+        let payer = Pubkey::new_unique();
+        let tip = Pubkey::from_str(BLOXROUTE_TIP_ADDRESSES[0]).unwrap();
+        let tx = legacy(
+            &[
+                ComputeBudgetInstruction::set_compute_unit_price(1_000),
+                system_instruction::transfer(&payer, &tip, 1_000_000),
+            ],
+            &payer,
+        );
+
+        let policy = SigningPolicy {
+            max_tip_lamports: Some(1_000_000),
+            max_compute_unit_price: Some(10_000),
+            ..Default::default()
+        };
+        assert_eq!(policy.check(&tx, &payer), Ok(()));
+    }
+
+    #[test]
+    fn test_policy_reports_every_violation() {
+        let payer = Pubkey::new_unique();
+        let token_account = Pubkey::new_unique();
+        let attacker = Pubkey::new_unique();
+        let unknown_program = Pubkey::new_unique();
+
+        let close = Instruction {
+            program_id: Pubkey::from_str(TOKEN_PROGRAM).unwrap(),
+            accounts: vec![
+                AccountMeta::new(token_account, false),
+                AccountMeta::new(attacker, false),
+                AccountMeta::new_readonly(payer, true),
+            ],
+            data: vec![TOKEN_CLOSE_ACCOUNT],
+        };
+        let tx = legacy(
+            &[
+                ComputeBudgetInstruction::set_compute_unit_price(50_000),
+                system_instruction::transfer(&payer, &attacker, 5_000_000_000),
+                Instruction::new_with_bytes(unknown_program, &[], vec![]),
+                close,
+            ],
+            &payer,
+        );
+
+        let policy = SigningPolicy {
+            allowed_programs: Some(HashSet::from([Pubkey::from_str(TOKEN_PROGRAM).unwrap()])),
+            max_sol_transfer_lamports: Some(1_000_000_000),
+            max_compute_unit_price: Some(10_000),
+            wallet_accounts: HashSet::from([token_account]),
+            expected_writable: Some(HashSet::new()),
+            ..Default::default()
+        };
+
+        let err = policy.check(&tx, &payer).unwrap_err();
+        assert!(err.violations.contains(&PolicyViolation::ProgramNotAllowed(
+            unknown_program.to_string()
+        )));
+        assert!(err
+            .violations
+            .contains(&PolicyViolation::SolTransferExceeded {
+                total: 5_000_000_000,
+                limit: 1_000_000_000,
+            }));
+        assert!(err
+            .violations
+            .contains(&PolicyViolation::ComputeUnitPriceExceeded {
+                price: 50_000,
+                limit: 10_000,
+            }));
+        assert!(err
+            .violations
+            .contains(&PolicyViolation::UnexpectedWritable(
+                token_account.to_string()
+            )));
+        assert!(err
+            .violations
+            .contains(&PolicyViolation::ForeignCloseAccount {
+                account: token_account.to_string(),
+                destination: attacker.to_string(),
+            }));
+    }
+
+    #[test]
+    fn test_policy_counts_account_creation_and_seeded_transfers() {
+        let payer = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let from = Pubkey::create_with_seed(&payer, "seed", &owner).unwrap();
+        let tx = legacy(
+            &[
+                system_instruction::create_account(&payer, &Pubkey::new_unique(), 1_000, 0, &owner),
+                system_instruction::create_account_with_seed(
+                    &payer,
+                    &Pubkey::new_unique(),
+                    &payer,
+                    "seed",
+                    2_000,
+                    0,
+                    &owner,
+                ),
+                system_instruction::transfer_with_seed(
+                    &from,
+                    &payer,
+                    "seed".to_string(),
+                    &owner,
+                    &Pubkey::new_unique(),
+                    4_000,
+                ),
+            ],
+            &payer,
+        );
+
+        let policy = SigningPolicy {
+            max_sol_transfer_lamports: Some(5_000),
+            ..Default::default()
+        };
+        let err = policy.check(&tx, &payer).unwrap_err();
+        assert_eq!(
+            err.violations,
+            vec![PolicyViolation::SolTransferExceeded {
+                total: 7_000,
+                limit: 5_000,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_policy_resolves_writable_lookups() {
+        let payer = Pubkey::new_unique();
+        let token_account = Pubkey::new_unique();
+        let table = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+
+        // the wallet's token account is only reachable through the lookup table
+        let message = v0::Message {
+            header: solana_sdk::message::MessageHeader {
+                num_required_signatures: 1,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 1,
+            },
+            account_keys: vec![payer, program],
+            recent_blockhash: Hash::default(),
+            instructions: vec![
+                solana_sdk::instruction::CompiledInstruction::new_from_raw_parts(
+                    1,
+                    vec![],
+                    vec![0, 2],
+                ),
+            ],
+            address_table_lookups: vec![v0::MessageAddressTableLookup {
+                account_key: table,
+                writable_indexes: vec![0],
+                readonly_indexes: vec![],
+            }],
+        };
+        let tx = TraderTransaction::Versioned {
+            transaction: VersionedTransaction {
+                signatures: vec![Default::default()],
+                message: VersionedMessage::V0(message),
+            },
+            is_cleanup: false,
+        };
+
+        let policy = SigningPolicy {
+            wallet_accounts: HashSet::from([token_account]),
+            expected_writable: Some(HashSet::new()),
+            ..Default::default()
+        };
+        assert_eq!(
+            policy.check(&tx, &payer).unwrap_err().violations,
+            vec![PolicyViolation::UnresolvedWritableLookups(1)]
+        );
+
+        let tables = HashMap::from([(table.to_string(), vec![token_account.to_string()])]);
+        assert_eq!(
+            policy
+                .check_with_lookup_tables(&tx, &payer, Some(&tables))
+                .unwrap_err()
+                .violations,
+            vec![PolicyViolation::UnexpectedWritable(
+                token_account.to_string()
+            )]
+        );
+
+        let policy = SigningPolicy {
+            expected_writable: Some(HashSet::from([token_account])),
+            ..policy
+        };
+        assert_eq!(
+            policy.check_with_lookup_tables(&tx, &payer, Some(&tables)),
+            Ok(())
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use std::fmt::Debug;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+use solana_sdk::{
+    message::VersionedMessage,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::VersionedTransaction,
+};
+
+#[async_trait]
+pub trait TransactionSigner: Debug + Send + Sync {
+    fn public_key(&self) -> Pubkey;
+
+    async fn sign(&self, message: &[u8]) -> Result<Signature>;
+}
+
+#[async_trait]
+impl TransactionSigner for Keypair {
+    fn public_key(&self) -> Pubkey {
+        self.pubkey()
+    }
+
+    async fn sign(&self, message: &[u8]) -> Result<Signature> {
+        Ok(self.sign_message(message))
+    }
+}
+
+// Talks to a signing service over HTTP:
+//   GET  {endpoint}/pubkey -> {"pubkey": "<base58>"}
+//   POST {endpoint}/sign   {"pubkey": "<base58>", "message": "<base64>"} -> {"signature": "<base58>"}
+#[derive(Debug, Clone)]
+pub struct RemoteSigner {
+    client: Client,
+    endpoint: String,
+    public_key: Pubkey,
+}
+
+#[derive(Deserialize)]
+struct PubkeyResponse {
+    pubkey: String,
+}
+
+#[derive(Deserialize)]
+struct SignResponse {
+    signature: String,
+}
+
+impl RemoteSigner {
+    pub fn new(endpoint: &str, public_key: Pubkey) -> Self {
+        Self {
+            client: Client::new(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            public_key,
+        }
+    }
+
+    pub async fn connect(endpoint: &str) -> Result<Self> {
+        let endpoint = endpoint.trim_end_matches('/').to_string();
+        let client = Client::new();
+
+        let response = client
+            .get(format!("{}/pubkey", endpoint))
+            .send()
+            .await
+            .map_err(|e| anyhow!("Remote signer request failed: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "Remote signer returned status {}",
+                response.status()
+            ));
+        }
+
+        let res: PubkeyResponse = response.json().await?;
+        let public_key = res
+            .pubkey
+            .parse()
+            .map_err(|e| anyhow!("Remote signer returned invalid pubkey: {}", e))?;
+
+        Ok(Self {
+            client,
+            endpoint,
+            public_key,
+        })
+    }
+}
+
+#[async_trait]
+impl TransactionSigner for RemoteSigner {
+    fn public_key(&self) -> Pubkey {
+        self.public_key
+    }
+
+    async fn sign(&self, message: &[u8]) -> Result<Signature> {
+        let response = self
+            .client
+            .post(format!("{}/sign", self.endpoint))
+            .json(&json!({
+                "pubkey": self.public_key.to_string(),
+                "message": STANDARD.encode(message),
+            }))
+            .send()
+            .await
+            .map_err(|e| anyhow!("Remote signer request failed: {}", e))?;
+
+        if !response.status().is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Failed to read error response".into());
+            return Err(anyhow!("Remote signer rejected request: {}", error_text));
+        }
+
+        let res: SignResponse = response.json().await?;
+        let signature: Signature = res
+            .signature
+            .parse()
+            .map_err(|e| anyhow!("Remote signer returned invalid signature: {}", e))?;
+
+        if !signature.verify(self.public_key.as_ref(), message) {
+            return Err(anyhow!(
+                "Remote signer returned a signature that does not verify"
+            ));
+        }
+
+        Ok(signature)
+    }
+}
+
+pub async fn new_versioned_transaction(
+    message: VersionedMessage,
+    signer: &dyn TransactionSigner,
+) -> Result<VersionedTransaction> {
+    let num_signers = message.header().num_required_signatures as usize;
+    let position = message.static_account_keys()[..num_signers]
+        .iter()
+        .position(|key| *key == signer.public_key())
+        .ok_or_else(|| anyhow!("Signer {} is not a required signer", signer.public_key()))?;
+
+    let mut tx = VersionedTransaction {
+        signatures: vec![Signature::default(); num_signers],
+        message,
+    };
+    tx.signatures[position] = signer.sign(&tx.message.serialize()).await?;
+
+    Ok(tx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{hash::Hash, message::Message, system_instruction};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    // Minimal HTTP/1.1 stand-in for a signing daemon, signing with a local keypair.
+    async fn spawn_stand_in_signer(keypair: Keypair) -> Result<String> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 4096];
+                let (head, body) = loop {
+                    let n = socket.read(&mut chunk).await.unwrap();
+                    buf.extend_from_slice(&chunk[..n]);
+                    let text = String::from_utf8_lossy(&buf).to_string();
+                    if let Some(split) = text.find("\r\n\r\n") {
+                        let head = text[..split].to_string();
+                        let length = head
+                            .lines()
+                            .find_map(|l| {
+                                l.to_ascii_lowercase()
+                                    .strip_prefix("content-length:")
+                                    .map(|v| v.trim().parse::<usize>().unwrap())
+                            })
+                            .unwrap_or(0);
+                        if buf.len() >= split + 4 + length {
+                            break (head, text[split + 4..split + 4 + length].to_string());
+                        }
+                    }
+                };
+
+                let payload = if head.starts_with("GET /pubkey") {
+                    json!({ "pubkey": keypair.pubkey().to_string() })
+                } else {
+                    let req: serde_json::Value = serde_json::from_str(&body).unwrap();
+                    let message = STANDARD.decode(req["message"].as_str().unwrap()).unwrap();
+                    json!({ "signature": keypair.sign_message(&message).to_string() })
+                }
+                .to_string();
+
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    payload.len(),
+                    payload
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        Ok(format!("http://{}", addr))
+    }
+
+    #[tokio::test]
+    async fn test_remote_signer_against_stand_in() -> Result<()> {
+        let keypair = Keypair::new();
+        let expected = keypair.pubkey();
+        let endpoint = spawn_stand_in_signer(keypair).await?;
+
+        let signer = RemoteSigner::connect(&endpoint).await?;
+        assert_eq!(signer.public_key(), expected);
+
+        let to = Pubkey::new_unique();
+        let message = VersionedMessage::Legacy(Message::new_with_blockhash(
+            &[system_instruction::transfer(&expected, &to, 1)],
+            Some(&expected),
+            &Hash::new_unique(),
+        ));
+
+        let tx = new_versioned_transaction(message, &signer).await?;
+        assert!(tx.verify_with_results().iter().all(|ok| *ok));
+
+        Ok(())
+    }
+}
@@ -1,26 +1,34 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use base64::{engine::general_purpose::STANDARD, Engine};
-use bincode::{deserialize, serialize};
-use serde::Serialize;
+use bincode::deserialize;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use solana_sdk::{
-    message::VersionedMessage,
-    signature::{Keypair, Signature},
-    signer::Signer,
+    hash::Hash,
+    instruction::{AccountMeta, CompiledInstruction},
+    message::{MessageHeader, VersionedMessage},
+    nonce::state::{State, Versions},
+    pubkey::Pubkey,
+    signature::Signature,
+    system_instruction,
     transaction::{Transaction, VersionedTransaction},
 };
 use solana_trader_proto::api;
 
+use crate::common::signer::TransactionSigner;
+use crate::common::transaction::TraderTransaction;
 use crate::provider::utils::IntoTransactionMessage;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubmitParams {
     pub skip_pre_flight: bool,
     pub front_running_protection: bool,
     pub use_staked_rpcs: bool,
     pub fast_best_effort: bool,
+    #[serde(with = "submit_strategy_name")]
     pub submit_strategy: api::SubmitStrategy,
     pub allow_back_run: Option<bool>,
     pub revenue_address: Option<String>,
+    pub durable_nonce: Option<DurableNonce>,
 }
 
 impl Default for SubmitParams {
@@ -33,66 +41,650 @@ impl Default for SubmitParams {
             submit_strategy: api::SubmitStrategy::PSubmitAll,
             allow_back_run: None,
             revenue_address: None,
+            durable_nonce: None,
         }
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DurableNonce {
+    pub nonce_account: Pubkey,
+    pub nonce_authority: Pubkey,
+    pub nonce_hash: Hash,
+}
+
+impl DurableNonce {
+    pub fn new(nonce_account: Pubkey, nonce_authority: Pubkey, nonce_hash: Hash) -> Self {
+        Self {
+            nonce_account,
+            nonce_authority,
+            nonce_hash,
+        }
+    }
+
+    // `data` is the raw account data of an initialized nonce account.
+    pub fn from_account_data(nonce_account: Pubkey, data: &[u8]) -> Result<Self> {
+        let versions: Versions = deserialize(data)?;
+        match versions.state() {
+            State::Initialized(state) => Ok(Self {
+                nonce_account,
+                nonce_authority: state.authority,
+                nonce_hash: state.blockhash(),
+            }),
+            State::Uninitialized => Err(anyhow!(
+                "Nonce account {} is not initialized",
+                nonce_account
+            )),
+        }
+    }
+}
+
+// Serialized by name, so stored submit options do not depend on enum discriminants.
+mod submit_strategy_name {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        strategy: &api::SubmitStrategy,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(strategy.as_str_name())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<api::SubmitStrategy, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        api::SubmitStrategy::from_str_name(&name)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown submit strategy {}", name)))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SignedTransaction {
     pub content: String,
     pub is_cleanup: bool,
 }
 
+impl SignedTransaction {
+    pub fn signature(&self) -> Result<String> {
+        let tx: VersionedTransaction = deserialize(&STANDARD.decode(&self.content)?)?;
+        tx.signatures
+            .first()
+            .map(|signature| signature.to_string())
+            .ok_or_else(|| anyhow!("Signed transaction has no signatures"))
+    }
+}
+
+// What `sign_and_submit` would have sent, without contacting the submit endpoint.
+// `endpoint` is the HTTP path or the RPC method name, depending on the transport.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DryRunResult {
+    pub signed_transactions: Vec<SignedTransaction>,
+    pub signatures: Vec<String>,
+    pub endpoint: String,
+    pub request_body: serde_json::Value,
+}
+
+impl DryRunResult {
+    pub fn new(
+        signed_transactions: Vec<SignedTransaction>,
+        endpoint: &str,
+        request_body: serde_json::Value,
+    ) -> Result<Self> {
+        let signatures = signed_transactions
+            .iter()
+            .map(|tx| tx.signature())
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            signed_transactions,
+            signatures,
+            endpoint: endpoint.to_string(),
+            request_body,
+        })
+    }
+}
+
 pub async fn sign_transaction<T>(
     tx: &T,
-    keypair: &Keypair,
+    signer: &dyn TransactionSigner,
     blockhash: String,
 ) -> Result<SignedTransaction>
 where
     T: IntoTransactionMessage + Clone,
 {
-    let tx_message = tx.clone().into_transaction_message();
-    let rawbytes = STANDARD.decode(&tx_message.content)?;
-    let parsed_hash = blockhash.parse()?;
+    sign_with_lifetime(tx, signer, Lifetime::Blockhash(blockhash.parse()?)).await
+}
+
+pub async fn sign_transaction_with_nonce<T>(
+    tx: &T,
+    signer: &dyn TransactionSigner,
+    nonce: &DurableNonce,
+) -> Result<SignedTransaction>
+where
+    T: IntoTransactionMessage + Clone,
+{
+    sign_with_lifetime(tx, signer, Lifetime::Nonce(nonce)).await
+}
+
+// Signs with the durable nonce from `submit_opts` when present, otherwise with `blockhash`.
+pub async fn sign_for_submit<T>(
+    tx: &T,
+    signer: &dyn TransactionSigner,
+    blockhash: &str,
+    submit_opts: &SubmitParams,
+) -> Result<SignedTransaction>
+where
+    T: IntoTransactionMessage + Clone,
+{
+    match &submit_opts.durable_nonce {
+        Some(nonce) => sign_transaction_with_nonce(tx, signer, nonce).await,
+        None => sign_transaction(tx, signer, blockhash.to_string()).await,
+    }
+}
 
-    let signed_data = match deserialize(&rawbytes) {
-        Ok(versioned_tx) => sign_versioned_transaction(versioned_tx, keypair, parsed_hash)?,
-        Err(_) => sign_legacy_transaction(&rawbytes, keypair, parsed_hash)?,
+// Signs a whole batch for submission. A durable nonce can only be advanced once, so every
+// transaction after the first would be rejected; such batches are refused before signing.
+pub async fn sign_all_for_submit<T>(
+    txs: &[T],
+    signer: &dyn TransactionSigner,
+    blockhash: &str,
+    submit_opts: &SubmitParams,
+) -> Result<Vec<SignedTransaction>>
+where
+    T: IntoTransactionMessage + Clone,
+{
+    if submit_opts.durable_nonce.is_some() && txs.len() > 1 {
+        return Err(anyhow!(
+            "A durable nonce can only sign one transaction, got {}",
+            txs.len()
+        ));
+    }
+
+    let mut signed_txs = Vec::with_capacity(txs.len());
+    for tx in txs {
+        signed_txs.push(sign_for_submit(tx, signer, blockhash, submit_opts).await?);
+    }
+    Ok(signed_txs)
+}
+
+#[derive(Clone, Copy)]
+enum Lifetime<'a> {
+    Blockhash(Hash),
+    Nonce(&'a DurableNonce),
+}
+
+async fn sign_with_lifetime<T>(
+    tx: &T,
+    signer: &dyn TransactionSigner,
+    lifetime: Lifetime<'_>,
+) -> Result<SignedTransaction>
+where
+    T: IntoTransactionMessage + Clone,
+{
+    let signed_tx = match TraderTransaction::decode(tx.clone())? {
+        TraderTransaction::Legacy {
+            transaction,
+            is_cleanup,
+        } => TraderTransaction::Legacy {
+            transaction: sign_legacy_transaction(transaction, signer, lifetime).await?,
+            is_cleanup,
+        },
+        TraderTransaction::Versioned {
+            transaction,
+            is_cleanup,
+        } => TraderTransaction::Versioned {
+            transaction: sign_versioned_transaction(transaction, signer, lifetime).await?,
+            is_cleanup,
+        },
     };
 
     Ok(SignedTransaction {
-        content: STANDARD.encode(signed_data),
-        is_cleanup: tx_message.is_cleanup,
+        content: signed_tx.encode()?,
+        is_cleanup: signed_tx.is_cleanup(),
     })
 }
 
-fn sign_versioned_transaction(
+async fn sign_versioned_transaction(
     mut tx: VersionedTransaction,
-    keypair: &Keypair,
-    blockhash: solana_sdk::hash::Hash,
-) -> Result<Vec<u8>> {
-    match &mut tx.message {
-        VersionedMessage::Legacy(message) => {
-            message.recent_blockhash = blockhash;
-        }
+    signer: &dyn TransactionSigner,
+    lifetime: Lifetime<'_>,
+) -> Result<VersionedTransaction> {
+    let changed = match &mut tx.message {
+        VersionedMessage::Legacy(message) => apply_lifetime(
+            &mut message.header,
+            &mut message.account_keys,
+            &mut message.instructions,
+            &mut message.recent_blockhash,
+            0,
+            lifetime,
+        )?,
         VersionedMessage::V0(message) => {
-            message.recent_blockhash = blockhash;
+            let lookup_accounts = message
+                .address_table_lookups
+                .iter()
+                .map(|lookup| lookup.writable_indexes.len() + lookup.readonly_indexes.len())
+                .sum();
+            apply_lifetime(
+                &mut message.header,
+                &mut message.account_keys,
+                &mut message.instructions,
+                &mut message.recent_blockhash,
+                lookup_accounts,
+                lifetime,
+            )?
         }
+    };
+    // same rules as the legacy path: keep co-signers' signatures unless the message changed
+    if changed {
+        tx.signatures
+            .iter_mut()
+            .for_each(|s| *s = Signature::default());
     }
 
-    tx.signatures = vec![Signature::default()];
+    let num_signers = tx.message.header().num_required_signatures as usize;
+    let position = tx
+        .message
+        .static_account_keys()
+        .iter()
+        .take(num_signers)
+        .position(|key| *key == signer.public_key())
+        .ok_or_else(|| anyhow!("Signer {} is not a required signer", signer.public_key()))?;
+
+    if tx.signatures.len() != num_signers {
+        tx.signatures.resize(num_signers, Signature::default());
+    }
     let message_data = tx.message.serialize();
-    tx.signatures[0] = keypair.sign_message(&message_data);
+    tx.signatures[position] = signer.sign(&message_data).await?;
+
+    Ok(tx)
+}
+
+async fn sign_legacy_transaction(
+    mut tx: Transaction,
+    signer: &dyn TransactionSigner,
+    lifetime: Lifetime<'_>,
+) -> Result<Transaction> {
+    // mirrors Transaction::try_partial_sign: a changed message invalidates existing signatures
+    let changed = apply_lifetime(
+        &mut tx.message.header,
+        &mut tx.message.account_keys,
+        &mut tx.message.instructions,
+        &mut tx.message.recent_blockhash,
+        0,
+        lifetime,
+    )?;
+    if changed {
+        tx.signatures
+            .iter_mut()
+            .for_each(|s| *s = Signature::default());
+    }
+
+    let position = tx.get_signing_keypair_positions(&[signer.public_key()])?[0]
+        .ok_or_else(|| anyhow!("Signer {} is not a required signer", signer.public_key()))?;
+
+    let num_signers = tx.message.header.num_required_signatures as usize;
+    if tx.signatures.len() != num_signers {
+        tx.signatures.resize(num_signers, Signature::default());
+    }
+    tx.signatures[position] = signer.sign(&tx.message_data()).await?;
+
+    Ok(tx)
+}
+
+// Returns whether the message was modified. `lookup_accounts` is how many accounts a v0
+// message loads from lookup tables; their indexes follow the static keys.
+fn apply_lifetime(
+    header: &mut MessageHeader,
+    account_keys: &mut Vec<Pubkey>,
+    instructions: &mut Vec<CompiledInstruction>,
+    recent_blockhash: &mut Hash,
+    lookup_accounts: usize,
+    lifetime: Lifetime<'_>,
+) -> Result<bool> {
+    let (hash, inserted) = match lifetime {
+        Lifetime::Blockhash(hash) => (hash, false),
+        Lifetime::Nonce(nonce) => (
+            nonce.nonce_hash,
+            insert_advance_nonce(header, account_keys, instructions, lookup_accounts, nonce)?,
+        ),
+    };
+
+    let changed = inserted || *recent_blockhash != hash;
+    *recent_blockhash = hash;
+    Ok(changed)
+}
+
+fn insert_advance_nonce(
+    header: &mut MessageHeader,
+    account_keys: &mut Vec<Pubkey>,
+    instructions: &mut Vec<CompiledInstruction>,
+    lookup_accounts: usize,
+    nonce: &DurableNonce,
+) -> Result<bool> {
+    let advance =
+        system_instruction::advance_nonce_account(&nonce.nonce_account, &nonce.nonce_authority);
+
+    let already_advanced = instructions.first().is_some_and(|ix| {
+        account_keys.get(ix.program_id_index as usize) == Some(&advance.program_id)
+            && ix.data == advance.data
+            && ix
+                .accounts
+                .first()
+                .and_then(|index| account_keys.get(*index as usize))
+                == Some(&nonce.nonce_account)
+    });
+    if already_advanced {
+        return Ok(false);
+    }
+
+    let metas: Vec<AccountMeta> =
+        std::iter::once(AccountMeta::new_readonly(advance.program_id, false))
+            .chain(advance.accounts.iter().cloned())
+            .collect();
+    for meta in &metas {
+        ensure_account_key(header, account_keys, instructions, lookup_accounts, meta)?;
+    }
+
+    // every key is present now, so the indexes below are stable
+    let mut indexes = Vec::with_capacity(metas.len());
+    for meta in &metas {
+        indexes.push(ensure_account_key(
+            header,
+            account_keys,
+            instructions,
+            lookup_accounts,
+            meta,
+        )?);
+    }
+    let program_id_index = indexes.remove(0);
+
+    instructions.insert(
+        0,
+        CompiledInstruction {
+            program_id_index,
+            accounts: indexes,
+            data: advance.data,
+        },
+    );
+
+    Ok(true)
+}
+
+// Finds or inserts `meta.pubkey` in the static keys, keeping the header's
+// signer/writable ordering and remapping every compiled index past the insertion point,
+// including the lookup table indexes that follow the static keys.
+fn ensure_account_key(
+    header: &mut MessageHeader,
+    account_keys: &mut Vec<Pubkey>,
+    instructions: &mut [CompiledInstruction],
+    lookup_accounts: usize,
+    meta: &AccountMeta,
+) -> Result<u8> {
+    let num_signers = header.num_required_signatures as usize;
+
+    if let Some(index) = account_keys.iter().position(|key| *key == meta.pubkey) {
+        let is_signer = index < num_signers;
+        let is_writable = if is_signer {
+            index < num_signers - header.num_readonly_signed_accounts as usize
+        } else {
+            index < account_keys.len() - header.num_readonly_unsigned_accounts as usize
+        };
+
+        if meta.is_signer && !is_signer {
+            return Err(anyhow!(
+                "Account {} must be a signer of the transaction",
+                meta.pubkey
+            ));
+        }
+        if meta.is_writable && !is_writable {
+            return Err(anyhow!(
+                "Account {} must be writable in the transaction",
+                meta.pubkey
+            ));
+        }
+        return Ok(index as u8);
+    }
+
+    if meta.is_signer {
+        return Err(anyhow!(
+            "Account {} must already be a signer of the transaction",
+            meta.pubkey
+        ));
+    }
+    if account_keys.len() + lookup_accounts >= u8::MAX as usize {
+        return Err(anyhow!("Transaction has too many accounts"));
+    }
+
+    let position = if meta.is_writable {
+        account_keys.len() - header.num_readonly_unsigned_accounts as usize
+    } else {
+        header.num_readonly_unsigned_accounts += 1;
+        account_keys.len()
+    };
+
+    let shift = |index: &mut u8| -> Result<()> {
+        if *index as usize >= position {
+            *index = index
+                .checked_add(1)
+                .ok_or_else(|| anyhow!("Transaction has too many accounts"))?;
+        }
+        Ok(())
+    };
+    for ix in instructions.iter_mut() {
+        shift(&mut ix.program_id_index)?;
+        for account in ix.accounts.iter_mut() {
+            shift(account)?;
+        }
+    }
+    account_keys.insert(position, meta.pubkey);
 
-    Ok(serialize(&tx)?)
+    Ok(position as u8)
 }
 
-fn sign_legacy_transaction(
-    rawbytes: &[u8],
-    keypair: &Keypair,
-    blockhash: solana_sdk::hash::Hash,
-) -> Result<Vec<u8>> {
-    let mut tx: Transaction = deserialize(rawbytes)?;
-    tx.try_partial_sign(&[keypair], blockhash)?;
-    Ok(serialize(&tx)?)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bincode::serialize;
+    use solana_sdk::{
+        message::{v0, Message},
+        signature::Keypair,
+        signer::Signer,
+        system_program,
+    };
+    use solana_trader_proto::api::TransactionMessage;
+
+    fn encode(tx: &impl serde::Serialize) -> TransactionMessage {
+        TransactionMessage {
+            content: STANDARD.encode(serialize(tx).unwrap()),
+            is_cleanup: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_nonce_inserted_into_legacy_transaction() -> Result<()> {
+        let keypair = Keypair::new();
+        let payer = keypair.pubkey();
+        let to = Pubkey::new_unique();
+        let message = Message::new(
+            &[system_instruction::transfer(&payer, &to, 1)],
+            Some(&payer),
+        );
+        let nonce = DurableNonce::new(Pubkey::new_unique(), payer, Hash::new_unique());
+
+        let signed = sign_transaction_with_nonce(
+            &encode(&Transaction::new_unsigned(message)),
+            &keypair,
+            &nonce,
+        )
+        .await?;
+
+        let tx: VersionedTransaction = deserialize(&STANDARD.decode(signed.content)?)?;
+        let keys = tx.message.static_account_keys();
+        let first = &tx.message.instructions()[0];
+
+        assert_eq!(*tx.message.recent_blockhash(), nonce.nonce_hash);
+        assert_eq!(keys[first.program_id_index as usize], system_program::id());
+        assert_eq!(keys[first.accounts[0] as usize], nonce.nonce_account);
+        assert_eq!(
+            keys[first.accounts[1] as usize],
+            system_instruction::advance_nonce_account(&nonce.nonce_account, &payer).accounts[1]
+                .pubkey
+        );
+        assert_eq!(keys[first.accounts[2] as usize], payer);
+        assert!(tx.verify_with_results().iter().all(|ok| *ok));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_nonce_insertion_shifts_lookup_indexes() -> Result<()> {
+        let keypair = Keypair::new();
+        let payer = keypair.pubkey();
+        let program = Pubkey::new_unique();
+
+        // index 2 points into the lookup table, which follows the static keys
+        let message = v0::Message {
+            header: MessageHeader {
+                num_required_signatures: 1,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 1,
+            },
+            account_keys: vec![payer, program],
+            recent_blockhash: Hash::default(),
+            instructions: vec![CompiledInstruction::new_from_raw_parts(
+                1,
+                vec![],
+                vec![0, 2],
+            )],
+            address_table_lookups: vec![v0::MessageAddressTableLookup {
+                account_key: Pubkey::new_unique(),
+                writable_indexes: vec![0],
+                readonly_indexes: vec![],
+            }],
+        };
+        let tx = VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::V0(message),
+        };
+        let nonce = DurableNonce::new(Pubkey::new_unique(), payer, Hash::new_unique());
+
+        let signed = sign_transaction_with_nonce(&encode(&tx), &keypair, &nonce).await?;
+        let tx: VersionedTransaction = deserialize(&STANDARD.decode(signed.content)?)?;
+        let keys = tx.message.static_account_keys();
+        let swap = &tx.message.instructions()[1];
+
+        assert_eq!(keys.len(), 5);
+        assert_eq!(keys[swap.program_id_index as usize], program);
+        assert_eq!(swap.accounts[0], 0);
+        assert_eq!(swap.accounts[1] as usize, keys.len());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_nonce_insertion_counts_lookup_accounts() -> Result<()> {
+        let keypair = Keypair::new();
+        let payer = keypair.pubkey();
+        let program = Pubkey::new_unique();
+
+        // two static keys plus 253 looked-up accounts: the last index is already 254
+        let message = v0::Message {
+            header: MessageHeader {
+                num_required_signatures: 1,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 1,
+            },
+            account_keys: vec![payer, program],
+            recent_blockhash: Hash::default(),
+            instructions: vec![CompiledInstruction::new_from_raw_parts(
+                1,
+                vec![],
+                vec![0, 254],
+            )],
+            address_table_lookups: vec![v0::MessageAddressTableLookup {
+                account_key: Pubkey::new_unique(),
+                writable_indexes: (0..200).collect(),
+                readonly_indexes: (200..253).collect(),
+            }],
+        };
+        let tx = VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::V0(message),
+        };
+        let nonce = DurableNonce::new(Pubkey::new_unique(), payer, Hash::new_unique());
+
+        let result = sign_transaction_with_nonce(&encode(&tx), &keypair, &nonce).await;
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_nonce_batches_and_foreign_advance() -> Result<()> {
+        let keypair = Keypair::new();
+        let payer = keypair.pubkey();
+        let other = DurableNonce::new(Pubkey::new_unique(), payer, Hash::new_unique());
+        let nonce = DurableNonce::new(Pubkey::new_unique(), payer, Hash::new_unique());
+
+        // advancing some other nonce account does not count as advancing ours
+        let message = Message::new(
+            &[system_instruction::advance_nonce_account(
+                &other.nonce_account,
+                &payer,
+            )],
+            Some(&payer),
+        );
+        let tx = encode(&Transaction::new_unsigned(message));
+
+        let signed = sign_transaction_with_nonce(&tx, &keypair, &nonce).await?;
+        let signed: VersionedTransaction = deserialize(&STANDARD.decode(signed.content)?)?;
+        let keys = signed.message.static_account_keys();
+        let instructions = signed.message.instructions();
+        assert_eq!(instructions.len(), 2);
+        assert_eq!(
+            keys[instructions[0].accounts[0] as usize],
+            nonce.nonce_account
+        );
+
+        let submit_opts = SubmitParams {
+            durable_nonce: Some(nonce),
+            ..Default::default()
+        };
+        let error = sign_all_for_submit(&[tx.clone(), tx], &keypair, "", &submit_opts)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("only sign one transaction"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_result_reports_signatures() -> Result<()> {
+        let keypair = Keypair::new();
+        let payer = keypair.pubkey();
+        let message = Message::new(
+            &[system_instruction::transfer(
+                &payer,
+                &Pubkey::new_unique(),
+                1,
+            )],
+            Some(&payer),
+        );
+
+        let signed = sign_transaction(
+            &encode(&Transaction::new_unsigned(message)),
+            &keypair,
+            Hash::new_unique().to_string(),
+        )
+        .await?;
+
+        let tx: VersionedTransaction = deserialize(&STANDARD.decode(&signed.content)?)?;
+        let result = DryRunResult::new(vec![signed], "/api/v2/submit", serde_json::json!({}))?;
+
+        assert_eq!(result.signatures, vec![tx.signatures[0].to_string()]);
+        assert_eq!(result.endpoint, "/api/v2/submit");
+
+        Ok(())
+    }
 }
@@ -0,0 +1,284 @@
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bincode::{deserialize, serialize};
+use solana_sdk::{
+    hash::Hash,
+    instruction::CompiledInstruction,
+    message::{v0::MessageAddressTableLookup, MessageHeader, VersionedMessage},
+    pubkey::Pubkey,
+    transaction::{Transaction, VersionedTransaction},
+};
+use solana_trader_proto::api::TransactionMessage;
+
+use crate::provider::utils::IntoTransactionMessage;
+
+const SIGNATURE_LENGTH: usize = 64;
+const VERSION_PREFIX_MASK: u8 = 0x80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionFormat {
+    Legacy,
+    V0,
+}
+
+// Wire layout: shortvec signature count, 64 bytes per signature, then the message.
+// A versioned message starts with a byte that has the high bit set; a legacy message
+// starts with `num_required_signatures`, which is always below 128.
+pub fn detect_format(bytes: &[u8]) -> Result<TransactionFormat> {
+    let (num_signatures, len) = decode_shortvec_len(bytes)?;
+    let offset = len + num_signatures * SIGNATURE_LENGTH;
+
+    let prefix = *bytes.get(offset).ok_or_else(|| {
+        anyhow!(
+            "Transaction truncated: expected message after {} signatures",
+            num_signatures
+        )
+    })?;
+
+    if prefix & VERSION_PREFIX_MASK == 0 {
+        return Ok(TransactionFormat::Legacy);
+    }
+
+    match prefix & !VERSION_PREFIX_MASK {
+        0 => Ok(TransactionFormat::V0),
+        version => Err(anyhow!("Unsupported transaction version {}", version)),
+    }
+}
+
+fn decode_shortvec_len(bytes: &[u8]) -> Result<(usize, usize)> {
+    let mut value = 0usize;
+    for (i, byte) in bytes.iter().take(3).enumerate() {
+        value |= ((byte & 0x7f) as usize) << (i * 7);
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1));
+        }
+    }
+
+    Err(anyhow!("Invalid signature count encoding"))
+}
+
+#[derive(Debug, Clone)]
+pub enum TraderTransaction {
+    Legacy {
+        transaction: Transaction,
+        is_cleanup: bool,
+    },
+    Versioned {
+        transaction: VersionedTransaction,
+        is_cleanup: bool,
+    },
+}
+
+impl TraderTransaction {
+    pub fn decode<T: IntoTransactionMessage>(tx: T) -> Result<Self> {
+        let tx_message = tx.into_transaction_message();
+        let rawbytes = STANDARD
+            .decode(&tx_message.content)
+            .map_err(|e| anyhow!("Failed to decode transaction base64: {}", e))?;
+
+        Self::from_bytes(&rawbytes, tx_message.is_cleanup)
+    }
+
+    pub fn from_bytes(rawbytes: &[u8], is_cleanup: bool) -> Result<Self> {
+        match detect_format(rawbytes)? {
+            TransactionFormat::Legacy => {
+                let transaction = deserialize(rawbytes)
+                    .map_err(|e| anyhow!("Failed to decode legacy transaction: {}", e))?;
+                Ok(Self::Legacy {
+                    transaction,
+                    is_cleanup,
+                })
+            }
+            TransactionFormat::V0 => {
+                let transaction = deserialize(rawbytes)
+                    .map_err(|e| anyhow!("Failed to decode versioned transaction: {}", e))?;
+                Ok(Self::Versioned {
+                    transaction,
+                    is_cleanup,
+                })
+            }
+        }
+    }
+
+    pub fn encode(&self) -> Result<String> {
+        let rawbytes = match self {
+            Self::Legacy { transaction, .. } => serialize(transaction)?,
+            Self::Versioned { transaction, .. } => serialize(transaction)?,
+        };
+
+        Ok(STANDARD.encode(rawbytes))
+    }
+
+    pub fn format(&self) -> TransactionFormat {
+        match self {
+            Self::Legacy { .. } => TransactionFormat::Legacy,
+            Self::Versioned { .. } => TransactionFormat::V0,
+        }
+    }
+
+    pub fn is_cleanup(&self) -> bool {
+        match self {
+            Self::Legacy { is_cleanup, .. } | Self::Versioned { is_cleanup, .. } => *is_cleanup,
+        }
+    }
+
+    pub fn header(&self) -> &MessageHeader {
+        match self {
+            Self::Legacy { transaction, .. } => &transaction.message.header,
+            Self::Versioned { transaction, .. } => transaction.message.header(),
+        }
+    }
+
+    pub fn static_account_keys(&self) -> &[Pubkey] {
+        match self {
+            Self::Legacy { transaction, .. } => &transaction.message.account_keys,
+            Self::Versioned { transaction, .. } => transaction.message.static_account_keys(),
+        }
+    }
+
+    pub fn signers(&self) -> &[Pubkey] {
+        let keys = self.static_account_keys();
+        let num_signers = (self.header().num_required_signatures as usize).min(keys.len());
+        &keys[..num_signers]
+    }
+
+    pub fn requires_signer(&self, pubkey: &Pubkey) -> bool {
+        self.signers().contains(pubkey)
+    }
+
+    // `index` is in the instruction index space: static keys, then loaded writable
+    // addresses, then loaded readonly addresses.
+    pub fn is_writable(&self, index: usize) -> bool {
+        let header = self.header();
+        let num_static = self.static_account_keys().len();
+        let num_signers = header.num_required_signatures as usize;
+        let readonly_signed = header.num_readonly_signed_accounts as usize;
+        let readonly_unsigned = header.num_readonly_unsigned_accounts as usize;
+
+        if index < num_signers {
+            return index < num_signers.saturating_sub(readonly_signed);
+        }
+        if index < num_static {
+            return index < num_static.saturating_sub(readonly_unsigned);
+        }
+
+        let num_loaded_writable: usize = self
+            .address_table_lookups()
+            .iter()
+            .map(|lookup| lookup.writable_indexes.len())
+            .sum();
+        index < num_static + num_loaded_writable
+    }
+
+    pub fn instructions(&self) -> &[CompiledInstruction] {
+        match self {
+            Self::Legacy { transaction, .. } => &transaction.message.instructions,
+            Self::Versioned { transaction, .. } => transaction.message.instructions(),
+        }
+    }
+
+    pub fn address_table_lookups(&self) -> &[MessageAddressTableLookup] {
+        match self {
+            Self::Legacy { .. } => &[],
+            Self::Versioned { transaction, .. } => {
+                transaction.message.address_table_lookups().unwrap_or(&[])
+            }
+        }
+    }
+
+    pub fn recent_blockhash(&self) -> &Hash {
+        match self {
+            Self::Legacy { transaction, .. } => &transaction.message.recent_blockhash,
+            Self::Versioned { transaction, .. } => transaction.message.recent_blockhash(),
+        }
+    }
+
+    pub fn into_versioned(self) -> VersionedTransaction {
+        match self {
+            Self::Legacy { transaction, .. } => VersionedTransaction {
+                signatures: transaction.signatures,
+                message: VersionedMessage::Legacy(transaction.message),
+            },
+            Self::Versioned { transaction, .. } => transaction,
+        }
+    }
+}
+
+impl TryFrom<TransactionMessage> for TraderTransaction {
+    type Error = anyhow::Error;
+
+    fn try_from(tx: TransactionMessage) -> Result<Self> {
+        Self::decode(tx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{
+        message::{v0, Message},
+        signature::Keypair,
+        signer::Signer,
+        system_instruction,
+    };
+
+    fn transfer_instruction(payer: &Pubkey) -> solana_sdk::instruction::Instruction {
+        system_instruction::transfer(payer, &Pubkey::new_unique(), 1)
+    }
+
+    #[test]
+    fn test_detects_legacy_transaction() -> Result<()> {
+        let payer = Keypair::new().pubkey();
+        let message = Message::new(&[transfer_instruction(&payer)], Some(&payer));
+        let rawbytes = serialize(&Transaction::new_unsigned(message))?;
+
+        assert_eq!(detect_format(&rawbytes)?, TransactionFormat::Legacy);
+
+        let tx = TraderTransaction::decode(TransactionMessage {
+            content: STANDARD.encode(&rawbytes),
+            is_cleanup: true,
+        })?;
+        assert!(tx.is_cleanup());
+        assert_eq!(tx.signers(), &[payer]);
+        assert_eq!(tx.instructions().len(), 1);
+        assert!(tx.address_table_lookups().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_detects_v0_transaction() -> Result<()> {
+        let payer = Keypair::new().pubkey();
+        let message = v0::Message::try_compile(
+            &payer,
+            &[transfer_instruction(&payer)],
+            &[],
+            Hash::new_unique(),
+        )?;
+        let tx = VersionedTransaction {
+            signatures: vec![Default::default()],
+            message: VersionedMessage::V0(message),
+        };
+        let rawbytes = serialize(&tx)?;
+
+        assert_eq!(detect_format(&rawbytes)?, TransactionFormat::V0);
+
+        let decoded = TraderTransaction::from_bytes(&rawbytes, false)?;
+        assert_eq!(decoded.format(), TransactionFormat::V0);
+        assert!(decoded.requires_signer(&payer));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_malformed_transactions() {
+        assert!(detect_format(&[]).is_err());
+        // one signature but no message bytes
+        assert!(detect_format(&[1u8; 65]).is_err());
+        // unsupported version 1
+        let mut rawbytes = vec![1u8];
+        rawbytes.extend_from_slice(&[0u8; 64]);
+        rawbytes.push(0x81);
+        assert!(detect_format(&rawbytes).is_err());
+    }
+}
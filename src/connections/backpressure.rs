@@ -0,0 +1,315 @@
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use anyhow::Result;
+use futures_util::stream::{self, BoxStream};
+use futures_util::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use tokio::sync::Notify;
+
+use super::frame::Notification;
+
+const DEFAULT_CAPACITY: usize = 1000;
+
+// What the read loop does with a notification when its subscription's buffer is full. `Block`
+// is lossless but pauses the whole connection until the consumer catches up; the other policies
+// never wait and count what they discard.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backpressure {
+    Block {
+        capacity: usize,
+    },
+    DropNewest {
+        capacity: usize,
+    },
+    DropOldest {
+        capacity: usize,
+    },
+    // Keeps only the latest pending notification per key, read from the result at `key`, e.g.
+    // `&["orderbook", "market"]` or `&["reserves", "poolAddress"]`. Notifications without the key
+    // are queued as with `DropOldest`, which also applies once `capacity` keys are pending.
+    ConflateLatest {
+        key: &'static [&'static str],
+        capacity: usize,
+    },
+}
+
+impl Default for Backpressure {
+    fn default() -> Self {
+        Backpressure::Block {
+            capacity: DEFAULT_CAPACITY,
+        }
+    }
+}
+
+impl Backpressure {
+    fn capacity(&self) -> usize {
+        match *self {
+            Backpressure::Block { capacity }
+            | Backpressure::DropNewest { capacity }
+            | Backpressure::DropOldest { capacity }
+            | Backpressure::ConflateLatest { capacity, .. } => capacity.max(1),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DropCounters {
+    dropped: Arc<AtomicU64>,
+    conflated: Arc<AtomicU64>,
+}
+
+impl DropCounters {
+    // Notifications discarded because the buffer was full.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    // Notifications replaced by a newer one with the same conflation key.
+    pub fn conflated(&self) -> u64 {
+        self.conflated.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct SubscriptionQueue {
+    policy: Backpressure,
+    items: Mutex<VecDeque<(Option<String>, Notification)>>,
+    readable: Notify,
+    writable: Notify,
+    closed: AtomicBool,
+    counters: DropCounters,
+}
+
+impl SubscriptionQueue {
+    pub(crate) fn new(policy: Backpressure) -> Arc<Self> {
+        Arc::new(Self {
+            policy,
+            items: Mutex::new(VecDeque::new()),
+            readable: Notify::new(),
+            writable: Notify::new(),
+            closed: AtomicBool::new(false),
+            counters: DropCounters::default(),
+        })
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+
+    // Ends the subscription from either side: the consumer stops waiting once the buffer is
+    // drained and a blocked producer gives up.
+    pub(crate) fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.readable.notify_one();
+        self.writable.notify_one();
+    }
+
+    pub(crate) async fn push(&self, notification: Notification) {
+        let key = match self.policy {
+            Backpressure::ConflateLatest { key, .. } => notification.field(key).map(str::to_string),
+            _ => None,
+        };
+
+        loop {
+            if self.is_closed() {
+                return;
+            }
+
+            {
+                let mut items = self.items.lock().unwrap();
+
+                if let Some(key) = &key {
+                    if let Some(pending) = items.iter_mut().find(|(k, _)| k.as_ref() == Some(key)) {
+                        pending.1 = notification;
+                        self.counters.conflated.fetch_add(1, Ordering::Relaxed);
+                        return;
+                    }
+                }
+
+                if items.len() < self.policy.capacity() {
+                    items.push_back((key, notification));
+                    self.readable.notify_one();
+                    return;
+                }
+
+                match self.policy {
+                    Backpressure::Block { .. } => {}
+                    Backpressure::DropNewest { .. } => {
+                        self.counters.dropped.fetch_add(1, Ordering::Relaxed);
+                        return;
+                    }
+                    Backpressure::DropOldest { .. } | Backpressure::ConflateLatest { .. } => {
+                        items.pop_front();
+                        items.push_back((key, notification));
+                        self.counters.dropped.fetch_add(1, Ordering::Relaxed);
+                        self.readable.notify_one();
+                        return;
+                    }
+                }
+            }
+
+            self.writable.notified().await;
+        }
+    }
+
+    async fn pop(&self) -> Option<Notification> {
+        loop {
+            let item = self.items.lock().unwrap().pop_front();
+            if let Some((_, notification)) = item {
+                self.writable.notify_one();
+                return Some(notification);
+            }
+            if self.is_closed() {
+                return None;
+            }
+
+            self.readable.notified().await;
+        }
+    }
+}
+
+// The consumer side of a WebSocket subscription. Dropping it closes the subscription's buffer
+// and runs the `on_drop` hook, which the connection uses to unsubscribe.
+pub struct SubscriptionStream<T> {
+    queue: Arc<SubscriptionQueue>,
+    notifications: BoxStream<'static, Notification>,
+    on_drop: Option<Box<dyn FnOnce() + Send>>,
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<T> SubscriptionStream<T> {
+    pub(crate) fn new(queue: Arc<SubscriptionQueue>) -> Self {
+        let notifications = stream::unfold(queue.clone(), |queue| async move {
+            let notification = queue.pop().await?;
+            Some((notification, queue))
+        })
+        .boxed();
+
+        Self {
+            queue,
+            notifications,
+            on_drop: None,
+            _item: PhantomData,
+        }
+    }
+
+    pub(crate) fn on_drop(mut self, hook: impl FnOnce() + Send + 'static) -> Self {
+        self.on_drop = Some(Box::new(hook));
+        self
+    }
+
+    pub fn backpressure(&self) -> Backpressure {
+        self.queue.policy
+    }
+
+    pub fn counters(&self) -> DropCounters {
+        self.queue.counters.clone()
+    }
+}
+
+impl<T: DeserializeOwned> Stream for SubscriptionStream<T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<T>>> {
+        self.notifications
+            .poll_next_unpin(cx)
+            .map(|notification| notification.map(|n| n.decode()))
+    }
+}
+
+impl<T> Drop for SubscriptionStream<T> {
+    fn drop(&mut self) {
+        self.queue.close();
+        if let Some(hook) = self.on_drop.take() {
+            hook();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connections::frame::Frame;
+    use std::time::Duration;
+    use tokio::time::sleep;
+
+    fn notification(market: &str, slot: u64) -> Notification {
+        let text = format!(
+            r#"{{"method":"subscribe","params":{{"subscription":"s","result":{{"slot":{},"orderbook":{{"market":"{}"}}}}}}}}"#,
+            slot, market
+        );
+        match Frame::parse(text) {
+            Some(Frame::Notification(notification)) => notification,
+            _ => panic!("expected a notification"),
+        }
+    }
+
+    async fn slots(queue: &SubscriptionQueue) -> Vec<u64> {
+        let mut slots = vec![];
+        while !queue.items.lock().unwrap().is_empty() {
+            let notification = queue.pop().await.unwrap();
+            slots.push(notification.field(&["slot"]).unwrap().parse().unwrap());
+        }
+        slots
+    }
+
+    #[tokio::test]
+    async fn test_drop_policies() {
+        let queue = SubscriptionQueue::new(Backpressure::DropNewest { capacity: 2 });
+        for slot in 1..=4 {
+            queue.push(notification("SOL/USDC", slot)).await;
+        }
+        assert_eq!(slots(&queue).await, vec![1, 2]);
+        assert_eq!(queue.counters.dropped(), 2);
+
+        let queue = SubscriptionQueue::new(Backpressure::DropOldest { capacity: 2 });
+        for slot in 1..=4 {
+            queue.push(notification("SOL/USDC", slot)).await;
+        }
+        assert_eq!(slots(&queue).await, vec![3, 4]);
+        assert_eq!(queue.counters.dropped(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_conflate_latest() {
+        let queue = SubscriptionQueue::new(Backpressure::ConflateLatest {
+            key: &["orderbook", "market"],
+            capacity: 2,
+        });
+        queue.push(notification("SOL/USDC", 1)).await;
+        queue.push(notification("BONK/SOL", 2)).await;
+        queue.push(notification("SOL/USDC", 3)).await;
+        assert_eq!(queue.counters.conflated(), 1);
+
+        queue.push(notification("JUP/SOL", 4)).await;
+        assert_eq!(queue.counters.dropped(), 1);
+        assert_eq!(slots(&queue).await, vec![2, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_block_waits_for_consumer() {
+        let queue = SubscriptionQueue::new(Backpressure::Block { capacity: 1 });
+        queue.push(notification("SOL/USDC", 1)).await;
+
+        let producer = {
+            let queue = queue.clone();
+            tokio::spawn(async move { queue.push(notification("SOL/USDC", 2)).await })
+        };
+        sleep(Duration::from_millis(50)).await;
+        assert!(!producer.is_finished());
+
+        let mut stream = SubscriptionStream::<serde_json::Value>::new(queue.clone());
+        assert_eq!(stream.next().await.unwrap().unwrap()["slot"], 1);
+        assert_eq!(stream.next().await.unwrap().unwrap()["slot"], 2);
+        producer.await.unwrap();
+        assert_eq!(stream.counters().dropped(), 0);
+
+        drop(stream);
+        assert!(queue.is_closed());
+    }
+}
@@ -0,0 +1,190 @@
+use std::fmt;
+use std::ops::Range;
+
+use anyhow::{anyhow, Result};
+use serde::de::{DeserializeOwned, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, Visitor};
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use serde_json::Value;
+
+use crate::provider::enums;
+
+// A JSON-RPC text frame, routed by a borrowed parse that only reads `id` and
+// `params.subscription`. The payload is left in the frame text and decoded exactly once, straight
+// into the caller's type, by whoever receives the frame.
+pub enum Frame {
+    Response { id: u64, text: String },
+    Notification(Notification),
+}
+
+#[derive(Debug)]
+pub struct Notification {
+    text: String,
+    subscription: Range<usize>,
+    result: Range<usize>,
+}
+
+#[derive(Deserialize)]
+struct Envelope<'a> {
+    id: Option<u64>,
+    #[serde(borrow)]
+    params: Option<Params<'a>>,
+}
+
+// Subscription ids are plain UUIDs, so they can always be borrowed from the frame.
+#[derive(Deserialize)]
+struct Params<'a> {
+    subscription: &'a str,
+    #[serde(borrow)]
+    result: &'a RawValue,
+}
+
+#[derive(Deserialize)]
+struct Response<T> {
+    result: Option<T>,
+    error: Option<Value>,
+}
+
+impl Frame {
+    pub fn parse(text: String) -> Option<Self> {
+        let envelope: Envelope = serde_json::from_str(&text).ok()?;
+
+        match (envelope.id, envelope.params) {
+            (Some(id), _) => Some(Frame::Response { id, text }),
+            (None, Some(params)) => {
+                let subscription = span(&text, params.subscription);
+                let result = span(&text, params.result.get());
+                Some(Frame::Notification(Notification {
+                    text,
+                    subscription,
+                    result,
+                }))
+            }
+            (None, None) => None,
+        }
+    }
+}
+
+impl Notification {
+    pub fn subscription(&self) -> &str {
+        &self.text[self.subscription.clone()]
+    }
+
+    pub fn result(&self) -> &str {
+        &self.text[self.result.clone()]
+    }
+
+    pub fn decode<T: DeserializeOwned>(&self) -> Result<T> {
+        enums::from_str(self.result()).map_err(|e| anyhow!("Failed to parse stream value: {}", e))
+    }
+
+    // Raw JSON of the result field at `path`, found without decoding the rest of the result.
+    pub fn field(&self, path: &[&str]) -> Option<&str> {
+        path.iter().try_fold(self.result(), |json, name| {
+            let mut deserializer = serde_json::Deserializer::from_str(json);
+            Field(name)
+                .deserialize(&mut deserializer)
+                .ok()
+                .flatten()
+                .map(RawValue::get)
+        })
+    }
+}
+
+// Decodes the `result` of a JSON-RPC response frame, or surfaces its `error`.
+pub fn decode_response<T: DeserializeOwned>(text: &str) -> Result<T> {
+    let response: Response<T> =
+        enums::from_str(text).map_err(|e| anyhow!("Failed to parse response: {}", e))?;
+
+    if let Some(error) = response.error {
+        return Err(anyhow!("RPC error: {}", error));
+    }
+
+    response
+        .result
+        .ok_or_else(|| anyhow!("Missing result field in response"))
+}
+
+struct Field<'k>(&'k str);
+
+impl<'de> DeserializeSeed<'de> for Field<'_> {
+    type Value = Option<&'de RawValue>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Field<'_> {
+    type Value = Option<&'de RawValue>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut found = None;
+        while let Some(key) = map.next_key::<&str>()? {
+            if found.is_none() && key == self.0 {
+                found = Some(map.next_value()?);
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(found)
+    }
+}
+
+// Byte range of `part` within `text`; `part` must have been borrowed from `text`.
+fn span(text: &str, part: &str) -> Range<usize> {
+    let start = part.as_ptr() as usize - text.as_ptr() as usize;
+    start..start + part.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_trader_proto::api;
+
+    const TRADES: &str = r#"{"jsonrpc":"2.0","method":"subscribe","params":{"subscription":"5d3b1d6c-2b8e-4c4e-9d2a-1f0c3a7e9b10","result":{"slot":"301234567","trades":{"trades":[{"side":"S_ASK","size":1.5,"fillPrice":142.25,"orderId":"42","isMaker":false,"address":"addr","feeOrRebate":0.01,"orderPrice":142.2}]},"timestamp":"2024-11-18T10:15:30Z"}}}"#;
+
+    #[test]
+    fn test_parse_notification() -> Result<()> {
+        let Some(Frame::Notification(notification)) = Frame::parse(TRADES.to_string()) else {
+            panic!("expected a notification");
+        };
+
+        assert_eq!(
+            notification.subscription(),
+            "5d3b1d6c-2b8e-4c4e-9d2a-1f0c3a7e9b10"
+        );
+        assert!(notification.result().starts_with(r#"{"slot":"301234567""#));
+        assert_eq!(notification.field(&["slot"]), Some(r#""301234567""#));
+        assert_eq!(notification.field(&["trades", "trades", "side"]), None);
+        assert_eq!(notification.field(&["missing"]), None);
+
+        let response: api::GetTradesStreamResponse = notification.decode()?;
+        assert_eq!(response.slot, 301234567);
+        let trades = response.trades.expect("trades").trades;
+        assert_eq!(trades[0].side, api::Side::SAsk as i32);
+        assert_eq!(trades[0].fill_price, 142.25);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_response() -> Result<()> {
+        let text = r#"{"jsonrpc":"2.0","id":7,"result":"5d3b1d6c"}"#;
+        let Some(Frame::Response { id, text }) = Frame::parse(text.to_string()) else {
+            panic!("expected a response");
+        };
+        assert_eq!(id, 7);
+        assert_eq!(decode_response::<String>(&text)?, "5d3b1d6c");
+
+        let error = decode_response::<String>(r#"{"id":8,"error":"connection closed"}"#);
+        assert!(error.unwrap_err().to_string().starts_with("RPC error"));
+
+        assert!(Frame::parse(r#"{"jsonrpc":"2.0"}"#.to_string()).is_none());
+        assert!(Frame::parse("not json".to_string()).is_none());
+        Ok(())
+    }
+}
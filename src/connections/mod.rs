@@ -1 +1,3 @@
+pub mod backpressure;
+pub mod frame;
 pub mod ws;
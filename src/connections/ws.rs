@@ -10,12 +10,11 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::net::TcpStream;
+use tokio::runtime::Handle;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::{broadcast, mpsc, Mutex};
 use tokio::time::timeout;
 use tokio_rustls::rustls::{ClientConfig, RootCertStore};
-use tokio_stream::wrappers::ReceiverStream;
-use tokio_stream::Stream;
 use tokio_tungstenite::tungstenite::client::IntoClientRequest;
 use tokio_tungstenite::tungstenite::handshake::client::Request;
 use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
@@ -23,17 +22,24 @@ use tokio_tungstenite::{connect_async_tls_with_config, Connector};
 use tokio_tungstenite::{tungstenite::protocol::Message, WebSocketStream};
 use url::Url;
 
+use super::backpressure::{Backpressure, SubscriptionQueue, SubscriptionStream};
+use super::frame::{decode_response, Frame};
 use crate::common::{get_base_url_from_env, ws_endpoint, BaseConfig};
-use crate::provider::utils::convert_string_enums;
+use crate::provider::codec::to_json;
 
 const CONNECTION_RETRY_TIMEOUT: Duration = Duration::from_secs(15);
 const CONNECTION_RETRY_INTERVAL: Duration = Duration::from_millis(100);
-const SUBSCRIPTION_BUFFER: usize = 1000;
 const PING_INTERVAL: Duration = Duration::from_secs(30);
 
 #[derive(Debug)]
 pub struct Subscription {
-    sender: mpsc::Sender<Value>,
+    queue: Arc<SubscriptionQueue>,
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.queue.close();
+    }
 }
 
 #[derive(Clone)]
@@ -206,50 +212,74 @@ impl WS {
             .map_err(|_| anyhow::anyhow!("Response timeout"))?
             .ok_or_else(|| anyhow::anyhow!("Channel closed unexpectedly"))?;
 
-        let json_response: Value = serde_json::from_str(&response.response)
-            .map_err(|e| anyhow::anyhow!("Failed to parse response: {}", e))?;
-
-        if let Some(error) = json_response.get("error") {
-            return Err(anyhow::anyhow!("RPC error: {}", error));
-        }
-
-        let result = json_response
-            .get("result")
-            .ok_or_else(|| anyhow::anyhow!("Missing result field in response"))?;
-
-        let mut res = result.clone();
-        convert_string_enums(&mut res);
-
-        serde_json::from_value(res).map_err(|e| anyhow::anyhow!("Failed to parse result: {}", e))
+        decode_response(&response.response)
     }
 
     pub async fn stream_proto<Req, Resp>(
         &self,
         method: &str,
         request: &Req,
-    ) -> Result<impl Stream<Item = Result<Resp>> + Unpin>
+    ) -> Result<SubscriptionStream<Resp>>
+    where
+        Req: prost::Message + Serialize,
+        Resp: prost::Message + Default + DeserializeOwned + Send + Clone + 'static,
+    {
+        self.stream_proto_with(method, request, Backpressure::default())
+            .await
+    }
+
+    pub async fn stream_proto_with<Req, Resp>(
+        &self,
+        method: &str,
+        request: &Req,
+        backpressure: Backpressure,
+    ) -> Result<SubscriptionStream<Resp>>
     where
         Req: prost::Message + Serialize,
         Resp: prost::Message + Default + DeserializeOwned + Send + Clone + 'static,
     {
-        let (tx, rx) = mpsc::channel(SUBSCRIPTION_BUFFER);
+        let queue = SubscriptionQueue::new(backpressure);
 
-        let params = serde_json::to_value(request)?;
+        let params = to_json(request)?;
         let params_array = json!([method, params]);
         let subscription_id: String = self.request("subscribe", params_array).await?;
 
         {
             let mut subs = self.subscriptions.lock().await;
-            subs.insert(subscription_id, Subscription { sender: tx });
+            subs.insert(
+                subscription_id.clone(),
+                Subscription {
+                    queue: queue.clone(),
+                },
+            );
         }
 
-        Ok(ReceiverStream::new(rx).map(|value: Value| {
-            let mut modified_value = value;
-            convert_string_enums(&mut modified_value);
+        let unsubscribe = self.unsubscriber(subscription_id);
+        Ok(SubscriptionStream::new(queue).on_drop(unsubscribe))
+    }
 
-            serde_json::from_value(modified_value)
-                .map_err(|e| anyhow::anyhow!("Failed to parse stream value: {}", e))
-        }))
+    // Forgets the subscription and tells the server to stop it. Runs from `Drop`, so the work is
+    // spawned onto the current runtime, if there still is one.
+    fn unsubscriber(&self, subscription_id: String) -> impl FnOnce() + Send + 'static {
+        let request_id = self.request_id.fetch_add(1, Ordering::SeqCst);
+        let write_tx = self.write_tx.clone();
+        let subscriptions = self.subscriptions.clone();
+
+        move || {
+            let Ok(runtime) = Handle::try_current() else {
+                return;
+            };
+            runtime.spawn(async move {
+                subscriptions.lock().await.remove(&subscription_id);
+                let request_json = json!({
+                    "jsonrpc": "2.0",
+                    "id": request_id,
+                    "method": "unsubscribe",
+                    "params": [subscription_id]
+                });
+                let _ = write_tx.send(Message::Text(request_json.to_string())).await;
+            });
+        }
     }
 
     pub async fn close(self) -> Result<()> {
@@ -302,11 +332,12 @@ async fn read_loop(
         let Ok(Some(Ok(msg))) = timeout(Duration::from_millis(100), stream.next()).await else {
             continue;
         };
+        drop(stream);
 
         match msg {
             Message::Text(text) => {
-                if let Ok(value) = serde_json::from_str(&text) {
-                    handle_message(&value, &request_map, &subscriptions, &text).await;
+                if let Some(frame) = Frame::parse(text) {
+                    handle_message(frame, &request_map, &subscriptions).await;
                 }
             }
             Message::Close(_) => break,
@@ -335,43 +366,30 @@ async fn ping_loop(
 }
 
 async fn handle_message(
-    value: &Value,
+    frame: Frame,
     request_map: &Arc<Mutex<HashMap<u64, RequestTracker>>>,
     subscriptions: &Arc<Mutex<HashMap<String, Subscription>>>,
-    text: &str,
 ) {
-    match value.get("id").and_then(|id| id.as_u64()) {
-        Some(id) => {
+    match frame {
+        Frame::Response { id, text } => {
             if let Some(tracker) = request_map.lock().await.get(&id) {
-                let _ = tracker
-                    .ch
-                    .send(ResponseUpdate {
-                        response: text.to_string(),
-                    })
-                    .await;
+                let _ = tracker.ch.send(ResponseUpdate { response: text }).await;
             }
         }
-        None => handle_subscription(value, subscriptions).await,
-    }
-}
+        Frame::Notification(notification) => {
+            let mut subs = subscriptions.lock().await;
+            let Some(sub) = subs.get(notification.subscription()) else {
+                return;
+            };
+            if sub.queue.is_closed() {
+                subs.remove(notification.subscription());
+                return;
+            }
 
-async fn handle_subscription(
-    map: &Value,
-    subscriptions: &Arc<Mutex<HashMap<String, Subscription>>>,
-) {
-    let Some(id) = map
-        .get("params")
-        .and_then(|p| p.get("subscription"))
-        .and_then(|s| s.as_str())
-    else {
-        return;
-    };
-
-    let Some(result) = map.get("params").and_then(|p| p.get("result")) else {
-        return;
-    };
-
-    if let Some(sub) = subscriptions.lock().await.get(id) {
-        let _ = sub.sender.send(result.clone()).await;
+            // Only this subscription's policy decides whether the read loop waits here.
+            let queue = sub.queue.clone();
+            drop(subs);
+            queue.push(notification).await;
+        }
     }
 }
@@ -0,0 +1,335 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use serde_json::Value;
+use solana_trader_proto::{api, common};
+use tokio::time::error::Elapsed;
+
+use super::guard::impact_percent_v2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum Venue {
+    Raydium,
+    RaydiumCpmm,
+    RaydiumClmm,
+    Jupiter,
+    PumpFun,
+}
+
+#[derive(Debug, Clone)]
+pub struct PumpFunQuoteParams {
+    pub mint_address: String,
+    pub bonding_curve_address: String,
+    pub quote_type: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct BestQuoteRequest {
+    pub in_token: String,
+    pub out_token: String,
+    pub in_amount: f64,
+    pub slippage: f64,
+    // Pump.fun is only queried when its bonding curve is known
+    pub pump_fun: Option<PumpFunQuoteParams>,
+    pub deadline: Duration,
+}
+
+impl BestQuoteRequest {
+    pub fn raydium(&self) -> api::GetRaydiumQuotesRequest {
+        api::GetRaydiumQuotesRequest {
+            in_token: self.in_token.clone(),
+            out_token: self.out_token.clone(),
+            in_amount: self.in_amount,
+            slippage: self.slippage,
+        }
+    }
+
+    pub fn raydium_cpmm(&self) -> api::GetRaydiumCpmmQuotesRequest {
+        api::GetRaydiumCpmmQuotesRequest {
+            in_token: self.in_token.clone(),
+            out_token: self.out_token.clone(),
+            in_amount: self.in_amount,
+            slippage: self.slippage,
+        }
+    }
+
+    pub fn raydium_clmm(&self) -> api::GetRaydiumClmmQuotesRequest {
+        api::GetRaydiumClmmQuotesRequest {
+            in_token: self.in_token.clone(),
+            out_token: self.out_token.clone(),
+            in_amount: self.in_amount,
+            slippage: self.slippage,
+        }
+    }
+
+    pub fn jupiter(&self) -> api::GetJupiterQuotesRequest {
+        api::GetJupiterQuotesRequest {
+            in_token: self.in_token.clone(),
+            out_token: self.out_token.clone(),
+            in_amount: self.in_amount,
+            slippage: self.slippage,
+            fast_mode: None,
+        }
+    }
+
+    pub fn pump_fun(&self) -> Option<api::GetPumpFunQuotesRequest> {
+        self.pump_fun
+            .as_ref()
+            .map(|params| api::GetPumpFunQuotesRequest {
+                quote_type: params.quote_type.clone(),
+                mint_address: params.mint_address.clone(),
+                bonding_curve_address: params.bonding_curve_address.clone(),
+                amount: self.in_amount,
+            })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QuoteCandidate {
+    pub venue: Venue,
+    pub out_amount: f64,
+    pub out_amount_min: f64,
+    // fee amounts by mint, since steps charge fees in different tokens; fees reported without a
+    // mint are kept under ""
+    pub fees: BTreeMap<String, f64>,
+    pub price_impact_percent: Option<f64>,
+    pub route: Value,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AggregatedQuotes {
+    // best first
+    pub candidates: Vec<QuoteCandidate>,
+    pub failures: Vec<(Venue, String)>,
+}
+
+impl AggregatedQuotes {
+    pub fn best(&self) -> Option<&QuoteCandidate> {
+        self.candidates.first()
+    }
+
+    pub fn record<R: VenueQuotes>(
+        &mut self,
+        venue: Venue,
+        result: std::result::Result<Result<R>, Elapsed>,
+    ) {
+        match result {
+            Ok(Ok(response)) => self.candidates.extend(response.candidates(venue)),
+            Ok(Err(e)) => self.failures.push((venue, e.to_string())),
+            Err(_) => self.failures.push((venue, "deadline exceeded".to_string())),
+        }
+    }
+
+    // Ranks by output, then by price impact. Fees are not compared: they are in different
+    // mints and already reflected in the output. Fails only if no venue produced a quote.
+    pub fn finish(mut self) -> Result<Self> {
+        if self.candidates.is_empty() {
+            let failures: Vec<String> = self
+                .failures
+                .iter()
+                .map(|(venue, e)| format!("{:?}: {}", venue, e))
+                .collect();
+            return Err(anyhow!(
+                "No venue returned a quote: {}",
+                failures.join("; ")
+            ));
+        }
+
+        self.candidates.sort_by(|a, b| {
+            b.out_amount.total_cmp(&a.out_amount).then_with(|| {
+                let impact = |c: &QuoteCandidate| c.price_impact_percent.unwrap_or(f64::MAX);
+                impact(a).total_cmp(&impact(b))
+            })
+        });
+        Ok(self)
+    }
+}
+
+// Quote responses the aggregator can rank: one candidate per route, or a single one for
+// Pump.fun, with fees summed and the largest price impact taken over the route's steps.
+pub trait VenueQuotes {
+    fn candidates(&self, venue: Venue) -> Vec<QuoteCandidate>;
+}
+
+impl VenueQuotes for api::GetRaydiumQuotesResponse {
+    fn candidates(&self, venue: Venue) -> Vec<QuoteCandidate> {
+        raydium_candidates(venue, &self.routes)
+    }
+}
+
+impl VenueQuotes for api::GetRaydiumCpmmQuotesResponse {
+    fn candidates(&self, venue: Venue) -> Vec<QuoteCandidate> {
+        raydium_candidates(venue, &self.routes)
+    }
+}
+
+impl VenueQuotes for api::GetRaydiumClmmQuotesResponse {
+    fn candidates(&self, venue: Venue) -> Vec<QuoteCandidate> {
+        raydium_candidates(venue, &self.routes)
+    }
+}
+
+impl VenueQuotes for api::GetJupiterQuotesResponse {
+    fn candidates(&self, venue: Venue) -> Vec<QuoteCandidate> {
+        self.routes
+            .iter()
+            .map(|route| {
+                let steps = route
+                    .steps
+                    .iter()
+                    .map(|step| (step.fee.as_ref(), step.price_impact_percent.as_ref()));
+                route_candidate(venue, route, route.out_amount, route.out_amount_min, steps)
+            })
+            .collect()
+    }
+}
+
+impl VenueQuotes for api::GetPumpFunQuotesResponse {
+    fn candidates(&self, venue: Venue) -> Vec<QuoteCandidate> {
+        vec![route_candidate(
+            venue,
+            self,
+            self.out_amount,
+            self.out_amount,
+            std::iter::empty(),
+        )]
+    }
+}
+
+fn raydium_candidates(venue: Venue, routes: &[api::RaydiumQuoteRoute]) -> Vec<QuoteCandidate> {
+    routes
+        .iter()
+        .map(|route| {
+            let steps = route
+                .steps
+                .iter()
+                .map(|step| (step.fee.as_ref(), step.price_impact_percent.as_ref()));
+            route_candidate(venue, route, route.out_amount, route.out_amount_min, steps)
+        })
+        .collect()
+}
+
+fn route_candidate<'a, R: Serialize>(
+    venue: Venue,
+    route: &R,
+    out_amount: f64,
+    out_amount_min: f64,
+    steps: impl Iterator<
+        Item = (
+            Option<&'a common::Fee>,
+            Option<&'a common::PriceImpactPercentV2>,
+        ),
+    >,
+) -> QuoteCandidate {
+    let mut fees = BTreeMap::new();
+    let mut price_impact_percent: Option<f64> = None;
+    for (fee, impact) in steps {
+        if let Some(fee) = fee {
+            *fees.entry(fee.mint.clone()).or_default() += fee.amount as f64;
+        }
+        if let Some(impact) = impact.map(impact_percent_v2) {
+            price_impact_percent = Some(price_impact_percent.map_or(impact, |max| max.max(impact)));
+        }
+    }
+
+    QuoteCandidate {
+        venue,
+        out_amount,
+        out_amount_min,
+        fees,
+        price_impact_percent,
+        route: serde_json::to_value(route).unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(fee: f32, mint: &str, impact: Option<(f64, &str)>) -> api::RaydiumQuoteStep {
+        api::RaydiumQuoteStep {
+            fee: Some(common::Fee {
+                amount: fee,
+                mint: mint.to_string(),
+                percent: 0.25,
+            }),
+            price_impact_percent: impact.map(|(percent, infinity)| common::PriceImpactPercentV2 {
+                percent,
+                infinity: infinity.to_string(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_normalize_and_rank() {
+        assert!(AggregatedQuotes::default().finish().is_err());
+
+        let raydium = api::GetRaydiumQuotesResponse {
+            routes: vec![api::RaydiumQuoteRoute {
+                out_amount: 1.5,
+                out_amount_min: 1.4,
+                steps: vec![
+                    step(0.25, "SOL", Some((0.3, "NOT"))),
+                    step(0.5, "USDC", Some((0.1, "NOT"))),
+                    step(0.5, "SOL", None),
+                ],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let pump_fun = api::GetPumpFunQuotesResponse {
+            in_amount: 1.0,
+            out_amount: 2.0,
+            ..Default::default()
+        };
+
+        let mut quotes = AggregatedQuotes::default();
+        quotes.record(Venue::Raydium, Ok(Ok(raydium)));
+        quotes.record(Venue::PumpFun, Ok(Ok(pump_fun)));
+        quotes.record::<api::GetJupiterQuotesResponse>(
+            Venue::Jupiter,
+            Ok(Err(anyhow!("unavailable"))),
+        );
+
+        let quotes = quotes.finish().unwrap();
+        let best = quotes.best().unwrap();
+        assert_eq!(best.venue, Venue::PumpFun);
+        assert_eq!(best.out_amount, 2.0);
+        assert!(best.fees.is_empty());
+
+        let raydium = &quotes.candidates[1];
+        assert_eq!(raydium.out_amount_min, 1.4);
+        assert_eq!(
+            raydium.fees,
+            BTreeMap::from([("SOL".to_string(), 0.75), ("USDC".to_string(), 0.5)])
+        );
+        assert_eq!(raydium.price_impact_percent, Some(0.3));
+        assert_eq!(quotes.failures.len(), 1);
+    }
+
+    #[test]
+    fn test_infinite_price_impact_ranks_last() {
+        let route = |infinity: &str| api::RaydiumQuoteRoute {
+            out_amount: 1.0,
+            steps: vec![step(0.0, "SOL", Some((0.1, infinity)))],
+            ..Default::default()
+        };
+        let response = api::GetRaydiumClmmQuotesResponse {
+            routes: vec![route("POSITIVE"), route("NOT")],
+            ..Default::default()
+        };
+
+        let mut quotes = AggregatedQuotes::default();
+        quotes.record(Venue::RaydiumClmm, Ok(Ok(response)));
+        let quotes = quotes.finish().unwrap();
+
+        assert_eq!(quotes.candidates[0].price_impact_percent, Some(0.1));
+        assert_eq!(
+            quotes.candidates[1].price_impact_percent,
+            Some(f64::INFINITY)
+        );
+    }
+}
@@ -0,0 +1,345 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::{Arc, RwLock};
+
+use anyhow::{anyhow, Result};
+use futures_util::{Stream, StreamExt};
+use serde::Serialize;
+use solana_trader_proto::api;
+use tokio::task::JoinHandle;
+
+// Raydium AMM v4 and the default CPMM config both charge 0.25% on the input amount.
+pub const DEFAULT_FEE_BPS: u64 = 25;
+const BPS_DENOMINATOR: u64 = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConstantProductPool {
+    pub pool_address: String,
+    pub project: String,
+    pub token_a: String,
+    pub reserve_a: u64,
+    pub token_b: String,
+    pub reserve_b: u64,
+    pub fee_bps: u64,
+    pub slot: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LocalQuote {
+    pub pool_address: String,
+    pub in_token: String,
+    pub out_token: String,
+    pub in_amount: u64,
+    pub out_amount: u64,
+    pub fee: u64,
+    pub price_impact_percent: f64,
+    pub slot: u64,
+}
+
+impl ConstantProductPool {
+    fn reserves_for(&self, in_token: &str) -> Result<(u64, u64, &str)> {
+        if in_token == self.token_a {
+            Ok((self.reserve_a, self.reserve_b, &self.token_b))
+        } else if in_token == self.token_b {
+            Ok((self.reserve_b, self.reserve_a, &self.token_a))
+        } else {
+            Err(anyhow!(
+                "Pool {} does not trade {}",
+                self.pool_address,
+                in_token
+            ))
+        }
+    }
+
+    // Amounts are in raw token units. The fee is rounded up, as the on-chain programs do,
+    // and the output is rounded down.
+    pub fn quote(&self, in_token: &str, in_amount: u64) -> Result<LocalQuote> {
+        let (reserve_in, reserve_out, out_token) = self.reserves_for(in_token)?;
+        check_fee_bps(self.fee_bps)?;
+        if reserve_in == 0 || reserve_out == 0 {
+            return Err(anyhow!("Pool {} has no liquidity", self.pool_address));
+        }
+
+        let fee = (in_amount as u128 * self.fee_bps as u128).div_ceil(BPS_DENOMINATOR as u128);
+        let in_after_fee = in_amount as u128 - fee;
+        let out_amount = reserve_out as u128 * in_after_fee / (reserve_in as u128 + in_after_fee);
+
+        // measured against the fee-free spot price, so it reflects pool depth only
+        let spot_out = in_after_fee as f64 * reserve_out as f64 / reserve_in as f64;
+        let price_impact_percent = if spot_out > 0.0 {
+            (1.0 - out_amount as f64 / spot_out) * 100.0
+        } else {
+            0.0
+        };
+
+        Ok(LocalQuote {
+            pool_address: self.pool_address.clone(),
+            in_token: in_token.to_string(),
+            out_token: out_token.to_string(),
+            in_amount,
+            out_amount: out_amount as u64,
+            fee: fee as u64,
+            price_impact_percent,
+            slot: self.slot,
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct LocalQuoter {
+    pools: RwLock<HashMap<String, ConstantProductPool>>,
+    fee_overrides: RwLock<HashMap<String, u64>>,
+}
+
+impl LocalQuoter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_fee_bps(&self, pool_address: &str, fee_bps: u64) -> Result<()> {
+        check_fee_bps(fee_bps)?;
+        self.fee_overrides
+            .write()
+            .unwrap()
+            .insert(pool_address.to_string(), fee_bps);
+        if let Some(pool) = self.pools.write().unwrap().get_mut(pool_address) {
+            pool.fee_bps = fee_bps;
+        }
+        Ok(())
+    }
+
+    pub fn pool(&self, pool_address: &str) -> Option<ConstantProductPool> {
+        self.pools.read().unwrap().get(pool_address).cloned()
+    }
+
+    pub fn pools(&self) -> Vec<ConstantProductPool> {
+        self.pools.read().unwrap().values().cloned().collect()
+    }
+
+    // Returns false when the update is older than the reserves already held, or is for a
+    // pool that is not constant-product.
+    pub fn apply(&self, response: &api::GetPoolReservesStreamResponse) -> Result<bool> {
+        let Some(mut pool) = parse_pool(response)? else {
+            return Ok(false);
+        };
+        if let Some(fee_bps) = self.fee_overrides.read().unwrap().get(&pool.pool_address) {
+            pool.fee_bps = *fee_bps;
+        }
+
+        let mut pools = self.pools.write().unwrap();
+        match pools.get(&pool.pool_address) {
+            Some(current) if current.slot > pool.slot => Ok(false),
+            _ => {
+                pools.insert(pool.pool_address.clone(), pool);
+                Ok(true)
+            }
+        }
+    }
+
+    pub fn quote(&self, pool_address: &str, in_token: &str, in_amount: u64) -> Result<LocalQuote> {
+        self.pools
+            .read()
+            .unwrap()
+            .get(pool_address)
+            .ok_or_else(|| anyhow!("No reserves known for pool {}", pool_address))?
+            .quote(in_token, in_amount)
+    }
+
+    // Quotes every known pool for the pair and returns the best output first.
+    pub fn quote_pair(&self, in_token: &str, out_token: &str, in_amount: u64) -> Vec<LocalQuote> {
+        let mut quotes: Vec<LocalQuote> = self
+            .pools
+            .read()
+            .unwrap()
+            .values()
+            .filter_map(|pool| pool.quote(in_token, in_amount).ok())
+            .filter(|quote| quote.out_token == out_token)
+            .collect();
+        quotes.sort_by(|a, b| b.out_amount.cmp(&a.out_amount));
+        quotes
+    }
+
+    // Keeps reserves current from a `get_pool_reserves_stream` subscription of any transport.
+    // Bad updates are logged and skipped; the task ends when the stream ends.
+    pub fn watch_reserves<S, E>(self: &Arc<Self>, stream: S) -> JoinHandle<()>
+    where
+        S: Stream<Item = std::result::Result<api::GetPoolReservesStreamResponse, E>>
+            + Send
+            + Unpin
+            + 'static,
+        E: Display + Send,
+    {
+        let quoter = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut stream = stream;
+            while let Some(message) = stream.next().await {
+                let result = match message {
+                    Ok(response) => quoter.apply(&response).map(|_| ()),
+                    Err(e) => Err(anyhow!("{}", e)),
+                };
+                if let Err(e) = result {
+                    tracing::warn!("Local quoter skipped reserves update: {}", e);
+                }
+            }
+        })
+    }
+}
+
+fn parse_pool(
+    response: &api::GetPoolReservesStreamResponse,
+) -> Result<Option<ConstantProductPool>> {
+    let reserves = response
+        .reserves
+        .as_ref()
+        .ok_or_else(|| anyhow!("Pool reserves update has no reserves"))?;
+
+    // projects this proto version does not name, such as CLMM pools, may not be constant-product
+    let Ok(project) = api::Project::try_from(reserves.project) else {
+        return Ok(None);
+    };
+
+    Ok(Some(ConstantProductPool {
+        pool_address: reserves.pool_address.clone(),
+        project: project.as_str_name().to_string(),
+        token_a: reserves.token1_address.clone(),
+        reserve_a: parse_reserve(&reserves.token1_reserves, "token1Reserves")?,
+        token_b: reserves.token2_address.clone(),
+        reserve_b: parse_reserve(&reserves.token2_reserves, "token2Reserves")?,
+        fee_bps: DEFAULT_FEE_BPS,
+        slot: u64::try_from(response.slot).unwrap_or_default(),
+    }))
+}
+
+// A fee above 100% would take more than the input amount.
+fn check_fee_bps(fee_bps: u64) -> Result<()> {
+    if fee_bps > BPS_DENOMINATOR {
+        return Err(anyhow!(
+            "Fee of {} bps exceeds {} bps",
+            fee_bps,
+            BPS_DENOMINATOR
+        ));
+    }
+    Ok(())
+}
+
+fn parse_reserve(reserve: &str, field: &str) -> Result<u64> {
+    reserve
+        .parse()
+        .map_err(|e| anyhow!("Pool reserves update has an invalid {}: {}", field, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(reserve_a: u64, reserve_b: u64, fee_bps: u64) -> ConstantProductPool {
+        ConstantProductPool {
+            pool_address: "pool".to_string(),
+            project: "P_RAYDIUM".to_string(),
+            token_a: "SOL".to_string(),
+            reserve_a,
+            token_b: "USDC".to_string(),
+            reserve_b,
+            fee_bps,
+            slot: 1,
+        }
+    }
+
+    #[test]
+    fn test_constant_product_swap_vectors() -> Result<()> {
+        // no fee: x * y = k with 100 * 100, swapping 100 in halves the price
+        let quote = pool(100, 100, 0).quote("SOL", 100)?;
+        assert_eq!(quote.out_amount, 50);
+        assert_eq!(quote.fee, 0);
+        assert!((quote.price_impact_percent - 50.0).abs() < 1e-9);
+
+        // 0.25%: fee ceil(2.5) = 3, 997 * 1e6 / (1e6 + 997) = 996.00..
+        let quote = pool(1_000_000, 1_000_000, 25).quote("SOL", 1_000)?;
+        assert_eq!(quote.fee, 3);
+        assert_eq!(quote.out_amount, 996);
+        assert_eq!(quote.out_token, "USDC");
+
+        // 1 SOL into 50 SOL / 7500 USDC:
+        // 7_500_000_000 * 997_500_000 / 50_997_500_000 = 146_698_367.56..
+        let quote = pool(50_000_000_000, 7_500_000_000, 25).quote("SOL", 1_000_000_000)?;
+        assert_eq!(quote.fee, 2_500_000);
+        assert_eq!(quote.out_amount, 146_698_367);
+
+        // reverse direction uses the other reserve as input
+        let quote = pool(50_000_000_000, 7_500_000_000, 25).quote("USDC", 150_000_000)?;
+        assert_eq!(quote.out_token, "SOL");
+        assert_eq!(quote.out_amount, 977_989_117);
+
+        assert!(pool(100, 100, 25).quote("BONK", 1).is_err());
+        assert!(pool(0, 100, 25).quote("SOL", 1).is_err());
+        Ok(())
+    }
+
+    fn update(
+        slot: i64,
+        reserve: &str,
+        project: api::Project,
+    ) -> api::GetPoolReservesStreamResponse {
+        api::GetPoolReservesStreamResponse {
+            slot,
+            reserves: Some(api::PoolReserves {
+                token1_reserves: reserve.to_string(),
+                token1_address: "SOL".to_string(),
+                token2_reserves: "1000000".to_string(),
+                token2_address: "USDC".to_string(),
+                pool_address: "pool".to_string(),
+                project: project as i32,
+            }),
+            timestamp: None,
+        }
+    }
+
+    #[test]
+    fn test_apply_reserves_updates() -> Result<()> {
+        let quoter = LocalQuoter::new();
+
+        assert!(quoter.apply(&update(10, "1000000", api::Project::PRaydium))?);
+        let pool = quoter.pool("pool").unwrap();
+        assert_eq!(pool.reserve_a, 1_000_000);
+        assert_eq!((pool.slot, pool.fee_bps), (10, DEFAULT_FEE_BPS));
+
+        // stale and unparseable updates leave the reserves alone
+        assert!(!quoter.apply(&update(9, "5", api::Project::PRaydium))?);
+        assert!(quoter
+            .apply(&update(11, "not a number", api::Project::PRaydium))
+            .is_err());
+        assert_eq!(quoter.pool("pool").unwrap().reserve_a, 1_000_000);
+
+        assert!(quoter.set_fee_bps("pool", 10_001).is_err());
+        quoter.set_fee_bps("pool", 0)?;
+        assert_eq!(quoter.quote("pool", "SOL", 1_000_000)?.out_amount, 500_000);
+        assert_eq!(quoter.quote_pair("SOL", "USDC", 1_000_000).len(), 1);
+        assert!(quoter.quote_pair("SOL", "BONK", 1_000_000).is_empty());
+        assert!(quoter.quote("other", "SOL", 1).is_err());
+
+        // the override outlives later updates
+        assert!(quoter.apply(&update(12, "2000000", api::Project::PRaydium))?);
+        assert_eq!(quoter.pool("pool").unwrap().fee_bps, 0);
+
+        assert!(pool(100, 100, 10_001).quote("SOL", 1).is_err());
+
+        // an unknown project may be a CLMM pool, which is not constant-product
+        let mut clmm = update(13, "1000000", api::Project::PRaydium);
+        clmm.reserves.as_mut().unwrap().project = 99;
+        assert!(!quoter.apply(&clmm)?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_watch_reserves_skips_bad_updates() {
+        let quoter = Arc::new(LocalQuoter::new());
+        let updates = futures_util::stream::iter(vec![
+            Ok(update(10, "oops", api::Project::PRaydium)),
+            Err("transient"),
+            Ok(update(11, "1000000", api::Project::PRaydium)),
+        ]);
+
+        quoter.watch_reserves(updates).await.unwrap();
+        assert_eq!(quoter.pool("pool").unwrap().slot, 11);
+    }
+}
@@ -0,0 +1,432 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use futures_util::{Stream, StreamExt};
+use serde::Serialize;
+use solana_trader_proto::api;
+use tokio::task::JoinHandle;
+
+use crate::provider::aggregator::Venue;
+
+#[derive(Debug, Clone)]
+pub struct QuoteCacheConfig {
+    pub ttl: Duration,
+    // in amounts within the same bucket share a cache entry; zero keys on the exact amount
+    pub amount_bucket: f64,
+    pub invalidate_on_new_slot: bool,
+}
+
+impl Default for QuoteCacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_millis(500),
+            amount_bucket: 0.0,
+            invalidate_on_new_slot: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QuoteKey {
+    pub venue: Venue,
+    pub in_token: String,
+    pub out_token: String,
+    pub amount_bucket: u64,
+    pub slippage_bps: u64,
+    // Jupiter's fast mode quotes a different route set than its normal mode
+    pub fast_mode: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct QuoteCacheMetrics {
+    pub hits: u64,
+    pub misses: u64,
+    pub expirations: u64,
+    pub invalidations: u64,
+    pub entries: usize,
+    pub slot: u64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    value: Arc<dyn Any + Send + Sync>,
+    inserted: Instant,
+}
+
+#[derive(Debug)]
+pub struct QuoteCache {
+    config: QuoteCacheConfig,
+    entries: Mutex<HashMap<QuoteKey, Entry>>,
+    // one lock per key being fetched, so concurrent misses wait for a single request
+    in_flight: Mutex<HashMap<QuoteKey, Arc<tokio::sync::Mutex<()>>>>,
+    slot: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    expirations: AtomicU64,
+    invalidations: AtomicU64,
+}
+
+impl QuoteCache {
+    pub fn new(config: QuoteCacheConfig) -> Self {
+        Self {
+            config,
+            entries: Mutex::new(HashMap::new()),
+            in_flight: Mutex::new(HashMap::new()),
+            slot: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            expirations: AtomicU64::new(0),
+            invalidations: AtomicU64::new(0),
+        }
+    }
+
+    pub fn config(&self) -> &QuoteCacheConfig {
+        &self.config
+    }
+
+    pub fn key(
+        &self,
+        venue: Venue,
+        in_token: &str,
+        out_token: &str,
+        amount: f64,
+        slippage: f64,
+    ) -> QuoteKey {
+        let amount_bucket = if self.config.amount_bucket > 0.0 {
+            (amount / self.config.amount_bucket).floor() as u64
+        } else {
+            amount.to_bits()
+        };
+
+        QuoteKey {
+            venue,
+            in_token: in_token.to_string(),
+            out_token: out_token.to_string(),
+            amount_bucket,
+            slippage_bps: (slippage * 100.0).round() as u64,
+            fast_mode: false,
+        }
+    }
+
+    pub fn get<T: Clone + Send + Sync + 'static>(&self, key: &QuoteKey) -> Option<T> {
+        let mut entries = self.entries.lock().unwrap();
+
+        let cached = match entries.get(key) {
+            Some(entry) if entry.inserted.elapsed() > self.config.ttl => {
+                entries.remove(key);
+                self.expirations.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+            Some(entry) => entry.value.downcast_ref::<T>().cloned(),
+            None => None,
+        };
+
+        let counter = if cached.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+
+        cached
+    }
+
+    pub fn insert<T: Clone + Send + Sync + 'static>(&self, key: QuoteKey, value: T) {
+        self.entries.lock().unwrap().insert(
+            key,
+            Entry {
+                value: Arc::new(value),
+                inserted: Instant::now(),
+            },
+        );
+    }
+
+    pub async fn get_or_fetch<T, F>(&self, key: QuoteKey, fetch: F) -> Result<T>
+    where
+        T: Clone + Send + Sync + 'static,
+        F: Future<Output = Result<T>>,
+    {
+        if let Some(cached) = self.get(&key) {
+            return Ok(cached);
+        }
+
+        let lock = self
+            .in_flight
+            .lock()
+            .unwrap()
+            .entry(key.clone())
+            .or_default()
+            .clone();
+        let guard = lock.lock().await;
+
+        // A concurrent miss may have filled the key while this one waited. If that fetch
+        // failed, the next waiter fetches in turn.
+        let result = match self.peek(&key) {
+            Some(cached) => Ok(cached),
+            None => fetch.await.map(|value| {
+                self.insert(key.clone(), value.clone());
+                value
+            }),
+        };
+
+        drop(guard);
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if in_flight
+            .get(&key)
+            .is_some_and(|current| Arc::ptr_eq(current, &lock) && Arc::strong_count(&lock) == 2)
+        {
+            in_flight.remove(&key);
+        }
+
+        result
+    }
+
+    // Like `get`, without touching the hit and miss counters.
+    fn peek<T: Clone + Send + Sync + 'static>(&self, key: &QuoteKey) -> Option<T> {
+        self.entries
+            .lock()
+            .unwrap()
+            .get(key)
+            .filter(|entry| entry.inserted.elapsed() <= self.config.ttl)
+            .and_then(|entry| entry.value.downcast_ref::<T>().cloned())
+    }
+
+    // Quotes are priced against pool state as of the slot they were served in, so every
+    // entry is dropped once a newer slot is seen.
+    pub fn on_slot(&self, slot: u64) {
+        let previous = self.slot.fetch_max(slot, Ordering::Relaxed);
+        if slot > previous && self.config.invalidate_on_new_slot {
+            self.invalidate();
+        }
+    }
+
+    pub fn invalidate(&self) {
+        let mut entries = self.entries.lock().unwrap();
+        if !entries.is_empty() {
+            entries.clear();
+            self.invalidations.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn metrics(&self) -> QuoteCacheMetrics {
+        QuoteCacheMetrics {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            expirations: self.expirations.load(Ordering::Relaxed),
+            invalidations: self.invalidations.load(Ordering::Relaxed),
+            entries: self.entries.lock().unwrap().len(),
+            slot: self.slot.load(Ordering::Relaxed),
+        }
+    }
+
+    // Feeds slots from a `get_block_stream` subscription of any transport into the cache.
+    // The task ends when the stream ends or errors.
+    pub fn watch_blocks<S, E>(self: &Arc<Self>, stream: S) -> JoinHandle<()>
+    where
+        S: Stream<Item = std::result::Result<api::GetBlockStreamResponse, E>>
+            + Send
+            + Unpin
+            + 'static,
+        E: Display + Send,
+    {
+        let cache = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut stream = stream;
+            while let Some(message) = stream.next().await {
+                match message {
+                    Ok(response) => {
+                        if let Some(slot) = block_slot(&response) {
+                            cache.on_slot(slot);
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!("Quote cache block stream error: {}", e);
+                        break;
+                    }
+                }
+            }
+        })
+    }
+}
+
+// Quote requests the generated endpoints can cache, keyed on what changes the quote.
+pub(crate) trait CachedQuote {
+    fn quote_key(&self, cache: &QuoteCache) -> QuoteKey;
+}
+
+impl CachedQuote for api::GetRaydiumQuotesRequest {
+    fn quote_key(&self, cache: &QuoteCache) -> QuoteKey {
+        cache.key(
+            Venue::Raydium,
+            &self.in_token,
+            &self.out_token,
+            self.in_amount,
+            self.slippage,
+        )
+    }
+}
+
+impl CachedQuote for api::GetRaydiumCpmmQuotesRequest {
+    fn quote_key(&self, cache: &QuoteCache) -> QuoteKey {
+        cache.key(
+            Venue::RaydiumCpmm,
+            &self.in_token,
+            &self.out_token,
+            self.in_amount,
+            self.slippage,
+        )
+    }
+}
+
+impl CachedQuote for api::GetRaydiumClmmQuotesRequest {
+    fn quote_key(&self, cache: &QuoteCache) -> QuoteKey {
+        cache.key(
+            Venue::RaydiumClmm,
+            &self.in_token,
+            &self.out_token,
+            self.in_amount,
+            self.slippage,
+        )
+    }
+}
+
+// Keyed on the mint and quote type; the bonding curve is implied by the mint.
+impl CachedQuote for api::GetPumpFunQuotesRequest {
+    fn quote_key(&self, cache: &QuoteCache) -> QuoteKey {
+        cache.key(
+            Venue::PumpFun,
+            &self.mint_address,
+            &self.quote_type,
+            self.amount,
+            0.0,
+        )
+    }
+}
+
+impl CachedQuote for api::GetJupiterQuotesRequest {
+    fn quote_key(&self, cache: &QuoteCache) -> QuoteKey {
+        QuoteKey {
+            fast_mode: self.fast_mode.unwrap_or_default(),
+            ..cache.key(
+                Venue::Jupiter,
+                &self.in_token,
+                &self.out_token,
+                self.in_amount,
+                self.slippage,
+            )
+        }
+    }
+}
+
+pub(crate) async fn cached_request<R, T, F>(
+    cache: Option<Arc<QuoteCache>>,
+    request: &R,
+    fetch: F,
+) -> Result<T>
+where
+    R: CachedQuote,
+    T: Clone + Send + Sync + 'static,
+    F: Future<Output = Result<T>>,
+{
+    match cache {
+        Some(cache) => {
+            let key = request.quote_key(&cache);
+            cache.get_or_fetch(key, fetch).await
+        }
+        None => fetch.await,
+    }
+}
+
+fn block_slot(response: &api::GetBlockStreamResponse) -> Option<u64> {
+    response.block.as_ref().map(|block| block.slot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_cache(ttl: Duration) -> QuoteCache {
+        QuoteCache::new(QuoteCacheConfig {
+            ttl,
+            amount_bucket: 0.5,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_amount_buckets_share_entries() {
+        let cache = new_cache(Duration::from_secs(60));
+        let key = cache.key(Venue::Raydium, "SOL", "USDC", 1.1, 0.1);
+        cache.insert(key, 42u64);
+
+        let same_bucket = cache.key(Venue::Raydium, "SOL", "USDC", 1.4, 0.1);
+        assert_eq!(cache.get::<u64>(&same_bucket), Some(42));
+
+        let other_bucket = cache.key(Venue::Raydium, "SOL", "USDC", 1.6, 0.1);
+        assert_eq!(cache.get::<u64>(&other_bucket), None);
+
+        let other_venue = cache.key(Venue::Jupiter, "SOL", "USDC", 1.1, 0.1);
+        assert_eq!(cache.get::<u64>(&other_venue), None);
+
+        let metrics = cache.metrics();
+        assert_eq!((metrics.hits, metrics.misses), (1, 2));
+    }
+
+    #[test]
+    fn test_ttl_and_slot_invalidation() {
+        let cache = new_cache(Duration::ZERO);
+        let key = cache.key(Venue::Raydium, "SOL", "USDC", 1.0, 0.1);
+        cache.insert(key.clone(), 1u64);
+        std::thread::sleep(Duration::from_millis(1));
+        assert_eq!(cache.get::<u64>(&key), None);
+        assert_eq!(cache.metrics().expirations, 1);
+
+        let cache = new_cache(Duration::from_secs(60));
+        cache.on_slot(10);
+        cache.insert(key.clone(), 1u64);
+        cache.on_slot(9);
+        assert_eq!(cache.get::<u64>(&key), Some(1));
+        cache.on_slot(11);
+        assert_eq!(cache.get::<u64>(&key), None);
+
+        let metrics = cache.metrics();
+        assert_eq!(metrics.invalidations, 1);
+        assert_eq!(metrics.slot, 11);
+        assert_eq!(metrics.entries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_misses_fetch_once() -> Result<()> {
+        let cache = new_cache(Duration::from_secs(60));
+        let fetches = AtomicU64::new(0);
+        let counter = &fetches;
+        let fetch = move || async move {
+            counter.fetch_add(1, Ordering::Relaxed);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            Ok::<_, anyhow::Error>(7u64)
+        };
+
+        let key = cache.key(Venue::Raydium, "SOL", "USDC", 1.0, 0.1);
+        let (a, b, c) = tokio::join!(
+            cache.get_or_fetch(key.clone(), fetch()),
+            cache.get_or_fetch(key.clone(), fetch()),
+            cache.get_or_fetch(key.clone(), fetch()),
+        );
+        assert_eq!((a?, b?, c?), (7, 7, 7));
+        assert_eq!(fetches.load(Ordering::Relaxed), 1);
+        assert!(cache.in_flight.lock().unwrap().is_empty());
+
+        let fast = QuoteKey {
+            fast_mode: true,
+            ..key.clone()
+        };
+        assert_eq!(cache.get::<u64>(&fast), None);
+        Ok(())
+    }
+}
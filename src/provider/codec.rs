@@ -0,0 +1,144 @@
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use url::form_urlencoded::Serializer;
+
+// Serializes a request to the Trader API's JSON form. The generated types already carry the
+// camelCase field names; unset optional fields are left out instead of being sent as null.
+pub fn to_json<T: Serialize>(request: &T) -> Result<Value> {
+    let mut value =
+        serde_json::to_value(request).map_err(|e| anyhow!("Failed to serialize request: {}", e))?;
+    strip_nulls(&mut value);
+    Ok(value)
+}
+
+// Encodes a request as a URL query string. Repeated fields become repeated keys and nested
+// messages dotted keys, which is how the REST gateway maps query parameters onto requests.
+pub fn to_query<T: Serialize>(request: &T) -> Result<String> {
+    let value = to_json(request)?;
+    let Value::Object(fields) = value else {
+        return Err(anyhow!(
+            "Failed to encode query: expected a message, got {}",
+            value
+        ));
+    };
+
+    let mut serializer = Serializer::new(String::new());
+    append_fields(&mut serializer, "", &fields);
+    Ok(serializer.finish())
+}
+
+pub fn with_query<T: Serialize>(url: &str, request: &T) -> Result<String> {
+    let query = to_query(request)?;
+    if query.is_empty() {
+        return Ok(url.to_string());
+    }
+    Ok(format!("{}?{}", url, query))
+}
+
+fn strip_nulls(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            fields.retain(|_, field| !field.is_null());
+            fields.values_mut().for_each(strip_nulls);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_nulls),
+        _ => {}
+    }
+}
+
+fn append_fields(
+    serializer: &mut Serializer<'_, String>,
+    prefix: &str,
+    fields: &Map<String, Value>,
+) {
+    for (key, value) in fields {
+        if prefix.is_empty() {
+            append_value(serializer, key, value);
+        } else {
+            append_value(serializer, &format!("{}.{}", prefix, key), value);
+        }
+    }
+}
+
+fn append_value(serializer: &mut Serializer<'_, String>, key: &str, value: &Value) {
+    match value {
+        Value::Null => {}
+        Value::String(s) => {
+            serializer.append_pair(key, s);
+        }
+        Value::Array(items) => {
+            for item in items {
+                append_value(serializer, key, item);
+            }
+        }
+        Value::Object(fields) => append_fields(serializer, key, fields),
+        other => {
+            serializer.append_pair(key, &other.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use solana_trader_proto::api;
+
+    #[test]
+    fn test_request_to_json() -> Result<()> {
+        let request = api::GetQuotesRequest {
+            in_token: "SOL".to_string(),
+            out_token: "USDC".to_string(),
+            in_amount: 0.5,
+            slippage: 0.1,
+            limit: 3,
+            projects: vec![api::Project::PRaydium as i32],
+        };
+
+        let value = to_json(&request)?;
+        assert_eq!(value["inToken"], "SOL");
+        assert_eq!(value["outToken"], "USDC");
+        assert_eq!(value["limit"], 3);
+        assert_eq!(value["projects"], json!([api::Project::PRaydium as i32]));
+
+        let value = to_json(&json!({ "a": null, "b": { "c": null, "d": 1 } }))?;
+        assert_eq!(value, json!({ "b": { "d": 1 } }));
+        Ok(())
+    }
+
+    #[test]
+    fn test_request_to_query() -> Result<()> {
+        let request = json!({
+            "inToken": "a b&c",
+            "inAmount": 0.1,
+            "projects": [1, 2],
+            "fastMode": null,
+            "step": { "pool": "x/y" },
+        });
+
+        let query = to_query(&request)?;
+        let mut pairs: Vec<&str> = query.split('&').collect();
+        pairs.sort();
+        assert_eq!(
+            pairs,
+            vec![
+                "inAmount=0.1",
+                "inToken=a+b%26c",
+                "projects=1",
+                "projects=2",
+                "step.pool=x%2Fy"
+            ]
+        );
+        assert_eq!(
+            with_query("https://host/api", &json!({ "tokens": ["A", "B"] }))?,
+            "https://host/api?tokens=A&tokens=B"
+        );
+        assert_eq!(
+            with_query("https://host/api", &json!({}))?,
+            "https://host/api"
+        );
+        assert!(to_query(&json!([1])).is_err());
+        Ok(())
+    }
+}
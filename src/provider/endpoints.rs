@@ -0,0 +1,140 @@
+// Single source for endpoints whose request maps one-to-one onto every transport. Each transport
+// passes its generator macro, which receives one row per endpoint:
+//
+//     method(request type) -> response type {
+//         grpc: ApiClient method, http: verb and path, ws: JSON-RPC method [without "field"], [cached,]
+//     }
+//
+// and emits the client method, so request fields are serialized the same way everywhere
+// instead of being copied field by field. `without` drops a field from the WebSocket params and
+// `cached` sends the call through the client's quote cache, if it has one. Endpoints that take
+// part of the request in the URL path, like `get_orderbook`, are still written per transport.
+// Rows named `*_request` back public methods that predate the table and keep their own
+// signatures, e.g. `get_priority_fee(project, percentile)`; those wrap the generated call.
+macro_rules! unary_endpoints {
+    ($generate:ident) => {
+        $generate! {
+            post_raydium_swap(api::PostRaydiumSwapRequest) -> api::PostRaydiumSwapResponse {
+                grpc: post_raydium_swap, http: POST "/api/v2/raydium/swap", ws: "PostRaydiumSwap",
+            }
+            post_raydium_route_swap(api::PostRaydiumRouteSwapRequest) -> api::PostRaydiumRouteSwapResponse {
+                grpc: post_raydium_route_swap, http: POST "/api/v2/raydium/route-swap", ws: "PostRaydiumRouteSwap",
+            }
+            post_raydium_swap_instructions(api::PostRaydiumSwapInstructionsRequest) -> api::PostRaydiumSwapInstructionsResponse {
+                grpc: post_raydium_swap_instructions, http: POST "/api/v2/raydium/swap-instructions", ws: "PostRaydiumSwapInstructions",
+            }
+            post_raydium_cpmm_swap(api::PostRaydiumCpmmSwapRequest) -> api::PostRaydiumCpmmSwapResponse {
+                grpc: post_raydium_cpmm_swap, http: POST "/api/v2/raydium/cpmm-swap", ws: "PostRaydiumCPMMSwap",
+            }
+            post_raydium_clmm_swap(api::PostRaydiumSwapRequest) -> api::PostRaydiumSwapResponse {
+                grpc: post_raydium_clmm_swap, http: POST "/api/v2/raydium/clmm-swap", ws: "PostRaydiumCLMMSwap",
+            }
+            post_raydium_clmm_route_swap(api::PostRaydiumRouteSwapRequest) -> api::PostRaydiumRouteSwapResponse {
+                grpc: post_raydium_clmm_route_swap, http: POST "/api/v2/raydium/clmm-route-swap", ws: "PostRaydiumCLMMRouteSwap",
+            }
+            // NOTE: Fast mode is not used as of 11/1, breaks the WebSocket endpoint.
+            post_jupiter_swap(api::PostJupiterSwapRequest) -> api::PostJupiterSwapResponse {
+                grpc: post_jupiter_swap, http: POST "/api/v2/jupiter/swap", ws: "PostJupiterSwap" without "fastMode",
+            }
+            post_jupiter_route_swap(api::PostJupiterRouteSwapRequest) -> api::PostJupiterRouteSwapResponse {
+                grpc: post_jupiter_route_swap, http: POST "/api/v2/jupiter/route-swap", ws: "PostJupiterRouteSwap",
+            }
+            post_jupiter_swap_instructions(api::PostJupiterSwapInstructionsRequest) -> api::PostJupiterSwapInstructionsResponse {
+                grpc: post_jupiter_swap_instructions, http: POST "/api/v2/jupiter/swap-instructions", ws: "PostJupiterSwapInstructions",
+            }
+            post_pump_swap(api::PostPumpFunSwapRequest) -> api::PostPumpFunSwapResponse {
+                grpc: post_pump_fun_swap, http: POST "/api/v2/pumpfun/swap", ws: "PostPumpFunSwap",
+            }
+            post_trade_swap(api::TradeSwapRequest) -> api::TradeSwapResponse {
+                grpc: post_trade_swap, http: POST "/api/v2/trade/swap", ws: "PostTradeSwap",
+            }
+            post_route_trade_swap(api::RouteTradeSwapRequest) -> api::TradeSwapResponse {
+                grpc: post_route_trade_swap, http: POST "/api/v2/trade/route-swap", ws: "PostRouteTradeSwap",
+            }
+            post_pump_fun_swap_sol(api::PostPumpFunSwapRequestSol) -> api::PostPumpFunSwapResponse {
+                grpc: post_pump_fun_swap_sol, http: POST "/api/v2/pumpfun/swap-sol", ws: "PostPumpFunSwapSol",
+            }
+            get_raydium_pools(api::GetRaydiumPoolsRequest) -> api::GetRaydiumPoolsResponse {
+                grpc: get_raydium_pools, http: GET "/api/v2/raydium/pools", ws: "GetRaydiumPools",
+            }
+            get_raydium_clmm_pools(api::GetRaydiumClmmPoolsRequest) -> api::GetRaydiumClmmPoolsResponse {
+                grpc: get_raydium_clmm_pools, http: GET "/api/v2/raydium/clmm-pools", ws: "GetRaydiumCLMMPools",
+            }
+            get_raydium_pool_reserve(api::GetRaydiumPoolReserveRequest) -> api::GetRaydiumPoolReserveResponse {
+                grpc: get_raydium_pool_reserve, http: GET "/api/v2/raydium/pool-reserves", ws: "GetRaydiumPoolReserve",
+            }
+            get_server_time(api::GetServerTimeRequest) -> api::GetServerTimeResponse {
+                grpc: get_server_time, http: GET "/api/v1/system/time", ws: "GetServerTime",
+            }
+
+            get_transaction_request(api::GetTransactionRequest) -> api::GetTransactionResponse {
+                grpc: get_transaction, http: GET "/api/v2/transaction", ws: "GetTransaction",
+            }
+            get_recent_block_hash_request(api::GetRecentBlockHashRequest) -> api::GetRecentBlockHashResponse {
+                grpc: get_recent_block_hash, http: GET "/api/v1/system/blockhash", ws: "GetRecentBlockHash",
+            }
+            get_recent_block_hash_v2_request(api::GetRecentBlockHashRequestV2) -> api::GetRecentBlockHashResponseV2 {
+                grpc: get_recent_block_hash_v2, http: GET "/api/v2/system/blockhash", ws: "GetRecentBlockHashV2",
+            }
+            get_rate_limit_request(api::GetRateLimitRequest) -> api::GetRateLimitResponse {
+                grpc: get_rate_limit, http: GET "/api/v2/rate-limit", ws: "GetRateLimit",
+            }
+            get_account_balance_request(api::GetAccountBalanceRequest) -> api::GetAccountBalanceResponse {
+                grpc: get_account_balance, http: GET "/api/v2/balance", ws: "GetAccountBalance",
+            }
+            get_account_balance_v2_request(api::GetAccountBalanceRequest) -> api::GetAccountBalanceResponse {
+                grpc: get_account_balance_v2, http: GET "/api/v2/balance", ws: "GetAccountBalanceV2",
+            }
+            get_priority_fee_request(api::GetPriorityFeeRequest) -> api::GetPriorityFeeResponse {
+                grpc: get_priority_fee, http: GET "/api/v2/system/priority-fee", ws: "GetPriorityFee",
+            }
+            get_priority_fee_by_program_request(api::GetPriorityFeeByProgramRequest) -> api::GetPriorityFeeByProgramResponse {
+                grpc: get_priority_fee_by_program, http: GET "/api/v2/system/priority-fee-by-program", ws: "GetPriorityFeeByProgram",
+            }
+            get_token_accounts_request(api::GetTokenAccountsRequest) -> api::GetTokenAccountsResponse {
+                grpc: get_token_accounts, http: GET "/api/v1/account/token-accounts", ws: "GetTokenAccounts",
+            }
+
+            get_raydium_quotes(api::GetRaydiumQuotesRequest) -> api::GetRaydiumQuotesResponse {
+                grpc: get_raydium_quotes, http: GET "/api/v2/raydium/quotes", ws: "GetRaydiumQuotes", cached,
+            }
+            get_raydium_cpmm_quotes(api::GetRaydiumCpmmQuotesRequest) -> api::GetRaydiumCpmmQuotesResponse {
+                grpc: get_raydium_cpmm_quotes, http: GET "/api/v2/raydium/cpmm-quotes", ws: "GetRaydiumCPMMQuotes", cached,
+            }
+            get_raydium_clmm_quotes(api::GetRaydiumClmmQuotesRequest) -> api::GetRaydiumClmmQuotesResponse {
+                grpc: get_raydium_clmm_quotes, http: GET "/api/v2/raydium/clmm-quotes", ws: "GetRaydiumCLMMQuotes", cached,
+            }
+            get_pump_fun_quotes(api::GetPumpFunQuotesRequest) -> api::GetPumpFunQuotesResponse {
+                grpc: get_pump_fun_quotes, http: GET "/api/v2/pumpfun/quotes", ws: "GetPumpFunQuotes", cached,
+            }
+            // NOTE: Fast mode is not used as of 11/1, breaks the WebSocket endpoint.
+            get_jupiter_quotes(api::GetJupiterQuotesRequest) -> api::GetJupiterQuotesResponse {
+                grpc: get_jupiter_quotes, http: GET "/api/v2/jupiter/quotes", ws: "GetJupiterQuotes" without "fastMode", cached,
+            }
+            // Projects go out as repeated `projects=` keys, the GetQuotesRequest field name the REST
+            // gateway binds query parameters to. Earlier releases sent `project=`, which names no field.
+            get_quotes_request(api::GetQuotesRequest) -> api::GetQuotesResponse {
+                grpc: get_quotes, http: GET "/api/v1/market/quote", ws: "GetQuotes",
+            }
+            get_raydium_prices_request(api::GetRaydiumPricesRequest) -> api::GetRaydiumPricesResponse {
+                grpc: get_raydium_prices, http: GET "/api/v2/raydium/prices", ws: "GetRaydiumPrices",
+            }
+            get_jupiter_prices_request(api::GetJupiterPricesRequest) -> api::GetJupiterPricesResponse {
+                grpc: get_jupiter_prices, http: GET "/api/v2/jupiter/prices", ws: "GetJupiterPrices",
+            }
+        }
+    };
+}
+
+// Awaits a generated call's fetch, through the quote cache for `cached` rows.
+macro_rules! through_cache {
+    ($cache:expr, $request:expr, $fetch:expr) => {
+        $fetch.await
+    };
+    ($cache:expr, $request:expr, $fetch:expr, cached) => {
+        $crate::provider::cache::cached_request($cache, $request, $fetch).await
+    };
+}
+
+pub(crate) use through_cache;
+pub(crate) use unary_endpoints;
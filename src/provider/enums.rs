@@ -0,0 +1,570 @@
+use std::fmt;
+
+use serde::de::value::{BorrowedStrDeserializer, StringDeserializer};
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess,
+    Unexpected, VariantAccess, Visitor,
+};
+use serde::Deserialize;
+use serde_json::{Error, Value};
+use solana_trader_proto::{api, common};
+
+// Proto enum fields are generated as plain `i32`s, while the HTTP and WebSocket endpoints send
+// them by name. Value names are unique across the api and common packages, so a name maps to
+// exactly one number.
+macro_rules! enum_value {
+    ($($enum:ty),* $(,)?) => {
+        pub fn enum_value(name: &str) -> Option<i32> {
+            None$(.or_else(|| <$enum>::from_str_name(name).map(|value| value as i32)))*
+                .or_else(|| alias_value(None, name))
+        }
+    };
+}
+
+// Names some endpoints send instead of the proto value names, with the field they are sent in.
+const ENUM_ALIASES: &[(&str, &str, i32)] = &[
+    ("infinity", "INF", common::Infinity::InfPositive as i32),
+    ("infinity", "INF_NEG", common::Infinity::InfNegative as i32),
+];
+
+fn alias_value(field: Option<&str>, name: &str) -> Option<i32> {
+    ENUM_ALIASES
+        .iter()
+        .find(|(of, alias, _)| (field.is_none() || field == Some(*of)) && *alias == name)
+        .map(|(_, _, value)| *value)
+}
+
+enum_value!(
+    api::MarketStatus,
+    api::Side,
+    api::OrderStatus,
+    api::Direction,
+    api::SubmitStrategy,
+    api::Step,
+    api::Project,
+    common::OrderType,
+    common::PerpOrderType,
+    common::PerpPositionSide,
+    common::PostOnlyParams,
+    common::MarginContract,
+    common::PerpContract,
+    common::PerpCollateralType,
+    common::PerpCollateralToken,
+    common::Infinity,
+);
+
+// The declared enum of every enum-typed field in the api and common packages, by JSON name.
+macro_rules! field_enums {
+    ($($field:literal => $($enum:ty)|+),* $(,)?) => {
+        fn field_enum(key: &str) -> Option<Field> {
+            match key {
+                $($field => Some(Field::Enum($field, |name| {
+                    None$(.or_else(|| <$enum>::from_str_name(name).map(|value| value as i32)))+
+                })),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+field_enums!(
+    "project" => api::Project,
+    "projects" => api::Project,
+    "side" => api::Side,
+    "direction" => api::Direction,
+    "status" => api::MarketStatus | api::OrderStatus,
+    "orderStatus" => api::OrderStatus,
+    "submitStrategy" => api::SubmitStrategy,
+    "type" => common::OrderType,
+    "types" => common::OrderType,
+    "infinity" => common::Infinity,
+);
+
+// Which names an `i32` may be given as: any enum's outside of a struct, the declared enum's in
+// an enum-typed field, and none in any other field.
+#[derive(Clone, Copy)]
+enum Field {
+    Any,
+    Enum(&'static str, fn(&str) -> Option<i32>),
+    Plain,
+}
+
+impl Field {
+    fn of(key: &str) -> Field {
+        field_enum(key).unwrap_or(Field::Plain)
+    }
+
+    fn value(self, name: &str) -> Option<i32> {
+        match self {
+            Field::Any => enum_value(name),
+            Field::Enum(field, value) => value(name).or_else(|| alias_value(Some(field), name)),
+            Field::Plain => None,
+        }
+    }
+}
+
+// Deserializes a response, accepting enum names wherever the target type expects an `i32`.
+pub fn from_value<T: DeserializeOwned>(value: Value) -> Result<T, Error> {
+    T::deserialize(Lenient(value, Field::Any))
+}
+
+// Same as `from_value`, but decodes straight from the text in a single pass.
+pub fn from_str<'a, T: Deserialize<'a>>(s: &'a str) -> Result<T, Error> {
+    let mut deserializer = serde_json::Deserializer::from_str(s);
+    let value = T::deserialize(Lenient(&mut deserializer, Field::Any))?;
+    deserializer.end()?;
+    Ok(value)
+}
+
+// Wraps any deserializer so that `i32` fields, at any depth, also accept the names of their
+// declared enum. Everything else is passed through to the inner deserializer untouched.
+struct Lenient<D>(D, Field);
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $ty:ty),*)),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, D::Error> {
+                self.0.$method($($arg,)* Wrap(visitor, self.1))
+            }
+        )*
+    };
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for Lenient<D> {
+    type Error = D::Error;
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+        self.0.deserialize_any(EnumOrI32(visitor, self.1))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.0.is_human_readable()
+    }
+
+    forward_deserialize!(
+        deserialize_any(),
+        deserialize_bool(),
+        deserialize_i8(),
+        deserialize_i16(),
+        deserialize_i64(),
+        deserialize_i128(),
+        deserialize_u8(),
+        deserialize_u16(),
+        deserialize_u32(),
+        deserialize_u64(),
+        deserialize_u128(),
+        deserialize_f32(),
+        deserialize_f64(),
+        deserialize_char(),
+        deserialize_str(),
+        deserialize_string(),
+        deserialize_bytes(),
+        deserialize_byte_buf(),
+        deserialize_option(),
+        deserialize_unit(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_seq(),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map(),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_identifier(),
+        deserialize_ignored_any(),
+    );
+}
+
+struct EnumOrI32<V>(V, Field);
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for EnumOrI32<V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.1 {
+            Field::Any => f.write_str("an enum name or i32"),
+            Field::Enum(field, _) => write!(f, "an enum name for `{}` or i32", field),
+            Field::Plain => f.write_str("an i32"),
+        }
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<V::Value, E> {
+        let v = i32::try_from(v).map_err(|_| E::invalid_value(Unexpected::Signed(v), &self))?;
+        self.0.visit_i32(v)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<V::Value, E> {
+        let v = i32::try_from(v).map_err(|_| E::invalid_value(Unexpected::Unsigned(v), &self))?;
+        self.0.visit_i32(v)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<V::Value, E> {
+        match v.parse::<i32>().ok().or_else(|| self.1.value(v)) {
+            Some(value) => self.0.visit_i32(value),
+            None => Err(E::invalid_value(Unexpected::Str(v), &self)),
+        }
+    }
+}
+
+// Carries the wrapper down into nested values: every visitor, seed and access handed to the
+// inner deserializer is wrapped so its own values go through `Lenient` again. Map values take
+// their field from the key just read; everything else keeps the field it was found in.
+struct Wrap<T>(T, Field);
+
+macro_rules! forward_visit {
+    ($($method:ident($ty:ty)),* $(,)?) => {
+        $(
+            fn $method<E: de::Error>(self, v: $ty) -> Result<V::Value, E> {
+                self.0.$method(v)
+            }
+        )*
+    };
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for Wrap<V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.expecting(f)
+    }
+
+    forward_visit!(
+        visit_bool(bool),
+        visit_i8(i8),
+        visit_i16(i16),
+        visit_i32(i32),
+        visit_i64(i64),
+        visit_i128(i128),
+        visit_u8(u8),
+        visit_u16(u16),
+        visit_u32(u32),
+        visit_u64(u64),
+        visit_u128(u128),
+        visit_f32(f32),
+        visit_f64(f64),
+        visit_char(char),
+        visit_str(&str),
+        visit_borrowed_str(&'de str),
+        visit_string(String),
+        visit_bytes(&[u8]),
+        visit_borrowed_bytes(&'de [u8]),
+        visit_byte_buf(Vec<u8>),
+    );
+
+    fn visit_none<E: de::Error>(self) -> Result<V::Value, E> {
+        self.0.visit_none()
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<V::Value, E> {
+        self.0.visit_unit()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<V::Value, D::Error> {
+        self.0.visit_some(Lenient(deserializer, self.1))
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<V::Value, D::Error> {
+        self.0.visit_newtype_struct(Lenient(deserializer, self.1))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<V::Value, A::Error> {
+        self.0.visit_seq(Wrap(seq, self.1))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<V::Value, A::Error> {
+        self.0.visit_map(Wrap(map, Field::Plain))
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<V::Value, A::Error> {
+        self.0.visit_enum(Wrap(data, self.1))
+    }
+}
+
+impl<'de, S: DeserializeSeed<'de>> DeserializeSeed<'de> for Wrap<S> {
+    type Value = S::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<S::Value, D::Error> {
+        self.0.deserialize(Lenient(deserializer, self.1))
+    }
+}
+
+impl<'de, A: SeqAccess<'de>> SeqAccess<'de> for Wrap<A> {
+    type Error = A::Error;
+
+    fn next_element_seed<S: DeserializeSeed<'de>>(
+        &mut self,
+        seed: S,
+    ) -> Result<Option<S::Value>, A::Error> {
+        self.0.next_element_seed(Wrap(seed, self.1))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for Wrap<A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, A::Error> {
+        let Some(key) = self.0.next_key::<Key>()? else {
+            return Ok(None);
+        };
+        match key {
+            Key::Borrowed(key) => {
+                self.1 = Field::of(key);
+                seed.deserialize(BorrowedStrDeserializer::new(key))
+                    .map(Some)
+            }
+            Key::Owned(key) => {
+                self.1 = Field::of(&key);
+                seed.deserialize(StringDeserializer::new(key)).map(Some)
+            }
+        }
+    }
+
+    fn next_value_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<S::Value, A::Error> {
+        self.0.next_value_seed(Wrap(seed, self.1))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
+
+impl<'de, A: EnumAccess<'de>> EnumAccess<'de> for Wrap<A> {
+    type Error = A::Error;
+    type Variant = Wrap<A::Variant>;
+
+    fn variant_seed<S: DeserializeSeed<'de>>(
+        self,
+        seed: S,
+    ) -> Result<(S::Value, Self::Variant), A::Error> {
+        let field = self.1;
+        let (value, variant) = self.0.variant_seed(seed)?;
+        Ok((value, Wrap(variant, field)))
+    }
+}
+
+impl<'de, A: VariantAccess<'de>> VariantAccess<'de> for Wrap<A> {
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), A::Error> {
+        self.0.unit_variant()
+    }
+
+    fn newtype_variant_seed<S: DeserializeSeed<'de>>(self, seed: S) -> Result<S::Value, A::Error> {
+        self.0.newtype_variant_seed(Wrap(seed, self.1))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, A::Error> {
+        self.0.tuple_variant(len, Wrap(visitor, self.1))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, A::Error> {
+        self.0.struct_variant(fields, Wrap(visitor, self.1))
+    }
+}
+
+// A map key, read ahead of its value so the value knows which field it belongs to. Keys are
+// borrowed from the input whenever the inner deserializer allows it.
+enum Key<'de> {
+    Borrowed(&'de str),
+    Owned(String),
+}
+
+impl<'de> Deserialize<'de> for Key<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(KeyVisitor)
+    }
+}
+
+struct KeyVisitor;
+
+impl<'de> Visitor<'de> for KeyVisitor {
+    type Value = Key<'de>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a string key")
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Key<'de>, E> {
+        Ok(Key::Borrowed(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Key<'de>, E> {
+        Ok(Key::Owned(v.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Key<'de>, E> {
+        Ok(Key::Owned(v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    struct Quote {
+        project: i32,
+        projects: Vec<i32>,
+        side: Option<i32>,
+        trade_fee_rate: String,
+        price_impact_percent: Impact,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Impact {
+        percent: f64,
+        infinity: i32,
+    }
+
+    #[test]
+    fn test_enum_value() {
+        assert_eq!(enum_value("P_JUPITER"), Some(api::Project::PJupiter as i32));
+        assert_eq!(
+            enum_value("P_SUBMIT_ALL"),
+            Some(api::SubmitStrategy::PSubmitAll as i32)
+        );
+        assert_eq!(enum_value("S_ASK"), Some(api::Side::SAsk as i32));
+        assert_eq!(
+            enum_value("OT_LIMIT"),
+            Some(common::OrderType::OtLimit as i32)
+        );
+        assert_eq!(
+            enum_value("INF_NEGATIVE"),
+            Some(common::Infinity::InfNegative as i32)
+        );
+        assert_eq!(
+            enum_value("INF"),
+            Some(common::Infinity::InfPositive as i32)
+        );
+        assert_eq!(
+            enum_value("INF_NEG"),
+            Some(common::Infinity::InfNegative as i32)
+        );
+        assert_eq!(enum_value("P_NOT_A_PROJECT"), None);
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Conversion {
+        project: i32,
+        trade_fee_rate: String,
+        nested: Nested,
+        array: Vec<Nested>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Nested {
+        project: i32,
+        price_impact_percent: Option<Impact>,
+    }
+
+    #[test]
+    fn test_conversions() -> Result<(), Error> {
+        let value: Conversion = from_value(json!({
+            "project": "P_JUPITER",
+            "tradeFeeRate": "1000",
+            "nested": {
+                "project": "P_RAYDIUM",
+                "priceImpactPercent": { "percent": 0.0, "infinity": "INF_NOT" }
+            },
+            "array": [
+                { "project": "P_OPENBOOK", "priceImpactPercent": { "percent": 0.0, "infinity": "INF" } },
+                { "project": "P_OPENBOOK", "priceImpactPercent": { "percent": 0.0, "infinity": "INF_NEG" } }
+            ]
+        }))?;
+
+        let infinity = |nested: &Nested| nested.price_impact_percent.as_ref().map(|i| i.infinity);
+        assert_eq!(value.project, 2);
+        assert_eq!(value.trade_fee_rate, "1000");
+        assert_eq!(value.nested.project, 3);
+        assert_eq!(infinity(&value.nested), Some(0));
+        assert_eq!(value.array[0].project, 5);
+        assert_eq!(infinity(&value.array[0]), Some(1));
+        assert_eq!(infinity(&value.array[1]), Some(2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_value_accepts_names_and_numbers() -> Result<(), Error> {
+        let quote: Quote = from_value(json!({
+            "project": "P_JUPITER",
+            "projects": ["P_RAYDIUM", 5, "2"],
+            "side": "S_BID",
+            "tradeFeeRate": "1000",
+            "priceImpactPercent": { "percent": 0.5, "infinity": "INF_POSITIVE" },
+        }))?;
+
+        assert_eq!(
+            quote,
+            Quote {
+                project: api::Project::PJupiter as i32,
+                projects: vec![3, 5, 2],
+                side: Some(api::Side::SBid as i32),
+                trade_fee_rate: "1000".to_string(),
+                price_impact_percent: Impact {
+                    percent: 0.5,
+                    infinity: common::Infinity::InfPositive as i32,
+                },
+            }
+        );
+
+        let quote: Quote = from_str(
+            r#"{"project": 3, "projects": [], "side": null, "tradeFeeRate": "1",
+                "priceImpactPercent": {"percent": 1, "infinity": 0}}"#,
+        )?;
+        assert_eq!(quote.side, None);
+        assert_eq!(quote.price_impact_percent.percent, 1.0);
+
+        assert!(from_value::<Quote>(json!({ "project": "P_NOT_A_PROJECT" })).is_err());
+        Ok(())
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Token {
+        decimals: i32,
+        side: i32,
+    }
+
+    #[test]
+    fn test_names_resolve_against_the_fields_enum() -> Result<(), Error> {
+        let err = from_value::<Quote>(json!({
+            "project": "S_BID",
+            "projects": [],
+            "side": null,
+            "tradeFeeRate": "1",
+            "priceImpactPercent": { "percent": 0.0, "infinity": "INF" },
+        }))
+        .unwrap_err();
+        assert!(err.to_string().contains("`project`"), "{}", err);
+
+        let err = from_str::<Quote>(
+            r#"{"project": 3, "projects": ["INF"], "side": null, "tradeFeeRate": "1",
+                "priceImpactPercent": {"percent": 1, "infinity": 0}}"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("`projects`"), "{}", err);
+
+        assert!(from_value::<Token>(json!({ "decimals": "P_JUPITER", "side": 0 })).is_err());
+        let token: Token = from_value(json!({ "decimals": "6", "side": "S_ASK" }))?;
+        assert_eq!(token.decimals, 6);
+        assert_eq!(token.side, api::Side::SAsk as i32);
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use anyhow::{anyhow, Result};
+use solana_trader_proto::api;
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::Channel;
+use tonic::Request;
+
+use super::{AuthInterceptor, GrpcClient};
+use crate::provider::endpoints::{through_cache, unary_endpoints};
+
+pub(super) type Client = api::api_client::ApiClient<InterceptedService<Channel, AuthInterceptor>>;
+
+macro_rules! grpc_endpoints {
+    ($(
+        $name:ident($request:ty) -> $response:ty {
+            grpc: $rpc:ident, http: $verb:ident $path:literal, ws: $method:literal $(without $omit:literal)?, $($cached:ident,)?
+        }
+    )*) => {
+        impl GrpcClient {
+            $(
+                pub async fn $name(&mut self, request: &$request) -> Result<$response> {
+                    through_cache!(
+                        self.quote_cache.clone(),
+                        request,
+                        rpc::$name(self.client.clone(), request)
+                        $(, $cached)?
+                    )
+                }
+            )*
+        }
+
+        // The calls run on a cloned client so that `get_best_quotes` can issue several of them
+        // concurrently.
+        pub(super) mod rpc {
+            use super::*;
+
+            $(
+                pub(crate) async fn $name(mut client: Client, request: &$request) -> Result<$response> {
+                    let response = client
+                        .$rpc(Request::new(request.clone()))
+                        .await
+                        .map_err(|e| anyhow!("{} error: {}", stringify!($rpc), e))?;
+
+                    Ok(response.into_inner())
+                }
+            )*
+        }
+    };
+}
+
+unary_endpoints!(grpc_endpoints);
@@ -1,5 +1,7 @@
+mod endpoints;
 pub mod general;
 pub mod quote;
+pub mod resilient;
 pub mod stream;
 pub mod swap;
 
@@ -9,20 +11,24 @@ use rustls::crypto::CryptoProvider;
 use solana_sdk::pubkey::Pubkey;
 use solana_trader_proto::api;
 use std::collections::HashMap;
+use std::sync::Arc;
 use tonic::service::Interceptor;
 use tonic::transport::ClientTlsConfig;
 use tonic::{
     metadata::MetadataValue, service::interceptor::InterceptedService, transport::Channel,
 };
 
-use crate::common::signing::{sign_transaction, SubmitParams};
+use crate::common::package::SignedTransactionPackage;
+use crate::common::policy::{lookup_table_addresses, SigningPolicy};
+use crate::common::signer::TransactionSigner;
+use crate::common::signing::{sign_all_for_submit, DryRunResult, SignedTransaction, SubmitParams};
 use crate::common::{get_base_url_from_env, grpc_endpoint, BaseConfig};
 use solana_sdk::signature::Keypair;
-use solana_trader_proto::api::{
-    GetRecentBlockHashRequestV2, PostSubmitRequest, TransactionMessage,
-};
+use solana_trader_proto::api::GetRecentBlockHashRequestV2;
 
-use super::utils::IntoTransactionMessage;
+use super::cache::QuoteCache;
+use super::shared::SharedStreams;
+use super::utils::{build_submit_request, IntoTransactionMessage, SubmitRequest};
 
 #[derive(Clone)]
 pub struct AuthInterceptor {
@@ -64,6 +70,10 @@ pub struct GrpcClient {
     pub client: api::api_client::ApiClient<InterceptedService<Channel, AuthInterceptor>>,
     pub keypair: Option<Keypair>,
     pub public_key: Option<Pubkey>,
+    pub signer: Option<Arc<dyn TransactionSigner>>,
+    pub signing_policy: Option<SigningPolicy>,
+    pub quote_cache: Option<Arc<QuoteCache>>,
+    shared: SharedStreams,
 }
 
 impl GrpcClient {
@@ -71,6 +81,28 @@ impl GrpcClient {
         Ok(self.keypair.as_ref().unwrap())
     }
 
+    pub fn get_signer(&self) -> Result<Arc<dyn TransactionSigner>> {
+        self.signer
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("No signer configured"))
+    }
+
+    pub fn with_signer(mut self, signer: Arc<dyn TransactionSigner>) -> Self {
+        self.public_key = Some(signer.public_key());
+        self.signer = Some(signer);
+        self
+    }
+
+    pub fn with_signing_policy(mut self, policy: SigningPolicy) -> Self {
+        self.signing_policy = Some(policy);
+        self
+    }
+
+    pub fn with_quote_cache(mut self, cache: Arc<QuoteCache>) -> Self {
+        self.quote_cache = Some(cache);
+        self
+    }
+
     pub async fn new(endpoint: Option<String>) -> Result<Self> {
         let base = BaseConfig::try_from_env()?;
         let (default_base_url, secure) = get_base_url_from_env();
@@ -98,6 +130,10 @@ impl GrpcClient {
             client,
             public_key: base.public_key,
             keypair: base.keypair,
+            signer: base.signer,
+            signing_policy: None,
+            quote_cache: None,
+            shared: SharedStreams::default(),
         })
     }
 
@@ -107,76 +143,115 @@ impl GrpcClient {
         submit_opts: SubmitParams,
         use_bundle: bool,
     ) -> Result<Vec<String>> {
-        let block_hash = self
-            .client
-            .get_recent_block_hash_v2(GetRecentBlockHashRequestV2 { offset: 0 })
-            .await?
-            .into_inner()
-            .block_hash;
-
-        let keypair = self.get_keypair()?;
-
-        if txs.len() == 1 {
-            let signed_tx = sign_transaction(&txs[0], keypair, block_hash).await?;
-
-            let req = PostSubmitRequest {
-                transaction: Some(TransactionMessage {
-                    content: signed_tx.content,
-                    is_cleanup: signed_tx.is_cleanup,
-                }),
-                skip_pre_flight: submit_opts.skip_pre_flight,
-                front_running_protection: Some(submit_opts.front_running_protection),
-                use_staked_rp_cs: Some(submit_opts.use_staked_rpcs),
-                fast_best_effort: Some(submit_opts.fast_best_effort),
-                tip: None,
-                allow_back_run: submit_opts.allow_back_run,
-                revenue_address: submit_opts.revenue_address,
-            };
-
-            let signature = self
-                .client
-                .post_submit_v2(req)
-                .await?
-                .into_inner()
-                .signature;
-
-            return Ok(vec![signature]);
-        }
+        let signed_txs = self.sign_transactions(&txs, None, &submit_opts).await?;
+
+        self.submit_signed(signed_txs, submit_opts, use_bundle)
+            .await
+    }
+
+    // Like `sign_and_submit`, with the lookup tables the transactions load accounts from so that
+    // the signing policy can resolve them.
+    pub async fn sign_and_submit_with_lookup_tables<T: IntoTransactionMessage + Clone>(
+        &mut self,
+        txs: Vec<T>,
+        address_lookup_tables: &HashMap<String, api::PublicKeys>,
+        submit_opts: SubmitParams,
+        use_bundle: bool,
+    ) -> Result<Vec<String>> {
+        let lookup_tables = lookup_table_addresses(address_lookup_tables);
+        let signed_txs = self
+            .sign_transactions(&txs, Some(&lookup_tables), &submit_opts)
+            .await?;
+
+        self.submit_signed(signed_txs, submit_opts, use_bundle)
+            .await
+    }
+
+    pub async fn sign_and_submit_dry_run<T: IntoTransactionMessage + Clone>(
+        &mut self,
+        txs: Vec<T>,
+        submit_opts: SubmitParams,
+        use_bundle: bool,
+    ) -> Result<DryRunResult> {
+        let signed_txs = self.sign_transactions(&txs, None, &submit_opts).await?;
+
+        let request = build_submit_request(&signed_txs, &submit_opts, use_bundle);
+        let method = match request {
+            SubmitRequest::Single(_) => "PostSubmitV2",
+            SubmitRequest::Batch(_) => "PostSubmitBatchV2",
+        };
+
+        DryRunResult::new(signed_txs, method, request.to_json()?)
+    }
+
+    async fn sign_transactions<T: IntoTransactionMessage + Clone>(
+        &mut self,
+        txs: &[T],
+        lookup_tables: Option<&HashMap<String, Vec<String>>>,
+        submit_opts: &SubmitParams,
+    ) -> Result<Vec<SignedTransaction>> {
+        let signer = self.get_signer()?;
 
-        let mut entries = Vec::with_capacity(txs.len());
-        for tx in txs {
-            let signed_tx = sign_transaction(&tx, keypair, block_hash.clone()).await?;
-
-            let entry = api::PostSubmitRequestEntry {
-                transaction: Some(TransactionMessage {
-                    content: signed_tx.content,
-                    is_cleanup: signed_tx.is_cleanup,
-                }),
-                skip_pre_flight: submit_opts.skip_pre_flight,
-            };
-            entries.push(entry);
+        // checked first so that a rejected batch costs no blockhash request
+        if let Some(policy) = &self.signing_policy {
+            policy.check_transactions(txs, &signer.public_key(), lookup_tables)?;
         }
 
-        let batch_request = api::PostSubmitBatchRequest {
-            entries,
-            use_bundle: Some(use_bundle),
-            submit_strategy: submit_opts.submit_strategy.into(),
-            front_running_protection: Some(submit_opts.front_running_protection),
+        let block_hash = match &submit_opts.durable_nonce {
+            Some(nonce) => nonce.nonce_hash.to_string(),
+            None => {
+                self.client
+                    .get_recent_block_hash_v2(GetRecentBlockHashRequestV2 { offset: 0 })
+                    .await?
+                    .into_inner()
+                    .block_hash
+            }
         };
 
-        let response = self
-            .client
-            .post_submit_batch_v2(batch_request)
-            .await?
-            .into_inner();
+        sign_all_for_submit(txs, signer.as_ref(), &block_hash, submit_opts).await
+    }
+
+    pub async fn submit_signed_package(
+        &mut self,
+        package: SignedTransactionPackage,
+    ) -> Result<Vec<String>> {
+        self.submit_signed(
+            package.transactions,
+            package.submit_opts,
+            package.use_bundle,
+        )
+        .await
+    }
+
+    pub async fn submit_signed(
+        &mut self,
+        signed_txs: Vec<SignedTransaction>,
+        submit_opts: SubmitParams,
+        use_bundle: bool,
+    ) -> Result<Vec<String>> {
+        match build_submit_request(&signed_txs, &submit_opts, use_bundle) {
+            SubmitRequest::Single(req) => {
+                let signature = self
+                    .client
+                    .post_submit_v2(req)
+                    .await?
+                    .into_inner()
+                    .signature;
+
+                Ok(vec![signature])
+            }
+            SubmitRequest::Batch(req) => {
+                let response = self.client.post_submit_batch_v2(req).await?.into_inner();
 
-        let signatures = response
-            .transactions
-            .into_iter()
-            .filter(|entry| entry.submitted)
-            .map(|entry| entry.signature)
-            .collect();
+                let signatures = response
+                    .transactions
+                    .into_iter()
+                    .filter(|entry| entry.submitted)
+                    .map(|entry| entry.signature)
+                    .collect();
 
-        Ok(signatures)
+                Ok(signatures)
+            }
+        }
     }
 }
@@ -1,118 +1,127 @@
 use anyhow::Result;
+use futures_util::Stream;
 use solana_trader_proto::api;
-use tonic::Request;
+use tokio::time::timeout;
 
+use crate::provider::aggregator::{AggregatedQuotes, BestQuoteRequest, Venue};
+use crate::provider::cache::cached_request;
+use crate::provider::polling::{poll_stream, PollInterval};
+
+use super::endpoints::rpc;
 use super::GrpcClient;
 
 impl GrpcClient {
-    pub async fn get_raydium_quotes(
-        &mut self,
-        request: &api::GetRaydiumQuotesRequest,
-    ) -> Result<api::GetRaydiumQuotesResponse> {
-        let response = self
-            .client
-            .get_raydium_quotes(Request::new(request.clone()))
-            .await
-            .map_err(|e| anyhow::anyhow!("GetRaydiumQuotes error: {}", e))?;
-
-        Ok(response.into_inner())
-    }
-
-    pub async fn get_raydium_cpmm_quotes(
-        &mut self,
-        request: &api::GetRaydiumCpmmQuotesRequest,
-    ) -> Result<api::GetRaydiumCpmmQuotesResponse> {
-        let response = self
-            .client
-            .get_raydium_cpmm_quotes(Request::new(request.clone()))
-            .await
-            .map_err(|e| anyhow::anyhow!("GetRaydiumCPMMQuotes error: {}", e))?;
-
-        Ok(response.into_inner())
-    }
-
-    pub async fn get_raydium_clmm_quotes(
-        &mut self,
-        request: &api::GetRaydiumClmmQuotesRequest,
-    ) -> Result<api::GetRaydiumClmmQuotesResponse> {
-        let response = self
-            .client
-            .get_raydium_clmm_quotes(Request::new(request.clone()))
-            .await
-            .map_err(|e| anyhow::anyhow!("GetRaydiumCLMMQuotes error: {}", e))?;
-
-        Ok(response.into_inner())
-    }
-
-    pub async fn get_pump_fun_quotes(
-        &mut self,
-        request: &api::GetPumpFunQuotesRequest,
-    ) -> Result<api::GetPumpFunQuotesResponse> {
-        let response = self
-            .client
-            .get_pump_fun_quotes(Request::new(request.clone()))
-            .await
-            .map_err(|e| anyhow::anyhow!("GetPumpFunQuotes error: {}", e))?;
-
-        Ok(response.into_inner())
-    }
-
-    // NOTE: Fast mode is not used as of 11/1/24, breaks the endpoint.
-    pub async fn get_jupiter_quotes(
-        &mut self,
-        request: &api::GetJupiterQuotesRequest,
-    ) -> Result<api::GetJupiterQuotesResponse> {
-        let req = Request::new(request.clone());
-
-        let response = self
-            .client
-            .get_jupiter_quotes(req)
-            .await
-            .map_err(|e| anyhow::anyhow!("GetJupiterQuotes error: {}", e))?;
-
-        Ok(response.into_inner())
-    }
-
     pub async fn get_quotes(
         &mut self,
         request: &api::GetQuotesRequest,
     ) -> Result<api::GetQuotesResponse> {
-        let response = self
-            .client
-            .get_quotes(Request::new(request.clone()))
-            .await
-            .map_err(|e| anyhow::anyhow!("GetQuotes error: {}", e))?;
-
-        Ok(response.into_inner())
+        self.get_quotes_request(request).await
     }
 
     pub async fn get_raydium_prices(
         &mut self,
         tokens: Vec<String>,
     ) -> Result<api::GetRaydiumPricesResponse> {
-        let request = Request::new(api::GetRaydiumPricesRequest { tokens });
-
-        let response = self
-            .client
-            .get_raydium_prices(request)
+        self.get_raydium_prices_request(&api::GetRaydiumPricesRequest { tokens })
             .await
-            .map_err(|e| anyhow::anyhow!("GetRaydiumPrices error: {}", e))?;
-
-        Ok(response.into_inner())
     }
 
     pub async fn get_jupiter_prices(
         &mut self,
         tokens: Vec<String>,
     ) -> Result<api::GetJupiterPricesResponse> {
-        let request = Request::new(api::GetJupiterPricesRequest { tokens });
-
-        let response = self
-            .client
-            .get_jupiter_prices(request)
+        self.get_jupiter_prices_request(&api::GetJupiterPricesRequest { tokens })
             .await
-            .map_err(|e| anyhow::anyhow!("GetJupiterPrices error: {}", e))?;
+    }
+
+    // Polls GetQuotes on a cloned channel, yielding only changed responses. The stream borrows
+    // the client like the HTTP and WebSocket ones do.
+    pub fn quote_stream(
+        &self,
+        request: &api::GetQuotesRequest,
+        interval: impl Into<PollInterval>,
+    ) -> impl Stream<Item = Result<api::GetQuotesResponse>> + Unpin + '_ {
+        let client = self.client.clone();
+        let request = request.clone();
+
+        poll_stream(interval.into(), move || {
+            let client = client.clone();
+            let request = request.clone();
+            async move { rpc::get_quotes_request(client, &request).await }
+        })
+    }
 
-        Ok(response.into_inner())
+    // Queries every venue concurrently on cloned channels, through the quote cache like the
+    // single-venue methods; venues that fail or miss the deadline are reported in `failures`
+    // instead of failing the whole call.
+    pub async fn get_best_quotes(&self, request: &BestQuoteRequest) -> Result<AggregatedQuotes> {
+        let deadline = request.deadline;
+        let cache = &self.quote_cache;
+        let (raydium_req, cpmm_req, clmm_req, jupiter_req) = (
+            request.raydium(),
+            request.raydium_cpmm(),
+            request.raydium_clmm(),
+            request.jupiter(),
+        );
+
+        let (raydium, cpmm, clmm, jupiter, pump_fun) = tokio::join!(
+            timeout(
+                deadline,
+                cached_request(
+                    cache.clone(),
+                    &raydium_req,
+                    rpc::get_raydium_quotes(self.client.clone(), &raydium_req),
+                )
+            ),
+            timeout(
+                deadline,
+                cached_request(
+                    cache.clone(),
+                    &cpmm_req,
+                    rpc::get_raydium_cpmm_quotes(self.client.clone(), &cpmm_req),
+                )
+            ),
+            timeout(
+                deadline,
+                cached_request(
+                    cache.clone(),
+                    &clmm_req,
+                    rpc::get_raydium_clmm_quotes(self.client.clone(), &clmm_req),
+                )
+            ),
+            timeout(
+                deadline,
+                cached_request(
+                    cache.clone(),
+                    &jupiter_req,
+                    rpc::get_jupiter_quotes(self.client.clone(), &jupiter_req),
+                )
+            ),
+            async {
+                let req = request.pump_fun()?;
+                Some(
+                    timeout(
+                        deadline,
+                        cached_request(
+                            cache.clone(),
+                            &req,
+                            rpc::get_pump_fun_quotes(self.client.clone(), &req),
+                        ),
+                    )
+                    .await,
+                )
+            },
+        );
+
+        let mut quotes = AggregatedQuotes::default();
+        quotes.record(Venue::Raydium, raydium);
+        quotes.record(Venue::RaydiumCpmm, cpmm);
+        quotes.record(Venue::RaydiumClmm, clmm);
+        quotes.record(Venue::Jupiter, jupiter);
+        if let Some(pump_fun) = pump_fun {
+            quotes.record(Venue::PumpFun, pump_fun);
+        }
+
+        quotes.finish()
     }
 }
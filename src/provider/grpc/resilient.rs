@@ -0,0 +1,400 @@
+use std::future::Future;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use futures_util::{stream, Stream, StreamExt};
+use solana_trader_proto::api;
+use tonic::{Code, Request, Status};
+
+use super::GrpcClient;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResubscribeConfig {
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub multiplier: f64,
+    // consecutive failed attempts before giving up; `None` retries until the stream is dropped
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for ResubscribeConfig {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(30),
+            multiplier: 2.0,
+            max_attempts: None,
+        }
+    }
+}
+
+impl ResubscribeConfig {
+    // Wait before the given retry, counting from 1.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = self
+            .multiplier
+            .max(1.0)
+            .powi(attempt.saturating_sub(1).min(64) as i32);
+        // scaled in f64 and clamped before converting, since a huge or infinite factor would
+        // overflow Duration
+        let secs = self.initial_backoff.as_secs_f64() * factor;
+        Duration::try_from_secs_f64(secs.min(self.max_backoff.as_secs_f64()))
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamEvent<T> {
+    Message(T),
+    // The stream was lost and has been re-established; anything the server sent in between was
+    // missed, so state built from earlier messages should be resynchronized.
+    Reconnected { attempts: u32, reason: String },
+}
+
+// Transient failures worth resubscribing after. Anything else (bad request, auth, unimplemented)
+// would fail the same way again.
+pub fn is_retryable(status: &Status) -> bool {
+    matches!(
+        status.code(),
+        Code::Unavailable
+            | Code::Unknown
+            | Code::Internal
+            | Code::Aborted
+            | Code::Cancelled
+            | Code::DeadlineExceeded
+            | Code::ResourceExhausted
+    )
+}
+
+struct ResubscribeState<S, F> {
+    name: &'static str,
+    config: ResubscribeConfig,
+    open: F,
+    stream: Option<S>,
+    connected: bool,
+    failures: u32,
+    gap: Option<String>,
+    done: bool,
+}
+
+impl<S, F> ResubscribeState<S, F> {
+    // Records a failed attempt; returns the error to end with once retries are exhausted.
+    fn fail(&mut self, reason: String) -> Option<anyhow::Error> {
+        self.stream = None;
+        self.failures += 1;
+        if self.connected && self.gap.is_none() {
+            self.gap = Some(reason.clone());
+        }
+
+        match self.config.max_attempts {
+            Some(max) if self.failures > max => {
+                self.done = true;
+                Some(anyhow!(
+                    "{} error: giving up after {} attempts: {}",
+                    self.name,
+                    max,
+                    reason
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+// Opens a server stream with `open` and reopens it with backoff whenever it fails with a
+// retryable status or is closed by the server, yielding `Reconnected` before the first message
+// of each new stream. A non-retryable status, or running out of attempts, ends it with an error.
+// Dropping the returned stream cancels any pending retry.
+pub fn resubscribing<T, S, F, Fut>(
+    name: &'static str,
+    config: ResubscribeConfig,
+    open: F,
+) -> impl Stream<Item = Result<StreamEvent<T>>> + Send + Unpin
+where
+    T: Send + 'static,
+    S: Stream<Item = Result<T, Status>> + Send + Unpin + 'static,
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = Result<S, Status>> + Send,
+{
+    let state = ResubscribeState {
+        name,
+        config,
+        open,
+        stream: None,
+        connected: false,
+        failures: 0,
+        gap: None,
+        done: false,
+    };
+
+    Box::pin(stream::unfold(state, |mut state| async move {
+        loop {
+            if state.done {
+                return None;
+            }
+
+            let Some(stream) = state.stream.as_mut() else {
+                if state.failures > 0 {
+                    tokio::time::sleep(state.config.backoff(state.failures)).await;
+                }
+
+                match (state.open)().await {
+                    Ok(stream) => {
+                        state.stream = Some(stream);
+                        state.connected = true;
+                        if let Some(reason) = state.gap.take() {
+                            let attempts = state.failures;
+                            return Some((
+                                Ok(StreamEvent::Reconnected { attempts, reason }),
+                                state,
+                            ));
+                        }
+                    }
+                    Err(status) if is_retryable(&status) => {
+                        if let Some(e) = state.fail(status.to_string()) {
+                            return Some((Err(e), state));
+                        }
+                    }
+                    Err(status) => {
+                        state.done = true;
+                        let e = anyhow!("{} error: {}", state.name, status);
+                        return Some((Err(e), state));
+                    }
+                }
+                continue;
+            };
+
+            match stream.next().await {
+                Some(Ok(message)) => {
+                    state.failures = 0;
+                    return Some((Ok(StreamEvent::Message(message)), state));
+                }
+                Some(Err(status)) if is_retryable(&status) => {
+                    if let Some(e) = state.fail(status.to_string()) {
+                        return Some((Err(e), state));
+                    }
+                }
+                Some(Err(status)) => {
+                    state.done = true;
+                    let e = anyhow!("{} error: {}", state.name, status);
+                    return Some((Err(e), state));
+                }
+                None => {
+                    if let Some(e) = state.fail("stream closed by server".to_string()) {
+                        return Some((Err(e), state));
+                    }
+                }
+            }
+        }
+    }))
+}
+
+// Resubscribing counterparts of the `get_*_stream` methods. Each takes the full request, which is
+// re-sent unchanged on every reconnect.
+macro_rules! resilient_streams {
+    ($($method:ident($request:ty) -> $response:ty { rpc: $rpc:ident, name: $name:literal })*) => {
+        impl GrpcClient {
+            $(
+                pub fn $method(
+                    &self,
+                    request: $request,
+                    config: ResubscribeConfig,
+                ) -> impl Stream<Item = Result<StreamEvent<$response>>> + Send + Unpin {
+                    let client = self.client.clone();
+                    resubscribing($name, config, move || {
+                        let mut client = client.clone();
+                        let request = Request::new(request.clone());
+                        async move { client.$rpc(request).await.map(|r| r.into_inner()) }
+                    })
+                }
+            )*
+        }
+    };
+}
+
+resilient_streams! {
+    get_prices_stream_resilient(api::GetPricesStreamRequest) -> api::GetPricesStreamResponse {
+        rpc: get_prices_stream, name: "GetPricesStream"
+    }
+    get_block_stream_resilient(api::GetBlockStreamRequest) -> api::GetBlockStreamResponse {
+        rpc: get_block_stream, name: "GetBlockStream"
+    }
+    get_orderbook_stream_resilient(api::GetOrderbooksRequest) -> api::GetOrderbooksStreamResponse {
+        rpc: get_orderbooks_stream, name: "GetOrderbooksStream"
+    }
+    get_market_depths_stream_resilient(api::GetMarketDepthsRequest) -> api::GetMarketDepthsStreamResponse {
+        rpc: get_market_depths_stream, name: "GetMarketDepthsStream"
+    }
+    get_ticker_stream_resilient(api::GetTickersStreamRequest) -> api::GetTickersStreamResponse {
+        rpc: get_tickers_stream, name: "GetTickersStream"
+    }
+    get_trades_stream_resilient(api::GetTradesRequest) -> api::GetTradesStreamResponse {
+        rpc: get_trades_stream, name: "GetTradesStream"
+    }
+    get_swaps_stream_resilient(api::GetSwapsStreamRequest) -> api::GetSwapsStreamResponse {
+        rpc: get_swaps_stream, name: "GetSwapsStream"
+    }
+    get_new_raydium_pools_stream_resilient(api::GetNewRaydiumPoolsRequest) -> api::GetNewRaydiumPoolsResponse {
+        rpc: get_new_raydium_pools_stream, name: "GetNewRaydiumPoolsStream"
+    }
+    get_new_raydium_pools_by_transaction_stream_resilient(api::GetNewRaydiumPoolsByTransactionRequest) -> api::GetNewRaydiumPoolsByTransactionResponse {
+        rpc: get_new_raydium_pools_by_transaction_stream, name: "GetNewRaydiumPoolsByTransactionStream"
+    }
+    get_recent_block_hash_stream_resilient(api::GetRecentBlockHashRequest) -> api::GetRecentBlockHashResponse {
+        rpc: get_recent_block_hash_stream, name: "GetRecentBlockHashStream"
+    }
+    get_pool_reserves_stream_resilient(api::GetPoolReservesStreamRequest) -> api::GetPoolReservesStreamResponse {
+        rpc: get_pool_reserves_stream, name: "GetPoolReservesStream"
+    }
+    get_priority_fee_stream_resilient(api::GetPriorityFeeRequest) -> api::GetPriorityFeeResponse {
+        rpc: get_priority_fee_stream, name: "GetPriorityFeeStream"
+    }
+    get_bundle_tip_stream_resilient(api::GetBundleTipRequest) -> api::GetBundleTipResponse {
+        rpc: get_bundle_tip_stream, name: "GetBundleTipStream"
+    }
+    get_pump_fun_new_tokens_stream_resilient(api::GetPumpFunNewTokensStreamRequest) -> api::GetPumpFunNewTokensStreamResponse {
+        rpc: get_pump_fun_new_tokens_stream, name: "GetPumpFunNewTokensStream"
+    }
+    get_pump_fun_swaps_stream_resilient(api::GetPumpFunSwapsStreamRequest) -> api::GetPumpFunSwapsStreamResponse {
+        rpc: get_pump_fun_swaps_stream, name: "GetPumpFunSwapsStream"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+
+    type Attempt = Result<Vec<Result<u64, Status>>, Status>;
+
+    fn config(max_attempts: Option<u32>) -> ResubscribeConfig {
+        ResubscribeConfig {
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(4),
+            multiplier: 2.0,
+            max_attempts,
+        }
+    }
+
+    // Each call to `open` plays the next scripted attempt: a failed open or a stream of items.
+    fn scripted(
+        attempts: Vec<Attempt>,
+        config: ResubscribeConfig,
+    ) -> (
+        impl Stream<Item = Result<StreamEvent<u64>>> + Unpin,
+        Arc<Mutex<u32>>,
+    ) {
+        let attempts = Arc::new(Mutex::new(VecDeque::from(attempts)));
+        let opened = Arc::new(Mutex::new(0));
+        let counter = opened.clone();
+
+        let stream = resubscribing("TestStream", config, move || {
+            *counter.lock().unwrap() += 1;
+            let attempt = attempts
+                .lock()
+                .unwrap()
+                .pop_front()
+                .unwrap_or_else(|| Err(Status::invalid_argument("script ended")));
+            async move { attempt.map(stream::iter) }
+        });
+        (stream, opened)
+    }
+
+    fn messages(events: &[StreamEvent<u64>]) -> Vec<u64> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                StreamEvent::Message(value) => Some(*value),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_backoff() {
+        let config = config(None);
+        assert_eq!(config.backoff(1), Duration::from_millis(1));
+        assert_eq!(config.backoff(3), Duration::from_millis(4));
+        assert_eq!(config.backoff(10), Duration::from_millis(4));
+
+        let config = ResubscribeConfig {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+            multiplier: 1e300,
+            max_attempts: None,
+        };
+        assert_eq!(config.backoff(1), Duration::from_secs(1));
+        assert_eq!(config.backoff(u32::MAX), Duration::from_secs(30));
+        let config = ResubscribeConfig {
+            multiplier: f64::INFINITY,
+            ..config
+        };
+        assert_eq!(config.backoff(2), Duration::from_secs(30));
+        assert!(is_retryable(&Status::unavailable("down")));
+        assert!(!is_retryable(&Status::unauthenticated("no auth")));
+    }
+
+    #[tokio::test]
+    async fn test_resubscribes_after_retryable_errors() -> Result<()> {
+        let (mut stream, opened) = scripted(
+            vec![
+                Err(Status::unavailable("connecting")),
+                Ok(vec![Ok(1), Ok(2), Err(Status::unavailable("reset"))]),
+                Err(Status::unavailable("still down")),
+                Ok(vec![Ok(3)]),
+                Ok(vec![Ok(4), Err(Status::permission_denied("revoked"))]),
+            ],
+            config(None),
+        );
+
+        let mut events = vec![];
+        while let Some(event) = stream.next().await {
+            match event {
+                Ok(event) => events.push(event),
+                Err(e) => {
+                    assert!(e.to_string().contains("revoked"));
+                    break;
+                }
+            }
+        }
+
+        assert_eq!(messages(&events), vec![1, 2, 3, 4]);
+        assert_eq!(
+            events[2],
+            StreamEvent::Reconnected {
+                attempts: 2,
+                reason: Status::unavailable("reset").to_string(),
+            }
+        );
+        // The server closing the stream after 3 is a gap as well.
+        assert!(matches!(
+            events[4],
+            StreamEvent::Reconnected { attempts: 1, .. }
+        ));
+        assert!(stream.next().await.is_none());
+        assert_eq!(*opened.lock().unwrap(), 5);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stops_after_max_attempts() {
+        let (mut stream, opened) = scripted(
+            vec![
+                Ok(vec![Ok(1)]),
+                Err(Status::unavailable("down")),
+                Err(Status::unavailable("down")),
+            ],
+            config(Some(2)),
+        );
+
+        assert!(matches!(
+            stream.next().await,
+            Some(Ok(StreamEvent::Message(1)))
+        ));
+        let error = stream.next().await.unwrap().unwrap_err();
+        assert!(error.to_string().contains("giving up after 2 attempts"));
+        assert!(stream.next().await.is_none());
+        assert_eq!(*opened.lock().unwrap(), 3);
+    }
+}
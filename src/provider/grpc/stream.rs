@@ -1,9 +1,16 @@
+use std::future::Future;
+
 use anyhow::Result;
+use futures_util::StreamExt;
+use serde::Serialize;
 use solana_trader_proto::api;
-use tonic::Request;
 use tonic::Streaming;
+use tonic::{Request, Response, Status};
 
+use super::endpoints::Client;
 use super::GrpcClient;
+use crate::provider::codec::to_json;
+use crate::provider::shared::SharedStream;
 
 impl GrpcClient {
     pub async fn get_prices_stream(
@@ -263,4 +270,66 @@ impl GrpcClient {
 
         Ok(response.into_inner())
     }
+
+    // Like `get_prices_stream`, but identical requests on this client share one server
+    // subscription, which ends once the last `SharedStream` is dropped.
+    pub async fn get_prices_stream_shared(
+        &self,
+        projects: Vec<api::Project>,
+        tokens: Vec<String>,
+    ) -> Result<SharedStream<api::GetPricesStreamResponse>> {
+        let request = api::GetPricesStreamRequest {
+            projects: projects.iter().map(|&p| p as i32).collect(),
+            tokens,
+        };
+
+        self.shared_stream(
+            "GetPricesStream",
+            request,
+            |mut client, request| async move { client.get_prices_stream(request).await },
+        )
+        .await
+    }
+
+    pub async fn get_block_stream_shared(
+        &self,
+    ) -> Result<SharedStream<api::GetBlockStreamResponse>> {
+        let request = api::GetBlockStreamRequest {};
+
+        self.shared_stream(
+            "GetBlockStream",
+            request,
+            |mut client, request| async move { client.get_block_stream(request).await },
+        )
+        .await
+    }
+
+    // Opens the stream on a cloned channel, so the shared subscription does not borrow the client.
+    async fn shared_stream<Req, Resp, F, Fut>(
+        &self,
+        rpc: &'static str,
+        request: Req,
+        call: F,
+    ) -> Result<SharedStream<Resp>>
+    where
+        Req: Serialize,
+        Resp: Clone + Send + Sync + 'static,
+        F: FnOnce(Client, Req) -> Fut,
+        Fut: Future<Output = std::result::Result<Response<Streaming<Resp>>, Status>>,
+    {
+        let key = format!("{}:{}", rpc, to_json(&request)?);
+        let client = self.client.clone();
+
+        self.shared
+            .subscribe(key, || async move {
+                let response = call(client, request)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("{} error: {}", rpc, e))?;
+
+                Ok(response
+                    .into_inner()
+                    .map(move |item| item.map_err(|e| anyhow::anyhow!("{} error: {}", rpc, e))))
+            })
+            .await
+    }
 }
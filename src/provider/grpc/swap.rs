@@ -1,60 +1,25 @@
 use anyhow::Result;
 use base64::{engine::general_purpose, Engine};
-use solana_sdk::{
-    message::{v0, VersionedMessage},
-    transaction::VersionedTransaction,
-};
+use solana_sdk::message::{v0, VersionedMessage};
 use solana_trader_proto::api;
-use tonic::Request;
 
 use crate::{
-    common::signing::SubmitParams,
-    provider::utils::{
-        convert_address_lookup_table, convert_jupiter_instructions, convert_raydium_instructions,
-        create_transaction_message,
+    common::{signer::new_versioned_transaction, signing::SubmitParams},
+    provider::{
+        guard::{
+            best_quoted_out_amount, jupiter_quotes_request, quotes_request, raydium_quotes_request,
+            verify_swap_output, SwapTolerance,
+        },
+        utils::{
+            convert_address_lookup_table, convert_jupiter_instructions,
+            convert_raydium_instructions, create_transaction_message,
+        },
     },
 };
 
 use super::GrpcClient;
 
 impl GrpcClient {
-    pub async fn post_raydium_swap(
-        &mut self,
-        request: &api::PostRaydiumSwapRequest,
-    ) -> Result<api::PostRaydiumSwapResponse> {
-        let response = self
-            .client
-            .post_raydium_swap(Request::new(request.clone()))
-            .await
-            .map_err(|e| anyhow::anyhow!("PostRaydiumSwap error: {}", e))?;
-
-        Ok(response.into_inner())
-    }
-
-    pub async fn post_raydium_route_swap(
-        &mut self,
-        request: &api::PostRaydiumRouteSwapRequest,
-    ) -> Result<api::PostRaydiumRouteSwapResponse> {
-        let response = self
-            .client
-            .post_raydium_route_swap(Request::new(request.clone()))
-            .await
-            .map_err(|e| anyhow::anyhow!("PostRaydiumRouteSwap error: {}", e))?;
-
-        Ok(response.into_inner())
-    }
-
-    pub async fn post_raydium_swap_instructions(
-        &mut self,
-        request: &api::PostRaydiumSwapInstructionsRequest,
-    ) -> Result<api::PostRaydiumSwapInstructionsResponse> {
-        Ok(self
-            .client
-            .post_raydium_swap_instructions(request.clone())
-            .await?
-            .into_inner())
-    }
-
     pub async fn submit_raydium_swap_instructions(
         &mut self,
         request: api::PostRaydiumSwapInstructionsRequest,
@@ -78,82 +43,6 @@ impl GrpcClient {
             .await
     }
 
-    pub async fn post_raydium_cpmm_swap(
-        &mut self,
-        request: &api::PostRaydiumCpmmSwapRequest,
-    ) -> Result<api::PostRaydiumCpmmSwapResponse> {
-        let response = self
-            .client
-            .post_raydium_cpmm_swap(Request::new(request.clone()))
-            .await
-            .map_err(|e| anyhow::anyhow!("PostRaydiumCPMMSwap error: {}", e))?;
-
-        Ok(response.into_inner())
-    }
-
-    pub async fn post_raydium_clmm_swap(
-        &mut self,
-        request: &api::PostRaydiumSwapRequest,
-    ) -> Result<api::PostRaydiumSwapResponse> {
-        let response = self
-            .client
-            .post_raydium_clmm_swap(Request::new(request.clone()))
-            .await
-            .map_err(|e| anyhow::anyhow!("PostRaydiumCLMMSwap error: {}", e))?;
-
-        Ok(response.into_inner())
-    }
-
-    pub async fn post_raydium_clmm_route_swap(
-        &mut self,
-        request: &api::PostRaydiumRouteSwapRequest,
-    ) -> Result<api::PostRaydiumRouteSwapResponse> {
-        let response = self
-            .client
-            .post_raydium_clmm_route_swap(Request::new(request.clone()))
-            .await
-            .map_err(|e| anyhow::anyhow!("PostRaydiumCLMMRouteSwap error: {}", e))?;
-
-        Ok(response.into_inner())
-    }
-
-    pub async fn post_jupiter_swap(
-        &mut self,
-        request: &api::PostJupiterSwapRequest,
-    ) -> Result<api::PostJupiterSwapResponse> {
-        let response = self
-            .client
-            .post_jupiter_swap(Request::new(request.clone()))
-            .await
-            .map_err(|e| anyhow::anyhow!("PostJupiterSwap error: {}", e))?;
-
-        Ok(response.into_inner())
-    }
-
-    pub async fn post_jupiter_route_swap(
-        &mut self,
-        request: &api::PostJupiterRouteSwapRequest,
-    ) -> Result<api::PostJupiterRouteSwapResponse> {
-        let response = self
-            .client
-            .post_jupiter_route_swap(Request::new(request.clone()))
-            .await
-            .map_err(|e| anyhow::anyhow!("PostJupiterRouteSwap error: {}", e))?;
-
-        Ok(response.into_inner())
-    }
-
-    pub async fn post_jupiter_swap_instructions(
-        &mut self,
-        request: &api::PostJupiterSwapInstructionsRequest,
-    ) -> Result<api::PostJupiterSwapInstructionsResponse> {
-        Ok(self
-            .client
-            .post_jupiter_swap_instructions(request.clone())
-            .await?
-            .into_inner())
-    }
-
     pub async fn submit_jupiter_swap_instructions(
         &mut self,
         request: api::PostJupiterSwapInstructionsRequest,
@@ -181,54 +70,93 @@ impl GrpcClient {
             blockhash.parse()?,
         )?);
 
-        let tx: VersionedTransaction =
-            VersionedTransaction::try_new(message, &[self.keypair.as_mut().unwrap()])?;
+        let tx = new_versioned_transaction(message, self.get_signer()?.as_ref()).await?;
 
         let tx_message = api::TransactionMessage {
             content: general_purpose::STANDARD.encode(bincode::serialize(&tx)?),
             is_cleanup: false,
         };
 
-        self.sign_and_submit(vec![tx_message], submit_opts, use_bundle)
-            .await
+        self.sign_and_submit_with_lookup_tables(
+            vec![tx_message],
+            &swap_instructions.address_lookup_table_addresses,
+            submit_opts,
+            use_bundle,
+        )
+        .await
     }
 
-    pub async fn post_pump_swap(
+    pub async fn submit_pump_swap(
         &mut self,
         request: &api::PostPumpFunSwapRequest,
-    ) -> Result<api::PostPumpFunSwapResponse> {
-        let response = self
-            .client
-            .post_pump_fun_swap(Request::new(request.clone()))
-            .await
-            .map_err(|e| anyhow::anyhow!("PostPumpFunSwap error: {}", e))?;
+        submit_opts: SubmitParams,
+        use_bundle: bool,
+    ) -> Result<Vec<String>> {
+        let response = self.post_pump_swap(request).await?;
+        let txs: Vec<api::TransactionMessage> = response.transaction.into_iter().collect();
+        if txs.is_empty() {
+            return Err(anyhow::anyhow!("PostPumpFunSwap returned no transaction"));
+        }
 
-        Ok(response.into_inner())
+        self.sign_and_submit(txs, submit_opts, use_bundle).await
     }
 
-    pub async fn post_trade_swap(
+    // Fetches a quote, requests the swap and only signs it if the swap's output
+    // stays within `tolerance` of the quote.
+    pub async fn quote_then_swap(
         &mut self,
         request: &api::TradeSwapRequest,
-    ) -> Result<api::TradeSwapResponse> {
-        let response = self
-            .client
-            .post_trade_swap(Request::new(request.clone()))
-            .await
-            .map_err(|e| anyhow::anyhow!("PostTradeSwap error: {}", e))?;
+        tolerance: &SwapTolerance,
+        submit_opts: SubmitParams,
+        use_bundle: bool,
+    ) -> Result<Vec<String>> {
+        let quotes = self.get_quotes_request(&quotes_request(request)).await?;
+        let quoted_out_amount = best_quoted_out_amount(&quotes)?;
 
-        Ok(response.into_inner())
+        let swap = self.post_trade_swap(request).await?;
+        verify_swap_output(quoted_out_amount, &swap, tolerance)?;
+
+        self.sign_and_submit(swap.transactions, submit_opts, use_bundle)
+            .await
     }
 
-    pub async fn post_route_trade_swap(
+    // Like `quote_then_swap`, against a Raydium quote.
+    pub async fn quote_then_raydium_swap(
         &mut self,
-        request: &api::RouteTradeSwapRequest,
-    ) -> Result<api::TradeSwapResponse> {
-        let response = self
-            .client
-            .post_route_trade_swap(Request::new(request.clone()))
+        request: &api::PostRaydiumSwapRequest,
+        tolerance: &SwapTolerance,
+        submit_opts: SubmitParams,
+        use_bundle: bool,
+    ) -> Result<Vec<String>> {
+        let quotes = self
+            .get_raydium_quotes(&raydium_quotes_request(request))
+            .await?;
+        let quoted_out_amount = best_quoted_out_amount(&quotes)?;
+
+        let swap = self.post_raydium_swap(request).await?;
+        verify_swap_output(quoted_out_amount, &swap, tolerance)?;
+
+        self.sign_and_submit(swap.transactions, submit_opts, use_bundle)
             .await
-            .map_err(|e| anyhow::anyhow!("PostRouteTradeSwap error: {}", e))?;
+    }
+
+    // Like `quote_then_swap`, against a Jupiter quote.
+    pub async fn quote_then_jupiter_swap(
+        &mut self,
+        request: &api::PostJupiterSwapRequest,
+        tolerance: &SwapTolerance,
+        submit_opts: SubmitParams,
+        use_bundle: bool,
+    ) -> Result<Vec<String>> {
+        let quotes = self
+            .get_jupiter_quotes(&jupiter_quotes_request(request))
+            .await?;
+        let quoted_out_amount = best_quoted_out_amount(&quotes)?;
 
-        Ok(response.into_inner())
+        let swap = self.post_jupiter_swap(request).await?;
+        verify_swap_output(quoted_out_amount, &swap, tolerance)?;
+
+        self.sign_and_submit(swap.transactions, submit_opts, use_bundle)
+            .await
     }
 }
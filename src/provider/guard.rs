@@ -0,0 +1,290 @@
+use anyhow::{anyhow, Result};
+use solana_trader_proto::{api, common};
+
+#[derive(Debug, Clone)]
+pub struct SwapTolerance {
+    // how far the swap's expected output may fall below the quoted output, in percent
+    pub max_quote_deviation_percent: f64,
+    // how far the swap's guaranteed minimum output may fall below the quoted output, in percent
+    pub max_slippage_percent: f64,
+    pub max_price_impact_percent: Option<f64>,
+}
+
+impl Default for SwapTolerance {
+    fn default() -> Self {
+        Self {
+            max_quote_deviation_percent: 0.5,
+            max_slippage_percent: 1.0,
+            max_price_impact_percent: None,
+        }
+    }
+}
+
+pub trait SwapOutput {
+    fn out_amount(&self) -> f64;
+
+    fn out_amount_min(&self) -> f64;
+
+    // an infinite impact is reported as positive or negative infinity
+    fn price_impact_percent(&self) -> Option<f64>;
+}
+
+impl SwapOutput for api::PostRaydiumSwapResponse {
+    fn out_amount(&self) -> f64 {
+        self.out_amount
+    }
+
+    fn out_amount_min(&self) -> f64 {
+        self.out_amount_min
+    }
+
+    fn price_impact_percent(&self) -> Option<f64> {
+        self.price_impact.as_ref().map(impact_percent_v2)
+    }
+}
+
+impl SwapOutput for api::PostJupiterSwapResponse {
+    fn out_amount(&self) -> f64 {
+        self.out_amount
+    }
+
+    fn out_amount_min(&self) -> f64 {
+        self.out_amount_min
+    }
+
+    fn price_impact_percent(&self) -> Option<f64> {
+        self.price_impact.as_ref().map(impact_percent_v2)
+    }
+}
+
+impl SwapOutput for api::TradeSwapResponse {
+    fn out_amount(&self) -> f64 {
+        self.out_amount
+    }
+
+    fn out_amount_min(&self) -> f64 {
+        self.out_amount_min
+    }
+
+    fn price_impact_percent(&self) -> Option<f64> {
+        self.price_impact
+            .as_ref()
+            .map(|impact| match common::Infinity::try_from(impact.infinity) {
+                Ok(common::Infinity::InfPositive) => f64::INFINITY,
+                Ok(common::Infinity::InfNegative) => f64::NEG_INFINITY,
+                _ => impact.percent,
+            })
+    }
+}
+
+// `infinity` is one of NOT, NEGATIVE or POSITIVE
+pub(crate) fn impact_percent_v2(impact: &common::PriceImpactPercentV2) -> f64 {
+    match impact.infinity.as_str() {
+        "POSITIVE" => f64::INFINITY,
+        "NEGATIVE" => f64::NEG_INFINITY,
+        _ => impact.percent,
+    }
+}
+
+// Quote responses whose routes can be compared against a swap's output.
+pub trait QuotedRoutes {
+    fn route_out_amounts(&self) -> Vec<f64>;
+}
+
+impl QuotedRoutes for api::GetQuotesResponse {
+    fn route_out_amounts(&self) -> Vec<f64> {
+        self.quotes
+            .iter()
+            .flat_map(|quote| quote.routes.iter().map(|route| route.out_amount))
+            .collect()
+    }
+}
+
+impl QuotedRoutes for api::GetRaydiumQuotesResponse {
+    fn route_out_amounts(&self) -> Vec<f64> {
+        self.routes.iter().map(|route| route.out_amount).collect()
+    }
+}
+
+impl QuotedRoutes for api::GetJupiterQuotesResponse {
+    fn route_out_amounts(&self) -> Vec<f64> {
+        self.routes.iter().map(|route| route.out_amount).collect()
+    }
+}
+
+pub fn quotes_request(request: &api::TradeSwapRequest) -> api::GetQuotesRequest {
+    api::GetQuotesRequest {
+        in_token: request.in_token.clone(),
+        out_token: request.out_token.clone(),
+        in_amount: request.in_amount,
+        slippage: request.slippage,
+        limit: 1,
+        projects: vec![request.project],
+    }
+}
+
+pub fn raydium_quotes_request(
+    request: &api::PostRaydiumSwapRequest,
+) -> api::GetRaydiumQuotesRequest {
+    api::GetRaydiumQuotesRequest {
+        in_token: request.in_token.clone(),
+        out_token: request.out_token.clone(),
+        in_amount: request.in_amount,
+        slippage: request.slippage,
+    }
+}
+
+pub fn jupiter_quotes_request(
+    request: &api::PostJupiterSwapRequest,
+) -> api::GetJupiterQuotesRequest {
+    api::GetJupiterQuotesRequest {
+        in_token: request.in_token.clone(),
+        out_token: request.out_token.clone(),
+        in_amount: request.in_amount,
+        slippage: request.slippage,
+        fast_mode: request.fast_mode,
+    }
+}
+
+pub fn best_quoted_out_amount<Q: QuotedRoutes>(response: &Q) -> Result<f64> {
+    response
+        .route_out_amounts()
+        .into_iter()
+        .fold(None, |best: Option<f64>, out| {
+            Some(best.map_or(out, |best| best.max(out)))
+        })
+        .ok_or_else(|| anyhow!("No quotes returned for swap"))
+}
+
+pub fn verify_swap_output<R: SwapOutput>(
+    quoted_out_amount: f64,
+    response: &R,
+    tolerance: &SwapTolerance,
+) -> Result<()> {
+    // NaN fails every comparison below and would pass the guard unnoticed
+    let amounts = [
+        ("quoted out amount", quoted_out_amount),
+        ("out amount", response.out_amount()),
+        ("minimum out amount", response.out_amount_min()),
+    ];
+    for (name, amount) in amounts {
+        if !amount.is_finite() {
+            return Err(anyhow!(
+                "Swap rejected against quote: {} is {}",
+                name,
+                amount
+            ));
+        }
+    }
+
+    let mut violations = Vec::new();
+
+    let min_expected = quoted_out_amount * (1.0 - tolerance.max_quote_deviation_percent / 100.0);
+    if response.out_amount() < min_expected {
+        violations.push(format!(
+            "out amount {} is more than {}% below quoted {}",
+            response.out_amount(),
+            tolerance.max_quote_deviation_percent,
+            quoted_out_amount
+        ));
+    }
+
+    let min_guaranteed = quoted_out_amount * (1.0 - tolerance.max_slippage_percent / 100.0);
+    if response.out_amount_min() < min_guaranteed {
+        violations.push(format!(
+            "minimum out amount {} is more than {}% below quoted {}",
+            response.out_amount_min(),
+            tolerance.max_slippage_percent,
+            quoted_out_amount
+        ));
+    }
+
+    if let Some(max_impact) = tolerance.max_price_impact_percent {
+        match response.price_impact_percent() {
+            Some(impact) if impact <= max_impact => {}
+            Some(impact) => {
+                violations.push(format!("price impact {}% exceeds {}%", impact, max_impact))
+            }
+            None => violations.push("price impact missing from swap response".to_string()),
+        }
+    }
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "Swap rejected against quote: {}",
+            violations.join("; ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn swap_response(out_amount: f64, out_amount_min: f64) -> api::TradeSwapResponse {
+        api::TradeSwapResponse {
+            out_amount,
+            out_amount_min,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_verify_swap_output() {
+        let tolerance = SwapTolerance::default();
+
+        assert!(verify_swap_output(100.0, &swap_response(99.8, 99.5), &tolerance).is_ok());
+        assert!(verify_swap_output(100.0, &swap_response(98.0, 97.5), &tolerance).is_err());
+        assert!(verify_swap_output(100.0, &swap_response(100.0, 90.0), &tolerance).is_err());
+        assert!(verify_swap_output(100.0, &swap_response(f64::NAN, 99.5), &tolerance).is_err());
+        assert!(verify_swap_output(100.0, &swap_response(99.8, f64::NAN), &tolerance).is_err());
+        assert!(verify_swap_output(f64::NAN, &swap_response(99.8, 99.5), &tolerance).is_err());
+        assert!(verify_swap_output(f64::INFINITY, &swap_response(99.8, 99.5), &tolerance).is_err());
+
+        let tolerance = SwapTolerance {
+            max_price_impact_percent: Some(1.0),
+            ..Default::default()
+        };
+        assert!(verify_swap_output(100.0, &swap_response(100.0, 99.5), &tolerance).is_err());
+    }
+
+    #[test]
+    fn test_raydium_swap_against_raydium_quotes() -> Result<()> {
+        let quotes = api::GetRaydiumQuotesResponse {
+            routes: vec![
+                api::RaydiumQuoteRoute {
+                    out_amount: 98.0,
+                    ..Default::default()
+                },
+                api::RaydiumQuoteRoute {
+                    out_amount: 100.0,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let quoted_out_amount = best_quoted_out_amount(&quotes)?;
+        assert_eq!(quoted_out_amount, 100.0);
+
+        let swap = |percent: f64, infinity: &str| api::PostRaydiumSwapResponse {
+            out_amount: 99.9,
+            out_amount_min: 99.5,
+            price_impact: Some(common::PriceImpactPercentV2 {
+                percent,
+                infinity: infinity.to_string(),
+            }),
+            ..Default::default()
+        };
+        let tolerance = SwapTolerance {
+            max_price_impact_percent: Some(1.0),
+            ..Default::default()
+        };
+
+        assert!(verify_swap_output(quoted_out_amount, &swap(0.2, "NOT"), &tolerance).is_ok());
+        assert!(verify_swap_output(quoted_out_amount, &swap(0.2, "POSITIVE"), &tolerance).is_err());
+        assert!(best_quoted_out_amount(&api::GetJupiterQuotesResponse::default()).is_err());
+        Ok(())
+    }
+}
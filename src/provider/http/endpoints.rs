@@ -0,0 +1,44 @@
+use anyhow::{anyhow, Result};
+use solana_trader_proto::api;
+
+use super::HTTPClient;
+use crate::provider::codec;
+use crate::provider::endpoints::{through_cache, unary_endpoints};
+
+macro_rules! http_request {
+    ($client:expr, POST, $url:expr, $request:expr) => {
+        $client.post($url).json(&codec::to_json($request)?)
+    };
+    ($client:expr, GET, $url:expr, $request:expr) => {
+        $client.get(codec::with_query(&$url, $request)?)
+    };
+}
+
+macro_rules! http_endpoints {
+    ($(
+        $name:ident($request:ty) -> $response:ty {
+            grpc: $rpc:ident, http: $verb:ident $path:literal, ws: $method:literal $(without $omit:literal)?, $($cached:ident,)?
+        }
+    )*) => {
+        impl HTTPClient {
+            $(
+                pub async fn $name(&self, request: &$request) -> Result<$response> {
+                    let fetch = async {
+                        let url = format!("{}{}", self.base_url, $path);
+
+                        let response = http_request!(self.client, $verb, url, request)
+                            .send()
+                            .await
+                            .map_err(|e| anyhow!("HTTP {} request failed: {}", stringify!($verb), e))?;
+
+                        self.handle_response(response).await
+                    };
+
+                    through_cache!(self.quote_cache.clone(), request, fetch $(, $cached)?)
+                }
+            )*
+        }
+    };
+}
+
+unary_endpoints!(http_endpoints);
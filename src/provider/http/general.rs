@@ -1,38 +1,25 @@
+use crate::provider::codec::with_query;
 use crate::provider::http::HTTPClient;
 use anyhow::{anyhow, Result};
+use serde_json::json;
 use solana_trader_proto::api;
-use solana_trader_proto::api::GetAccountBalanceRequest;
+use url::Url;
 
 impl HTTPClient {
-    pub async fn get_transaction(
+    // The market is a path segment, so it is escaped there and left out of the query.
+    pub async fn get_orderbook(
         &self,
-        request: &api::GetTransactionRequest,
-    ) -> anyhow::Result<api::GetTransactionResponse> {
-        let url = format!(
-            "{}/api/v2/transaction?signature={}",
-            self.base_url, request.signature
-        );
-
-        println!("{}", url);
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| anyhow!("HTTP GET request failed: {}", e))?;
-
-        let response_text = response.text().await?;
-
-        println!("{}", response_text);
-
-        // let mut value: serde_json::Value = serde_json::from_str(&response_text)
-        //     .map_err(|e| anyhow::anyhow!("Failed to parse response as JSON: {}", e))?;
-        //
-        // convert_string_enums(&mut value);
-        //
-        // serde_json::from_value(value)
-        //     .map_err(|e| anyhow::anyhow!("Failed to parse response into GetTransactionResponse: {}", e))
+        request: &api::GetOrderbookRequest,
+    ) -> Result<api::GetOrderbookResponse> {
+        let mut url = Url::parse(&format!("{}/api/v1/market/orderbooks", self.base_url))
+            .map_err(|e| anyhow!("Invalid base URL: {}", e))?;
+        url.path_segments_mut()
+            .map_err(|_| anyhow!("Invalid base URL: {}", self.base_url))?
+            .push(&request.market);
+        let url = with_query(
+            url.as_str(),
+            &json!({ "limit": request.limit, "project": request.project }),
+        )?;
 
         let response = self
             .client
@@ -43,76 +30,36 @@ impl HTTPClient {
 
         self.handle_response(response).await
     }
-    pub async fn get_recent_block_hash(&self) -> anyhow::Result<api::GetRecentBlockHashResponse> {
-        let url = format!("{}/api/v1/system/blockhash", self.base_url);
 
-        println!("{}", url);
+    pub async fn get_transaction(
+        &self,
+        request: &api::GetTransactionRequest,
+    ) -> Result<api::GetTransactionResponse> {
+        self.get_transaction_request(request).await
+    }
 
-        let response = self
-            .client
-            .get(&url)
-            .send()
+    pub async fn get_recent_block_hash(&self) -> Result<api::GetRecentBlockHashResponse> {
+        self.get_recent_block_hash_request(&api::GetRecentBlockHashRequest {})
             .await
-            .map_err(|e| anyhow!("HTTP GET request failed: {}", e))?;
-
-        self.handle_response(response).await
     }
 
     pub async fn get_recent_block_hash_v2(
         &self,
         request: &api::GetRecentBlockHashRequestV2,
-    ) -> anyhow::Result<api::GetRecentBlockHashResponseV2> {
-        let url = format!(
-            "{}/api/v2/system/blockhash?offset={}",
-            self.base_url, request.offset
-        );
-
-        println!("{}", url);
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| anyhow!("HTTP GET request failed: {}", e))?;
-
-        self.handle_response(response).await
+    ) -> Result<api::GetRecentBlockHashResponseV2> {
+        self.get_recent_block_hash_v2_request(request).await
     }
 
-    pub async fn get_rate_limit(&self) -> anyhow::Result<api::GetRateLimitResponse> {
-        let url = format!("{}/api/v2/rate-limit", self.base_url);
-
-        println!("{}", url);
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
+    pub async fn get_rate_limit(&self) -> Result<api::GetRateLimitResponse> {
+        self.get_rate_limit_request(&api::GetRateLimitRequest {})
             .await
-            .map_err(|e| anyhow!("HTTP GET request failed: {}", e))?;
-
-        self.handle_response(response).await
     }
 
     pub async fn get_account_balance_v2(
         &self,
-        request: GetAccountBalanceRequest,
-    ) -> anyhow::Result<api::GetAccountBalanceResponse> {
-        println!("here1");
-
-        let url = format!(
-            "{}/api/v2/balance?ownerAddress={}",
-            self.base_url, request.owner_address
-        );
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| anyhow!("HTTP GET request failed: {}", e))?;
-
-        self.handle_response(response).await
+        request: api::GetAccountBalanceRequest,
+    ) -> Result<api::GetAccountBalanceResponse> {
+        self.get_account_balance_v2_request(&request).await
     }
 
     pub async fn get_priority_fee(
@@ -120,81 +67,34 @@ impl HTTPClient {
         project: api::Project,
         percentile: Option<f64>,
     ) -> Result<api::GetPriorityFeeResponse> {
-        let mut url = format!(
-            "{}/api/v2/system/priority-fee?project={}",
-            self.base_url, project as i32
-        );
-        if let Some(p) = percentile {
-            url = format!(
-                "{}/api/v2/system/priority-fee?project={}&percentile={}",
-                self.base_url, project as i32, p
-            );
-        }
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| anyhow!("HTTP GET request failed: {}", e))?;
-
-        self.handle_response(response).await
+        self.get_priority_fee_request(&api::GetPriorityFeeRequest {
+            project: project as i32,
+            percentile,
+        })
+        .await
     }
 
     pub async fn get_priority_fee_by_program(
         &self,
         programs: Vec<String>,
     ) -> Result<api::GetPriorityFeeByProgramResponse> {
-        let url = format!(
-            "{}/api/v2/system/priority-fee-by-program?programs={}",
-            self.base_url, programs.join("&programs=")
-        );
-
-        let response: reqwest::Response = self
-            .client
-            .get(&url)
-            .send()
+        self.get_priority_fee_by_program_request(&api::GetPriorityFeeByProgramRequest { programs })
             .await
-            .map_err(|e| anyhow!("HTTP GET request failed: {}", e))?;
-
-        self.handle_response(response).await
     }
 
     pub async fn get_token_accounts(
         &self,
         owner_address: String,
     ) -> Result<api::GetTokenAccountsResponse> {
-        let url = format!(
-            "{}/api/v1/account/token-accounts?ownerAddress={}",
-            self.base_url, owner_address
-        );
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
+        self.get_token_accounts_request(&api::GetTokenAccountsRequest { owner_address })
             .await
-            .map_err(|e| anyhow!("HTTP GET request failed: {}", e))?;
-
-        self.handle_response(response).await
     }
 
     pub async fn get_account_balance(
         &self,
         owner_address: String,
     ) -> Result<api::GetAccountBalanceResponse> {
-        let url = format!(
-            "{}/api/v2/balance?ownerAddress={}",
-            self.base_url, owner_address
-        );
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
+        self.get_account_balance_request(&api::GetAccountBalanceRequest { owner_address })
             .await
-            .map_err(|e| anyhow!("HTTP GET request failed: {}", e))?;
-
-        self.handle_response(response).await
     }
 }
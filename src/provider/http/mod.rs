@@ -1,5 +1,7 @@
+mod endpoints;
 pub mod general;
 pub mod quote;
+pub mod stream;
 pub mod swap;
 
 use anyhow::{anyhow, Result};
@@ -8,26 +10,37 @@ use reqwest::{
     Client,
 };
 use serde::de::DeserializeOwned;
-use serde_json::json;
 use solana_sdk::{pubkey::Pubkey, signature::Keypair};
-use solana_trader_proto::api::GetRecentBlockHashResponseV2;
+use solana_trader_proto::api::{self, GetRecentBlockHashResponseV2};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 
 use crate::{
     common::{
         get_base_url_from_env, http_endpoint,
-        signing::{sign_transaction, SubmitParams},
+        package::SignedTransactionPackage,
+        policy::{lookup_table_addresses, SigningPolicy},
+        signer::TransactionSigner,
+        signing::{sign_all_for_submit, DryRunResult, SignedTransaction, SubmitParams},
         BaseConfig,
     },
-    provider::utils::convert_string_enums,
+    provider::{cache::QuoteCache, enums, polling::PollInterval},
 };
 
-use super::utils::IntoTransactionMessage;
+use super::utils::{self, IntoTransactionMessage, SubmitRequest};
+
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
 
 pub struct HTTPClient {
     client: Client,
     base_url: String,
     keypair: Option<Keypair>,
     pub public_key: Option<Pubkey>,
+    signer: Option<Arc<dyn TransactionSigner>>,
+    signing_policy: Option<SigningPolicy>,
+    quote_cache: Option<Arc<QuoteCache>>,
+    poll_interval: PollInterval,
 }
 
 impl HTTPClient {
@@ -35,6 +48,34 @@ impl HTTPClient {
         Ok(self.keypair.as_ref().unwrap())
     }
 
+    pub fn get_signer(&self) -> Result<Arc<dyn TransactionSigner>> {
+        self.signer
+            .clone()
+            .ok_or_else(|| anyhow!("No signer configured"))
+    }
+
+    pub fn with_signer(mut self, signer: Arc<dyn TransactionSigner>) -> Self {
+        self.public_key = Some(signer.public_key());
+        self.signer = Some(signer);
+        self
+    }
+
+    pub fn with_signing_policy(mut self, policy: SigningPolicy) -> Self {
+        self.signing_policy = Some(policy);
+        self
+    }
+
+    pub fn with_quote_cache(mut self, cache: Arc<QuoteCache>) -> Self {
+        self.quote_cache = Some(cache);
+        self
+    }
+
+    // Schedule for the polling-based `get_*_stream` methods.
+    pub fn with_poll_interval(mut self, interval: impl Into<PollInterval>) -> Self {
+        self.poll_interval = interval.into();
+        self
+    }
+
     pub fn new(endpoint: Option<String>) -> Result<Self> {
         let base = BaseConfig::try_from_env()?;
         let (default_base_url, secure) = get_base_url_from_env();
@@ -52,6 +93,10 @@ impl HTTPClient {
             base_url: endpoint,
             keypair: base.keypair,
             public_key: base.public_key,
+            signer: base.signer,
+            signing_policy: None,
+            quote_cache: None,
+            poll_interval: PollInterval::fixed(DEFAULT_POLL_INTERVAL),
         })
     }
 
@@ -81,12 +126,10 @@ impl HTTPClient {
 
         let res = response.text().await?;
 
-        let mut value = serde_json::from_str(&res)
+        let value = serde_json::from_str(&res)
             .map_err(|e| anyhow::anyhow!("Failed to parse response as JSON: {}", e))?;
 
-        convert_string_enums(&mut value);
-
-        serde_json::from_value(value)
+        enums::from_value(value)
             .map_err(|e| anyhow::anyhow!("Failed to parse response into desired type: {}", e))
     }
 
@@ -96,76 +139,113 @@ impl HTTPClient {
         submit_opts: SubmitParams,
         use_bundle: bool,
     ) -> Result<Vec<String>> {
-        let keypair = self.get_keypair()?;
+        let signed_txs = self.sign_transactions(&txs, None, &submit_opts).await?;
 
-        // TODO: refactor once this endpoint is defined
-        let response = self
-            .client
-            .get(format!(
-                "{}/api/v2/system/blockhash?offset={}",
-                self.base_url, 0
-            ))
-            .send()
+        self.submit_signed(signed_txs, submit_opts, use_bundle)
+            .await
+    }
+
+    // Like `sign_and_submit`, with the lookup tables the transactions load accounts from so that
+    // the signing policy can resolve them.
+    pub async fn sign_and_submit_with_lookup_tables<T: IntoTransactionMessage + Clone>(
+        &self,
+        txs: Vec<T>,
+        address_lookup_tables: &HashMap<String, api::PublicKeys>,
+        submit_opts: SubmitParams,
+        use_bundle: bool,
+    ) -> Result<Vec<String>> {
+        let lookup_tables = lookup_table_addresses(address_lookup_tables);
+        let signed_txs = self
+            .sign_transactions(&txs, Some(&lookup_tables), &submit_opts)
             .await?;
 
-        let res: GetRecentBlockHashResponseV2 = self.handle_response(response).await?;
+        self.submit_signed(signed_txs, submit_opts, use_bundle)
+            .await
+    }
 
-        if txs.len() == 1 {
-            let signed_tx = sign_transaction(&txs[0], keypair, res.block_hash).await?;
+    pub async fn sign_and_submit_dry_run<T: IntoTransactionMessage + Clone>(
+        &self,
+        txs: Vec<T>,
+        submit_opts: SubmitParams,
+        use_bundle: bool,
+    ) -> Result<DryRunResult> {
+        let signed_txs = self.sign_transactions(&txs, None, &submit_opts).await?;
+        let (endpoint, request_json) = build_submit_request(&signed_txs, &submit_opts, use_bundle)?;
 
-            let request_json = json!({
-                "transaction": { "content": signed_tx.content, "isCleanup": signed_tx.is_cleanup },
-                "skipPreFlight": submit_opts.skip_pre_flight,
-                "frontRunningProtection": submit_opts.front_running_protection,
-                "useStakedRPCs": submit_opts.use_staked_rpcs,
-                "fastBestEffort": submit_opts.fast_best_effort
-            });
+        DryRunResult::new(signed_txs, endpoint, request_json)
+    }
 
-            let response = self
-                .client
-                .post(format!("{}/api/v2/submit", self.base_url))
-                .json(&request_json)
-                .send()
-                .await?;
+    async fn sign_transactions<T: IntoTransactionMessage + Clone>(
+        &self,
+        txs: &[T],
+        lookup_tables: Option<&HashMap<String, Vec<String>>>,
+        submit_opts: &SubmitParams,
+    ) -> Result<Vec<SignedTransaction>> {
+        let signer = self.get_signer()?;
 
-            let result: serde_json::Value = self.handle_response(response).await?;
-            return Ok(vec![result
-                .get("signature")
-                .and_then(|s| s.as_str())
-                .map(String::from)
-                .ok_or_else(|| anyhow!("Missing signature in response"))?]);
+        // checked first so that a rejected batch costs no blockhash request
+        if let Some(policy) = &self.signing_policy {
+            policy.check_transactions(txs, &signer.public_key(), lookup_tables)?;
         }
 
-        let mut entries = Vec::with_capacity(txs.len());
-        for tx in txs {
-            let signed_tx = sign_transaction(&tx, keypair, res.block_hash.clone()).await?;
-            entries.push(json!({
-                "transaction": {
-                    "content": signed_tx.content,
-                    "isCleanup": signed_tx.is_cleanup
-                },
-                "skipPreFlight": submit_opts.skip_pre_flight,
-                "frontRunningProtection": submit_opts.front_running_protection,
-                "useStakedRPCs": submit_opts.use_staked_rpcs,
-                "fastBestEffort": submit_opts.fast_best_effort
-            }));
-        }
+        let block_hash = match &submit_opts.durable_nonce {
+            Some(nonce) => nonce.nonce_hash.to_string(),
+            None => {
+                // TODO: refactor once this endpoint is defined
+                let response = self
+                    .client
+                    .get(format!(
+                        "{}/api/v2/system/blockhash?offset={}",
+                        self.base_url, 0
+                    ))
+                    .send()
+                    .await?;
+
+                let res: GetRecentBlockHashResponseV2 = self.handle_response(response).await?;
+                res.block_hash
+            }
+        };
+
+        sign_all_for_submit(txs, signer.as_ref(), &block_hash, submit_opts).await
+    }
+
+    pub async fn submit_signed_package(
+        &self,
+        package: SignedTransactionPackage,
+    ) -> Result<Vec<String>> {
+        self.submit_signed(
+            package.transactions,
+            package.submit_opts,
+            package.use_bundle,
+        )
+        .await
+    }
 
-        let request_json = json!({
-            "entries": entries,
-            "useBundle": use_bundle,
-            "submitStrategy": submit_opts.submit_strategy
-        });
+    pub async fn submit_signed(
+        &self,
+        signed_txs: Vec<SignedTransaction>,
+        submit_opts: SubmitParams,
+        use_bundle: bool,
+    ) -> Result<Vec<String>> {
+        let (endpoint, request_json) = build_submit_request(&signed_txs, &submit_opts, use_bundle)?;
 
         let response = self
             .client
-            .post(format!("{}/api/v2/submit/batch", self.base_url))
+            .post(format!("{}{}", self.base_url, endpoint))
             .json(&request_json)
             .send()
             .await?;
 
         let result: serde_json::Value = self.handle_response(response).await?;
 
+        if signed_txs.len() == 1 {
+            return Ok(vec![result
+                .get("signature")
+                .and_then(|s| s.as_str())
+                .map(String::from)
+                .ok_or_else(|| anyhow!("Missing signature in response"))?]);
+        }
+
         let signatures = result["transactions"]
             .as_array()
             .ok_or_else(|| anyhow!("Invalid response format"))?
@@ -177,3 +257,17 @@ impl HTTPClient {
         Ok(signatures)
     }
 }
+
+fn build_submit_request(
+    signed_txs: &[SignedTransaction],
+    submit_opts: &SubmitParams,
+    use_bundle: bool,
+) -> Result<(&'static str, serde_json::Value)> {
+    let request = utils::build_submit_request(signed_txs, submit_opts, use_bundle);
+    let endpoint = match request {
+        SubmitRequest::Single(_) => "/api/v2/submit",
+        SubmitRequest::Batch(_) => "/api/v2/submit/batch",
+    };
+
+    Ok((endpoint, request.to_json()?))
+}
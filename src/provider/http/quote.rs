@@ -1,110 +1,13 @@
-use crate::provider::utils::convert_string_enums;
+use crate::provider::aggregator::{AggregatedQuotes, BestQuoteRequest, Venue};
+use crate::provider::polling::{poll_stream, PollInterval};
 
 use super::HTTPClient;
-use anyhow::{anyhow, Result};
+use anyhow::Result;
+use futures_util::Stream;
 use solana_trader_proto::api;
+use tokio::time::timeout;
 
 impl HTTPClient {
-    pub async fn get_raydium_quotes(
-        &self,
-        request: &api::GetRaydiumQuotesRequest,
-    ) -> Result<api::GetRaydiumQuotesResponse> {
-        let url = format!(
-            "{}/api/v2/raydium/quotes?inToken={}&outToken={}&inAmount={}&slippage={}",
-            self.base_url, request.in_token, request.out_token, request.in_amount, request.slippage
-        );
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| anyhow!("HTTP GET request failed: {}", e))?;
-
-        self.handle_response(response).await
-    }
-
-    pub async fn get_raydium_cpmm_quotes(
-        &self,
-        request: &api::GetRaydiumCpmmQuotesRequest,
-    ) -> Result<api::GetRaydiumCpmmQuotesResponse> {
-        let url = format!(
-            "{}/api/v2/raydium/cpmm-quotes?inToken={}&outToken={}&inAmount={}&slippage={}",
-            self.base_url, request.in_token, request.out_token, request.in_amount, request.slippage
-        );
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| anyhow::anyhow!("HTTP GET request failed: {}", e))?;
-
-        self.handle_response(response).await
-    }
-
-    pub async fn get_raydium_clmm_quotes(
-        &self,
-        request: &api::GetRaydiumClmmQuotesRequest,
-    ) -> Result<api::GetRaydiumClmmQuotesResponse> {
-        let url = format!(
-            "{}/api/v2/raydium/clmm-quotes?inToken={}&outToken={}&inAmount={}&slippage={}",
-            self.base_url, request.in_token, request.out_token, request.in_amount, request.slippage
-        );
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| anyhow::anyhow!("HTTP GET request failed: {}", e))?;
-
-        self.handle_response(response).await
-    }
-
-    pub async fn get_pump_fun_quotes(
-        &self,
-        request: &api::GetPumpFunQuotesRequest,
-    ) -> Result<api::GetPumpFunQuotesResponse> {
-        let url = format!(
-            "{}/api/v2/pumpfun/quotes?mintAddress={}&quoteType={}&amount={}&bondingCurveAddress={}",
-            self.base_url,
-            request.mint_address,
-            request.quote_type,
-            request.amount,
-            request.bonding_curve_address,
-        );
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| anyhow::anyhow!("HTTP GET request failed: {}", e))?;
-
-        self.handle_response(response).await
-    }
-
-    // NOTE: Fast mode is not used as of 11/1, breaks the endpoint.
-    pub async fn get_jupiter_quotes(
-        &self,
-        request: &api::GetJupiterQuotesRequest,
-    ) -> Result<api::GetJupiterQuotesResponse> {
-        let url = format!(
-            "{}/api/v2/jupiter/quotes?inToken={}&outToken={}&inAmount={}&slippage={}",
-            self.base_url, request.in_token, request.out_token, request.in_amount, request.slippage,
-        );
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| anyhow::anyhow!("HTTP GET request failed: {}", e))?;
-
-        self.handle_response(response).await
-    }
-
     pub async fn get_quotes(
         &self,
         in_token: &str,
@@ -114,69 +17,78 @@ impl HTTPClient {
         limit: i32,
         projects: &[api::Project],
     ) -> Result<api::GetQuotesResponse> {
-        let project_params: Vec<String> = projects
-            .iter()
-            .map(|p| format!("&project={}", *p as i32))
-            .collect();
-
-        let url = format!(
-            "{}/api/v1/market/quote?inToken={}&outToken={}&inAmount={}&slippage={}&limit={}{}",
-            self.base_url,
-            in_token,
-            out_token,
+        self.get_quotes_request(&api::GetQuotesRequest {
+            in_token: in_token.to_string(),
+            out_token: out_token.to_string(),
             in_amount,
             slippage,
             limit,
-            project_params.join("")
-        );
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| anyhow::anyhow!("HTTP GET request failed: {}", e))?;
-
-        let response_text = response.text().await?;
-
-        let mut value: serde_json::Value = serde_json::from_str(&response_text)
-            .map_err(|e| anyhow::anyhow!("Failed to parse response as JSON: {}", e))?;
-
-        convert_string_enums(&mut value);
-
-        serde_json::from_value(value)
-            .map_err(|e| anyhow::anyhow!("Failed to parse response into GetQuotesResponse: {}", e))
+            projects: projects.iter().map(|p| *p as i32).collect(),
+        })
+        .await
     }
 
     pub async fn get_raydium_prices(
         &self,
         tokens: Vec<String>,
     ) -> Result<api::GetRaydiumPricesResponse> {
-        let mut url = format!("{}/api/v2/raydium/prices?", self.base_url);
-        for (i, token) in tokens.iter().enumerate() {
-            if i > 0 {
-                url.push('&');
-            }
-            url.push_str(&format!("tokens={}", token));
-        }
-
-        let response = self.client.get(&url).send().await?;
-        self.handle_response(response).await
+        self.get_raydium_prices_request(&api::GetRaydiumPricesRequest { tokens })
+            .await
     }
 
     pub async fn get_jupiter_prices(
         &self,
         tokens: Vec<String>,
     ) -> Result<api::GetJupiterPricesResponse> {
-        let mut url = format!("{}/api/v2/jupiter/prices?", self.base_url);
-        for (i, token) in tokens.iter().enumerate() {
-            if i > 0 {
-                url.push('&');
-            }
-            url.push_str(&format!("tokens={}", token));
+        self.get_jupiter_prices_request(&api::GetJupiterPricesRequest { tokens })
+            .await
+    }
+
+    // Polls the quote endpoint, yielding only changed responses.
+    pub fn quote_stream(
+        &self,
+        request: &api::GetQuotesRequest,
+        interval: impl Into<PollInterval>,
+    ) -> impl Stream<Item = Result<api::GetQuotesResponse>> + Unpin + '_ {
+        let request = request.clone();
+
+        poll_stream(interval.into(), move || {
+            let request = request.clone();
+            async move { self.get_quotes_request(&request).await }
+        })
+    }
+
+    // Venues that fail or miss the deadline are reported in `failures` instead of
+    // failing the whole call.
+    pub async fn get_best_quotes(&self, request: &BestQuoteRequest) -> Result<AggregatedQuotes> {
+        let deadline = request.deadline;
+        let (raydium_req, cpmm_req, clmm_req, jupiter_req) = (
+            request.raydium(),
+            request.raydium_cpmm(),
+            request.raydium_clmm(),
+            request.jupiter(),
+        );
+
+        let (raydium, cpmm, clmm, jupiter, pump_fun) = tokio::join!(
+            timeout(deadline, self.get_raydium_quotes(&raydium_req)),
+            timeout(deadline, self.get_raydium_cpmm_quotes(&cpmm_req)),
+            timeout(deadline, self.get_raydium_clmm_quotes(&clmm_req)),
+            timeout(deadline, self.get_jupiter_quotes(&jupiter_req)),
+            async {
+                let req = request.pump_fun()?;
+                Some(timeout(deadline, self.get_pump_fun_quotes(&req)).await)
+            },
+        );
+
+        let mut quotes = AggregatedQuotes::default();
+        quotes.record(Venue::Raydium, raydium);
+        quotes.record(Venue::RaydiumCpmm, cpmm);
+        quotes.record(Venue::RaydiumClmm, clmm);
+        quotes.record(Venue::Jupiter, jupiter);
+        if let Some(pump_fun) = pump_fun {
+            quotes.record(Venue::PumpFun, pump_fun);
         }
 
-        let response = self.client.get(&url).send().await?;
-        self.handle_response(response).await
+        quotes.finish()
     }
 }
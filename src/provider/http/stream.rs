@@ -0,0 +1,169 @@
+use anyhow::{anyhow, Result};
+use futures_util::Stream;
+use solana_trader_proto::api;
+
+use super::HTTPClient;
+use crate::provider::polling::{poll_items, poll_stream};
+
+// HTTP has no server push, so these emulate the gRPC and WebSocket streams by polling the
+// matching REST endpoints every `poll_interval` and yielding only changed results. The REST
+// responses carry no slot, so the items' `slot` is left unset.
+impl HTTPClient {
+    pub async fn get_prices_stream(
+        &self,
+        projects: Vec<api::Project>,
+        tokens: Vec<String>,
+    ) -> Result<impl Stream<Item = Result<api::GetPricesStreamResponse>> + Unpin + '_> {
+        for project in &projects {
+            if !matches!(project, api::Project::PRaydium | api::Project::PJupiter) {
+                return Err(anyhow!(
+                    "Prices polling is not supported for project {:?}",
+                    project
+                ));
+            }
+        }
+
+        Ok(poll_items(self.poll_interval, move || {
+            let projects = projects.clone();
+            let tokens = tokens.clone();
+            async move {
+                let mut prices: Vec<api::GetPricesStreamResponse> = Vec::new();
+                for project in projects {
+                    let token_prices = match project {
+                        api::Project::PJupiter => {
+                            self.get_jupiter_prices(tokens.clone()).await?.token_prices
+                        }
+                        _ => self.get_raydium_prices(tokens.clone()).await?.token_prices,
+                    };
+                    prices.extend(
+                        token_prices
+                            .into_iter()
+                            .map(|price| token_price(project, price)),
+                    );
+                }
+                Ok::<_, anyhow::Error>(prices)
+            }
+        }))
+    }
+
+    pub async fn get_recent_block_hash_stream(
+        &self,
+    ) -> Result<impl Stream<Item = Result<api::GetRecentBlockHashResponse>> + Unpin + '_> {
+        Ok(poll_stream(self.poll_interval, move || {
+            self.get_recent_block_hash()
+        }))
+    }
+
+    pub async fn get_priority_fee_stream(
+        &self,
+        project: api::Project,
+        percentile: Option<f64>,
+    ) -> Result<impl Stream<Item = Result<api::GetPriorityFeeResponse>> + Unpin + '_> {
+        Ok(poll_stream(self.poll_interval, move || {
+            self.get_priority_fee(project, percentile)
+        }))
+    }
+
+    // Only Raydium pool reserves are served over REST.
+    pub async fn get_pool_reserves_stream(
+        &self,
+        projects: Vec<api::Project>,
+        pools: Vec<String>,
+    ) -> Result<impl Stream<Item = Result<api::GetPoolReservesStreamResponse>> + Unpin + '_> {
+        for project in &projects {
+            if !matches!(project, api::Project::PRaydium) {
+                return Err(anyhow!(
+                    "Pool reserves polling is not supported for project {:?}",
+                    project
+                ));
+            }
+        }
+        if pools.is_empty() {
+            return Err(anyhow!("Pool reserves polling requires at least one pool"));
+        }
+
+        Ok(poll_items(self.poll_interval, move || {
+            let pools = pools.clone();
+            async move {
+                let request = api::GetRaydiumPoolReserveRequest {
+                    pairs_or_addresses: pools,
+                };
+                let response = self.get_raydium_pool_reserve(&request).await?;
+                Ok::<_, anyhow::Error>(response.pools.into_iter().map(pool_reserves).collect())
+            }
+        }))
+    }
+}
+
+fn token_price(project: api::Project, price: api::TokenPriceV2) -> api::GetPricesStreamResponse {
+    api::GetPricesStreamResponse {
+        price: Some(api::TokenPrice {
+            token: price.token,
+            token_address: price.token_address,
+            project: project as i32,
+            buy: price.buy,
+            buy_size: price.buy_size,
+            sell: price.sell,
+            sell_size: price.sell_size,
+        }),
+        ..Default::default()
+    }
+}
+
+fn pool_reserves(pool: api::ProjectPool) -> api::GetPoolReservesStreamResponse {
+    api::GetPoolReservesStreamResponse {
+        reserves: Some(api::PoolReserves {
+            token1_reserves: pool.token1_reserves.to_string(),
+            token1_address: pool.token1_mint_address,
+            token2_reserves: pool.token2_reserves.to_string(),
+            token2_address: pool.token2_mint_address,
+            pool_address: pool.pool_address,
+            project: api::Project::PRaydium as i32,
+        }),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_reserves() {
+        let pool = api::ProjectPool {
+            pool_address: "pool".to_string(),
+            token1_reserves: 1_000,
+            token1_mint_address: "SOL".to_string(),
+            token2_reserves: 2_000,
+            token2_mint_address: "USDC".to_string(),
+            ..Default::default()
+        };
+
+        let reserves = pool_reserves(pool).reserves.unwrap();
+        assert_eq!(reserves.pool_address, "pool");
+        assert_eq!(reserves.token1_reserves, "1000");
+        assert_eq!(reserves.token1_address, "SOL");
+        assert_eq!(reserves.token2_reserves, "2000");
+        assert_eq!(reserves.token2_address, "USDC");
+        assert_eq!(reserves.project, api::Project::PRaydium as i32);
+    }
+
+    #[test]
+    fn test_token_price() {
+        let price = api::TokenPriceV2 {
+            token: "SOL".to_string(),
+            buy: 150.5,
+            sell: 150.0,
+            ..Default::default()
+        };
+
+        let response = token_price(api::Project::PJupiter, price);
+        assert_eq!(response.slot, 0);
+        let price = response.price.unwrap();
+        assert_eq!(
+            (price.token.as_str(), price.buy, price.sell),
+            ("SOL", 150.5, 150.0)
+        );
+        assert_eq!(price.project, api::Project::PJupiter as i32);
+    }
+}
@@ -1,66 +1,24 @@
 use crate::{
-    common::signing::SubmitParams,
-    provider::utils::{
-        convert_address_lookup_table, convert_jupiter_instructions, convert_raydium_instructions,
-        create_transaction_message,
+    common::{signer::new_versioned_transaction, signing::SubmitParams},
+    provider::{
+        guard::{
+            best_quoted_out_amount, jupiter_quotes_request, quotes_request, raydium_quotes_request,
+            verify_swap_output, SwapTolerance,
+        },
+        utils::{
+            convert_address_lookup_table, convert_jupiter_instructions,
+            convert_raydium_instructions, create_transaction_message,
+        },
     },
 };
 
 use super::HTTPClient;
 use anyhow::Result;
 use base64::{engine::general_purpose, Engine};
-use solana_sdk::{
-    message::{v0, VersionedMessage},
-    transaction::VersionedTransaction,
-};
+use solana_sdk::message::{v0, VersionedMessage};
 use solana_trader_proto::api;
 
 impl HTTPClient {
-    pub async fn post_raydium_swap(
-        &self,
-        request: &api::PostRaydiumSwapRequest,
-    ) -> Result<api::PostRaydiumSwapResponse> {
-        let response = self
-            .client
-            .post(format!("{}/api/v2/raydium/swap", self.base_url))
-            .json(&request)
-            .send()
-            .await?;
-
-        self.handle_response(response).await
-    }
-
-    pub async fn post_raydium_route_swap(
-        &self,
-        request: &api::PostRaydiumRouteSwapRequest,
-    ) -> Result<api::PostRaydiumRouteSwapResponse> {
-        let response = self
-            .client
-            .post(format!("{}/api/v2/raydium/route-swap", self.base_url))
-            .json(&request)
-            .send()
-            .await?;
-
-        self.handle_response(response).await
-    }
-
-    pub async fn post_raydium_swap_instructions(
-        &self,
-        request: &api::PostRaydiumSwapInstructionsRequest,
-    ) -> Result<api::PostRaydiumSwapInstructionsResponse> {
-        let response = self
-            .client
-            .post(format!(
-                "{}/api/v2/raydium/swap-instructions",
-                self.base_url
-            ))
-            .json(&request)
-            .send()
-            .await?;
-
-        self.handle_response(response).await
-    }
-
     pub async fn submit_raydium_swap_instructions(
         &self,
         request: api::PostRaydiumSwapInstructionsRequest,
@@ -89,100 +47,13 @@ impl HTTPClient {
             .await
     }
 
-    pub async fn post_raydium_cpmm_swap(
-        &self,
-        request: &api::PostRaydiumCpmmSwapRequest,
-    ) -> Result<api::PostRaydiumCpmmSwapResponse> {
-        let response = self
-            .client
-            .post(format!("{}/api/v2/raydium/cpmm-swap", self.base_url))
-            .json(&request)
-            .send()
-            .await?;
-
-        self.handle_response(response).await
-    }
-
-    pub async fn post_raydium_clmm_swap(
-        &self,
-        request: &api::PostRaydiumSwapRequest,
-    ) -> Result<api::PostRaydiumSwapResponse> {
-        let response = self
-            .client
-            .post(format!("{}/api/v2/raydium/clmm-swap", self.base_url))
-            .json(&request)
-            .send()
-            .await?;
-
-        self.handle_response(response).await
-    }
-
-    pub async fn post_raydium_clmm_route_swap(
-        &self,
-        request: &api::PostRaydiumRouteSwapRequest,
-    ) -> Result<api::PostRaydiumRouteSwapResponse> {
-        let response = self
-            .client
-            .post(format!("{}/api/v2/raydium/clmm-route-swap", self.base_url))
-            .json(&request)
-            .send()
-            .await?;
-
-        self.handle_response(response).await
-    }
-
-    pub async fn post_jupiter_swap(
-        &self,
-        request: &api::PostJupiterSwapRequest,
-    ) -> Result<api::PostJupiterSwapResponse> {
-        let response = self
-            .client
-            .post(format!("{}/api/v2/jupiter/swap", self.base_url))
-            .json(&request)
-            .send()
-            .await?;
-
-        self.handle_response(response).await
-    }
-
-    pub async fn post_jupiter_route_swap(
-        &self,
-        request: &api::PostJupiterRouteSwapRequest,
-    ) -> Result<api::PostJupiterRouteSwapResponse> {
-        let response = self
-            .client
-            .post(format!("{}/api/v2/jupiter/route-swap", self.base_url))
-            .json(&request)
-            .send()
-            .await?;
-
-        self.handle_response(response).await
-    }
-
-    pub async fn post_jupiter_swap_instructions(
-        &self,
-        request: &api::PostJupiterSwapInstructionsRequest,
-    ) -> Result<api::PostJupiterSwapInstructionsResponse> {
-        let response = self
-            .client
-            .post(format!(
-                "{}/api/v2/jupiter/swap-instructions",
-                self.base_url
-            ))
-            .json(&request)
-            .send()
-            .await?;
-
-        self.handle_response(response).await
-    }
-
     pub async fn submit_jupiter_swap_instructions(
         &self,
         request: api::PostJupiterSwapInstructionsRequest,
         submit_opts: SubmitParams,
         use_bundle: bool,
     ) -> Result<Vec<String>> {
-        let keypair = self.get_keypair()?;
+        let signer = self.get_signer()?;
 
         let swap_instructions = self.post_jupiter_swap_instructions(&request).await?;
 
@@ -210,42 +81,93 @@ impl HTTPClient {
             blockhash_response.block_hash.parse()?,
         )?);
 
-        let tx: VersionedTransaction = VersionedTransaction::try_new(message, &[keypair])?;
+        let tx = new_versioned_transaction(message, signer.as_ref()).await?;
 
         let tx_message = api::TransactionMessage {
             content: general_purpose::STANDARD.encode(bincode::serialize(&tx)?),
             is_cleanup: false,
         };
 
-        self.sign_and_submit(vec![tx_message], submit_opts, use_bundle)
-            .await
+        self.sign_and_submit_with_lookup_tables(
+            vec![tx_message],
+            &swap_instructions.address_lookup_table_addresses,
+            submit_opts,
+            use_bundle,
+        )
+        .await
+    }
+
+    pub async fn submit_pump_swap(
+        &self,
+        request: &api::PostPumpFunSwapRequest,
+        submit_opts: SubmitParams,
+        use_bundle: bool,
+    ) -> Result<Vec<String>> {
+        let response = self.post_pump_swap(request).await?;
+        let txs: Vec<api::TransactionMessage> = response.transaction.into_iter().collect();
+        if txs.is_empty() {
+            return Err(anyhow::anyhow!("PostPumpFunSwap returned no transaction"));
+        }
+
+        self.sign_and_submit(txs, submit_opts, use_bundle).await
     }
 
-    pub async fn post_trade_swap(
+    // Fetches a quote, requests the swap and only signs it if the swap's output
+    // stays within `tolerance` of the quote.
+    pub async fn quote_then_swap(
         &self,
         request: &api::TradeSwapRequest,
-    ) -> Result<api::TradeSwapResponse> {
-        let response = self
-            .client
-            .post(format!("{}/api/v2/trade/swap", self.base_url))
-            .json(&request)
-            .send()
+        tolerance: &SwapTolerance,
+        submit_opts: SubmitParams,
+        use_bundle: bool,
+    ) -> Result<Vec<String>> {
+        let quotes = self.get_quotes_request(&quotes_request(request)).await?;
+        let quoted_out_amount = best_quoted_out_amount(&quotes)?;
+
+        let swap = self.post_trade_swap(request).await?;
+        verify_swap_output(quoted_out_amount, &swap, tolerance)?;
+
+        self.sign_and_submit(swap.transactions, submit_opts, use_bundle)
+            .await
+    }
+
+    // Like `quote_then_swap`, against a Raydium quote.
+    pub async fn quote_then_raydium_swap(
+        &self,
+        request: &api::PostRaydiumSwapRequest,
+        tolerance: &SwapTolerance,
+        submit_opts: SubmitParams,
+        use_bundle: bool,
+    ) -> Result<Vec<String>> {
+        let quotes = self
+            .get_raydium_quotes(&raydium_quotes_request(request))
             .await?;
+        let quoted_out_amount = best_quoted_out_amount(&quotes)?;
+
+        let swap = self.post_raydium_swap(request).await?;
+        verify_swap_output(quoted_out_amount, &swap, tolerance)?;
 
-        self.handle_response(response).await
+        self.sign_and_submit(swap.transactions, submit_opts, use_bundle)
+            .await
     }
 
-    pub async fn post_route_trade_swap(
+    // Like `quote_then_swap`, against a Jupiter quote.
+    pub async fn quote_then_jupiter_swap(
         &self,
-        request: &api::RouteTradeSwapRequest,
-    ) -> Result<api::TradeSwapResponse> {
-        let response = self
-            .client
-            .post(format!("{}/api/v2/trade/route-swap", self.base_url))
-            .json(&request)
-            .send()
+        request: &api::PostJupiterSwapRequest,
+        tolerance: &SwapTolerance,
+        submit_opts: SubmitParams,
+        use_bundle: bool,
+    ) -> Result<Vec<String>> {
+        let quotes = self
+            .get_jupiter_quotes(&jupiter_quotes_request(request))
             .await?;
+        let quoted_out_amount = best_quoted_out_amount(&quotes)?;
+
+        let swap = self.post_jupiter_swap(request).await?;
+        verify_swap_output(quoted_out_amount, &swap, tolerance)?;
 
-        self.handle_response(response).await
+        self.sign_and_submit(swap.transactions, submit_opts, use_bundle)
+            .await
     }
 }
@@ -1,4 +1,17 @@
+pub mod aggregator;
+pub mod amm;
+pub mod cache;
+pub mod codec;
+pub mod endpoints;
+pub mod enums;
 pub mod grpc;
+pub mod guard;
 pub mod http;
+pub mod orderbook;
+pub mod parity;
+pub mod polling;
+pub mod pump;
+pub mod route;
+pub mod shared;
 pub mod utils;
 pub mod ws;
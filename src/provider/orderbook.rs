@@ -0,0 +1,630 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::future::Future;
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use solana_trader_proto::api;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Level {
+    pub price: f64,
+    pub size: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Side {
+    Bid,
+    Ask,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum BookStatus {
+    Applied,
+    // update held back until the book has a snapshot to apply it to
+    Buffered,
+    // older than the book's current slot
+    Stale,
+    // slots were skipped; the book is unsynced until the next snapshot
+    Gap { last_slot: u64, slot: u64 },
+    Crossed,
+}
+
+// Positive finite prices order the same way as their bit patterns, so they can key a BTreeMap.
+fn price_key(price: f64) -> Result<u64> {
+    if price.is_finite() && price > 0.0 {
+        Ok(price.to_bits())
+    } else {
+        Err(anyhow!("Invalid orderbook price {}", price))
+    }
+}
+
+// Updates held for an unsynced book; past this the oldest are dropped, since the snapshot
+// that resyncs the book covers them anyway.
+pub const MAX_PENDING_UPDATES: usize = 1024;
+
+#[derive(Debug, Clone)]
+struct PendingUpdate {
+    slot: u64,
+    bids: Vec<Level>,
+    asks: Vec<Level>,
+}
+
+#[derive(Debug, Clone)]
+pub struct OrderBook {
+    pub market: String,
+    pub slot: u64,
+    // a newer update further than this many slots ahead counts as a gap
+    pub max_slot_gap: Option<u64>,
+    bids: BTreeMap<u64, f64>,
+    asks: BTreeMap<u64, f64>,
+    synced: bool,
+    pending: VecDeque<PendingUpdate>,
+}
+
+impl OrderBook {
+    pub fn new(market: &str) -> Self {
+        Self {
+            market: market.to_string(),
+            slot: 0,
+            max_slot_gap: None,
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+            synced: false,
+            pending: VecDeque::new(),
+        }
+    }
+
+    pub fn is_synced(&self) -> bool {
+        self.synced
+    }
+
+    // Newest slot among the buffered updates.
+    pub fn pending_slot(&self) -> Option<u64> {
+        self.pending.iter().map(|update| update.slot).max()
+    }
+
+    // Replaces every level, then replays buffered updates newer than the snapshot. An invalid
+    // snapshot leaves the book untouched.
+    pub fn apply_snapshot(
+        &mut self,
+        slot: u64,
+        bids: &[Level],
+        asks: &[Level],
+    ) -> Result<BookStatus> {
+        let (bids, asks) = (levels_map(bids)?, levels_map(asks)?);
+        self.bids = bids;
+        self.asks = asks;
+        self.slot = slot;
+        self.synced = true;
+
+        let mut status = self.status();
+        for update in std::mem::take(&mut self.pending) {
+            if update.slot > slot {
+                status = self.apply_update(update.slot, &update.bids, &update.asks)?;
+            }
+        }
+        Ok(status)
+    }
+
+    // Levels are absolute sizes at a price; a size of zero removes the level.
+    pub fn apply_update(
+        &mut self,
+        slot: u64,
+        bids: &[Level],
+        asks: &[Level],
+    ) -> Result<BookStatus> {
+        if !self.synced {
+            self.buffer(slot, bids, asks);
+            return Ok(BookStatus::Buffered);
+        }
+        if slot < self.slot {
+            return Ok(BookStatus::Stale);
+        }
+        if let Some(max_gap) = self.max_slot_gap {
+            if slot - self.slot > max_gap {
+                let last_slot = self.slot;
+                self.synced = false;
+                self.buffer(slot, bids, asks);
+                return Ok(BookStatus::Gap { last_slot, slot });
+            }
+        }
+
+        for level in bids {
+            upsert(&mut self.bids, level)?;
+        }
+        for level in asks {
+            upsert(&mut self.asks, level)?;
+        }
+        self.slot = slot;
+
+        Ok(self.status())
+    }
+
+    pub fn best_bid(&self) -> Option<Level> {
+        self.bids.iter().next_back().map(to_level)
+    }
+
+    pub fn best_ask(&self) -> Option<Level> {
+        self.asks.iter().next().map(to_level)
+    }
+
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.best_ask()?.price - self.best_bid()?.price)
+    }
+
+    pub fn is_crossed(&self) -> bool {
+        match (self.best_bid(), self.best_ask()) {
+            (Some(bid), Some(ask)) => bid.price >= ask.price,
+            _ => false,
+        }
+    }
+
+    // Best `levels` levels per side, best first.
+    pub fn depth(&self, levels: usize) -> (Vec<Level>, Vec<Level>) {
+        (
+            self.bids.iter().rev().take(levels).map(to_level).collect(),
+            self.asks.iter().take(levels).map(to_level).collect(),
+        )
+    }
+
+    // Average price to fill `size` against `side`, or None when the book is too thin.
+    pub fn vwap(&self, side: Side, size: f64) -> Option<f64> {
+        if size <= 0.0 {
+            return None;
+        }
+
+        let levels: Box<dyn Iterator<Item = (&u64, &f64)>> = match side {
+            Side::Bid => Box::new(self.bids.iter().rev()),
+            Side::Ask => Box::new(self.asks.iter()),
+        };
+
+        let mut remaining = size;
+        let mut notional = 0.0;
+        for level in levels.map(to_level) {
+            let fill = remaining.min(level.size);
+            notional += fill * level.price;
+            remaining -= fill;
+            if remaining <= 0.0 {
+                return Some(notional / size);
+            }
+        }
+        None
+    }
+
+    fn buffer(&mut self, slot: u64, bids: &[Level], asks: &[Level]) {
+        if self.pending.len() >= MAX_PENDING_UPDATES {
+            self.pending.pop_front();
+        }
+        self.pending.push_back(PendingUpdate {
+            slot,
+            bids: bids.to_vec(),
+            asks: asks.to_vec(),
+        });
+    }
+
+    fn status(&self) -> BookStatus {
+        if self.is_crossed() {
+            BookStatus::Crossed
+        } else {
+            BookStatus::Applied
+        }
+    }
+}
+
+fn levels_map(levels: &[Level]) -> Result<BTreeMap<u64, f64>> {
+    let mut map = BTreeMap::new();
+    for level in levels {
+        upsert(&mut map, level)?;
+    }
+    Ok(map)
+}
+
+fn upsert(map: &mut BTreeMap<u64, f64>, level: &Level) -> Result<()> {
+    let key = price_key(level.price)?;
+    if level.size > 0.0 {
+        map.insert(key, level.size);
+    } else {
+        map.remove(&key);
+    }
+    Ok(())
+}
+
+fn to_level((price, size): (&u64, &f64)) -> Level {
+    Level {
+        price: f64::from_bits(*price),
+        size: *size,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UpdateMode {
+    // every message carries the full top-of-book and replaces it
+    #[default]
+    Snapshot,
+    // messages change individual levels on top of a `get_orderbook` snapshot
+    Delta,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BookLevels {
+    pub slot: u64,
+    pub market: String,
+    pub bids: Vec<Level>,
+    pub asks: Vec<Level>,
+}
+
+pub trait BookMessage {
+    fn levels(&self) -> Result<BookLevels>;
+}
+
+impl BookMessage for api::GetOrderbooksStreamResponse {
+    fn levels(&self) -> Result<BookLevels> {
+        let book = self
+            .orderbook
+            .as_ref()
+            .ok_or_else(|| anyhow!("Orderbook message has no orderbook"))?;
+
+        book_levels(
+            self.slot,
+            &book.market,
+            &book.market_address,
+            orderbook_levels(&book.bids),
+            orderbook_levels(&book.asks),
+        )
+    }
+}
+
+impl BookMessage for api::GetMarketDepthsStreamResponse {
+    fn levels(&self) -> Result<BookLevels> {
+        let book = self
+            .data
+            .as_ref()
+            .ok_or_else(|| anyhow!("Market depth message has no data"))?;
+        let side = |items: &[api::MarketDepthItem]| -> Vec<Level> {
+            items
+                .iter()
+                .map(|item| Level {
+                    price: item.price,
+                    size: item.size,
+                })
+                .collect()
+        };
+
+        book_levels(
+            self.slot,
+            &book.market,
+            &book.market_address,
+            side(&book.bids),
+            side(&book.asks),
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct OrderBooks {
+    pub mode: UpdateMode,
+    pub max_slot_gap: Option<u64>,
+    books: HashMap<String, OrderBook>,
+}
+
+impl OrderBooks {
+    pub fn new(mode: UpdateMode, max_slot_gap: Option<u64>) -> Self {
+        Self {
+            mode,
+            max_slot_gap,
+            books: HashMap::new(),
+        }
+    }
+
+    pub fn book(&self, market: &str) -> Option<&OrderBook> {
+        self.books.get(market)
+    }
+
+    pub fn markets(&self) -> impl Iterator<Item = &String> {
+        self.books.keys()
+    }
+
+    // Feeds one message from `get_orderbook_stream` or `get_market_depths_stream`. In delta
+    // mode a new market, or one that saw a gap, buffers its messages until it gets a snapshot
+    // through `apply_orderbook` or `apply_or_bootstrap`.
+    pub fn apply<M: BookMessage>(&mut self, message: &M) -> Result<(String, BookStatus)> {
+        let BookLevels {
+            slot,
+            market,
+            bids,
+            asks,
+        } = message.levels()?;
+        let book = self.books.entry(market.clone()).or_insert_with(|| {
+            let mut book = OrderBook::new(&market);
+            book.max_slot_gap = self.max_slot_gap;
+            book
+        });
+
+        let status = match self.mode {
+            UpdateMode::Snapshot if slot < book.slot => BookStatus::Stale,
+            UpdateMode::Snapshot => book.apply_snapshot(slot, &bids, &asks)?,
+            UpdateMode::Delta => book.apply_update(slot, &bids, &asks)?,
+        };
+        Ok((market, status))
+    }
+
+    // Applies a `get_orderbook` snapshot to `market` and replays its buffered updates newer
+    // than `slot`. GetOrderbookResponse carries no slot, so `slot` is the newest slot the
+    // snapshot is known to cover.
+    pub fn apply_orderbook(
+        &mut self,
+        market: &str,
+        slot: u64,
+        snapshot: &api::GetOrderbookResponse,
+    ) -> Result<BookStatus> {
+        let book = self.books.entry(market.to_string()).or_insert_with(|| {
+            let mut book = OrderBook::new(market);
+            book.max_slot_gap = self.max_slot_gap;
+            book
+        });
+
+        book.apply_snapshot(
+            slot,
+            &orderbook_levels(&snapshot.bids),
+            &orderbook_levels(&snapshot.asks),
+        )
+    }
+
+    // Like `apply`, but when the market is left waiting for a snapshot, fetches one with
+    // `fetch` (usually a client's `get_orderbook`). The snapshot is requested after the
+    // buffered updates arrived, so it counts as of the newest of them.
+    pub async fn apply_or_bootstrap<M, F, Fut>(
+        &mut self,
+        message: &M,
+        fetch: F,
+    ) -> Result<(String, BookStatus)>
+    where
+        M: BookMessage,
+        F: FnOnce(String) -> Fut,
+        Fut: Future<Output = Result<api::GetOrderbookResponse>>,
+    {
+        let (market, status) = self.apply(message)?;
+        let pending_slot = match self.books.get(&market) {
+            Some(book) if self.mode == UpdateMode::Delta && !book.is_synced() => {
+                book.pending_slot().unwrap_or(book.slot)
+            }
+            _ => return Ok((market, status)),
+        };
+
+        let snapshot = fetch(market.clone()).await?;
+        let status = self.apply_orderbook(&market, pending_slot, &snapshot)?;
+        Ok((market, status))
+    }
+}
+
+fn orderbook_levels(items: &[api::OrderbookItem]) -> Vec<Level> {
+    items
+        .iter()
+        .map(|item| Level {
+            price: item.price,
+            size: item.size,
+        })
+        .collect()
+}
+
+fn book_levels(
+    slot: i64,
+    market: &str,
+    market_address: &str,
+    bids: Vec<Level>,
+    asks: Vec<Level>,
+) -> Result<BookLevels> {
+    let market = if market.is_empty() {
+        market_address
+    } else {
+        market
+    };
+    if market.is_empty() {
+        return Err(anyhow!("Orderbook message has no market"));
+    }
+
+    Ok(BookLevels {
+        slot: u64::try_from(slot).unwrap_or_default(),
+        market: market.to_string(),
+        bids,
+        asks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn levels(levels: &[(f64, f64)]) -> Vec<Level> {
+        levels
+            .iter()
+            .map(|&(price, size)| Level { price, size })
+            .collect()
+    }
+
+    #[test]
+    fn test_book_queries() -> Result<()> {
+        let mut book = OrderBook::new("SOL/USDC");
+        book.apply_snapshot(
+            1,
+            &levels(&[(99.0, 1.0), (98.0, 2.0), (97.0, 5.0)]),
+            &levels(&[(101.0, 1.0), (102.0, 3.0)]),
+        )?;
+
+        assert_eq!(book.best_bid().unwrap().price, 99.0);
+        assert_eq!(book.best_ask().unwrap().price, 101.0);
+        assert_eq!(book.spread(), Some(2.0));
+
+        let (bids, asks) = book.depth(2);
+        assert_eq!(bids, levels(&[(99.0, 1.0), (98.0, 2.0)]));
+        assert_eq!(asks, levels(&[(101.0, 1.0), (102.0, 3.0)]));
+
+        // 1 @ 101 + 2 @ 102
+        assert_eq!(book.vwap(Side::Ask, 3.0), Some(305.0 / 3.0));
+        assert_eq!(book.vwap(Side::Bid, 3.0), Some(295.0 / 3.0));
+        assert_eq!(book.vwap(Side::Ask, 10.0), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_updates_buffering_gaps_and_crossing() -> Result<()> {
+        let mut book = OrderBook::new("SOL/USDC");
+        book.max_slot_gap = Some(5);
+
+        // buffered until the snapshot, then replayed if newer
+        assert_eq!(
+            book.apply_update(3, &levels(&[(99.5, 1.0)]), &[])?,
+            BookStatus::Buffered
+        );
+        assert_eq!(
+            book.apply_update(1, &levels(&[(50.0, 1.0)]), &[])?,
+            BookStatus::Buffered
+        );
+        book.apply_snapshot(2, &levels(&[(99.0, 1.0)]), &levels(&[(101.0, 1.0)]))?;
+        assert_eq!(book.best_bid().unwrap().price, 99.5);
+        assert_eq!(book.depth(10).0.len(), 2);
+
+        assert_eq!(book.apply_update(1, &[], &[])?, BookStatus::Stale);
+        assert_eq!(
+            book.apply_update(4, &levels(&[(99.5, 0.0)]), &[])?,
+            BookStatus::Applied
+        );
+        assert_eq!(book.best_bid().unwrap().price, 99.0);
+
+        assert_eq!(
+            book.apply_update(5, &levels(&[(102.0, 1.0)]), &[])?,
+            BookStatus::Crossed
+        );
+        assert!(book.is_crossed());
+
+        assert_eq!(
+            book.apply_update(20, &[], &[])?,
+            BookStatus::Gap {
+                last_slot: 5,
+                slot: 20
+            }
+        );
+        assert!(!book.is_synced());
+        assert_eq!(book.apply_update(21, &[], &[])?, BookStatus::Buffered);
+        assert!(book
+            .apply_snapshot(22, &levels(&[(-1.0, 1.0)]), &[])
+            .is_err());
+
+        // a bad ask side does not swap in the bids either
+        assert!(book
+            .apply_snapshot(22, &levels(&[(10.0, 1.0)]), &levels(&[(f64::NAN, 1.0)]))
+            .is_err());
+        assert_eq!(book.best_bid().unwrap().price, 102.0);
+        assert!(!book.is_synced());
+
+        for slot in 0..MAX_PENDING_UPDATES as u64 + 10 {
+            book.apply_update(30 + slot, &[], &[])?;
+        }
+        assert_eq!(book.pending.len(), MAX_PENDING_UPDATES);
+        Ok(())
+    }
+
+    fn delta(slot: i64, bids: &[(f64, f64)]) -> api::GetOrderbooksStreamResponse {
+        let items = |levels: &[(f64, f64)]| {
+            levels
+                .iter()
+                .map(|&(price, size)| api::OrderbookItem {
+                    price,
+                    size,
+                    ..Default::default()
+                })
+                .collect()
+        };
+        api::GetOrderbooksStreamResponse {
+            slot,
+            orderbook: Some(api::GetOrderbookResponse {
+                market: "SOL/USDC".to_string(),
+                bids: items(bids),
+                asks: items(&[(101.0, 1.0)]),
+                ..Default::default()
+            }),
+            timestamp: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delta_bootstrap_from_snapshot() -> Result<()> {
+        let mut books = OrderBooks::new(UpdateMode::Delta, Some(5));
+        let snapshot = api::GetOrderbookResponse {
+            market: "SOL/USDC".to_string(),
+            bids: vec![api::OrderbookItem {
+                price: 98.0,
+                size: 4.0,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        // the first delta is buffered, not taken as the whole book
+        let (market, status) = books.apply(&delta(10, &[(99.0, 1.0)]))?;
+        assert_eq!(status, BookStatus::Buffered);
+        assert!(!books.book(&market).unwrap().is_synced());
+
+        let (_, status) = books
+            .apply_or_bootstrap(&delta(11, &[(99.5, 1.0)]), |market| {
+                assert_eq!(market, "SOL/USDC");
+                let snapshot = snapshot.clone();
+                async move { Ok(snapshot) }
+            })
+            .await?;
+        assert_eq!(status, BookStatus::Applied);
+        let book = books.book("SOL/USDC").unwrap();
+        assert_eq!((book.slot, book.best_bid().unwrap().price), (11, 98.0));
+
+        // deltas newer than the snapshot's slot are replayed on top of it
+        books.apply(&delta(30, &[]))?;
+        books.apply(&delta(31, &[(97.0, 2.0)]))?;
+        books.apply_orderbook("SOL/USDC", 30, &snapshot)?;
+        let book = books.book("SOL/USDC").unwrap();
+        assert_eq!(book.slot, 31);
+        assert_eq!(book.depth(10).0, levels(&[(98.0, 4.0), (97.0, 2.0)]));
+
+        let failed = books
+            .apply_or_bootstrap(&delta(50, &[]), |_| async {
+                Err::<api::GetOrderbookResponse, _>(anyhow!("unavailable"))
+            })
+            .await;
+        assert!(failed.is_err());
+        assert!(!books.book("SOL/USDC").unwrap().is_synced());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_stream_messages() -> Result<()> {
+        let depth = api::GetMarketDepthsStreamResponse {
+            slot: 42,
+            data: Some(api::GetMarketDepthResponse {
+                market_address: "SOL/USDC".to_string(),
+                bids: vec![api::MarketDepthItem {
+                    price: 99.0,
+                    size: 1.5,
+                }],
+                asks: vec![api::MarketDepthItem {
+                    price: 101.0,
+                    size: 2.0,
+                }],
+                ..Default::default()
+            }),
+            timestamp: None,
+        };
+        let book = depth.levels()?;
+        assert_eq!((book.slot, book.market.as_str()), (42, "SOL/USDC"));
+        assert_eq!(book.bids, levels(&[(99.0, 1.5)]));
+        assert_eq!(book.asks, levels(&[(101.0, 2.0)]));
+
+        assert!(api::GetMarketDepthsStreamResponse::default()
+            .levels()
+            .is_err());
+        let unnamed = api::GetMarketDepthsStreamResponse {
+            data: Some(api::GetMarketDepthResponse::default()),
+            ..Default::default()
+        };
+        assert!(unnamed.levels().is_err());
+        Ok(())
+    }
+}
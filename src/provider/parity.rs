@@ -0,0 +1,345 @@
+use solana_trader_proto::api::api_client::ApiClient;
+use tonic::transport::Channel;
+
+use super::grpc::GrpcClient;
+use super::http::HTTPClient;
+use super::ws::WebSocketClient;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Support {
+    // name of the client method serving the RPC
+    Method(&'static str),
+    // why the transport deliberately does not offer the RPC
+    OptOut(&'static str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    Grpc,
+    Http,
+    Ws,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Endpoint {
+    pub rpc: &'static str,
+    pub grpc: Support,
+    pub http: Support,
+    pub ws: Support,
+}
+
+impl Endpoint {
+    pub fn support(&self, transport: Transport) -> Support {
+        match transport {
+            Transport::Grpc => self.grpc,
+            Transport::Http => self.http,
+            Transport::Ws => self.ws,
+        }
+    }
+}
+
+const HTTP_NO_STREAM: &str = "no REST endpoint to poll for this stream";
+
+const OPENBOOK_MARKET_DATA: &str =
+    "Openbook market listings, tickers and depth; the SDK quotes and trades through AMMs";
+const OPENBOOK_ORDERS: &str = "Openbook limit orders; the SDK trades through AMM swaps only";
+const INTERNAL: &str = "restricted to internal callers by the API's method visibility";
+const SUBMIT_V1: &str = "superseded by PostSubmitV2 and PostSubmitBatchV2";
+const ZETA: &str = "Zeta perpetuals are outside the SDK's spot trading scope";
+
+macro_rules! support {
+    (($reason:expr)) => {
+        Support::OptOut($reason)
+    };
+    ($method:ident) => {
+        Support::Method(stringify!($method))
+    };
+}
+
+macro_rules! check_method {
+    ($client:ty, ($reason:expr)) => {};
+    ($client:ty, $method:ident) => {
+        let _ = <$client>::$method;
+    };
+}
+
+// Every row is checked at compile time: the RPC must exist on the generated `ApiClient` and
+// each named method must exist on its transport. RPCs the SDK does not wrap on any transport
+// go in `NOT_OFFERED` instead, and `test_every_rpc_is_listed` checks the two against
+// `API_RPCS`.
+macro_rules! api_parity {
+    ($($rpc:ident => { grpc: $grpc:tt, http: $http:tt, ws: $ws:tt },)*) => {
+        pub const API_PARITY: &[Endpoint] = &[$(
+            Endpoint {
+                rpc: stringify!($rpc),
+                grpc: support!($grpc),
+                http: support!($http),
+                ws: support!($ws),
+            },
+        )*];
+
+        #[allow(dead_code)]
+        fn assert_methods_exist() {
+            $(
+                let _ = |client: &mut ApiClient<Channel>| {
+                    let _ = client.$rpc(tonic::Request::new(Default::default()));
+                };
+                check_method!(GrpcClient, $grpc);
+                check_method!(HTTPClient, $http);
+                check_method!(WebSocketClient, $ws);
+            )*
+        }
+    };
+}
+
+api_parity! {
+    get_account_balance => { grpc: get_account_balance, http: get_account_balance, ws: get_account_balance },
+    get_account_balance_v2 => { grpc: get_account_balance_v2, http: get_account_balance_v2, ws: get_account_balance_v2 },
+    get_priority_fee => { grpc: get_priority_fee, http: get_priority_fee, ws: get_priority_fee },
+    get_priority_fee_by_program => { grpc: get_priority_fee_by_program, http: get_priority_fee_by_program, ws: get_priority_fee_by_program },
+    get_rate_limit => { grpc: get_rate_limit, http: get_rate_limit, ws: get_rate_limit },
+    get_recent_block_hash => { grpc: get_recent_block_hash, http: get_recent_block_hash, ws: get_recent_block_hash },
+    get_recent_block_hash_v2 => { grpc: get_recent_block_hash_v2, http: get_recent_block_hash_v2, ws: get_recent_block_hash_v2 },
+    get_token_accounts => { grpc: get_token_accounts, http: get_token_accounts, ws: get_token_accounts },
+    get_transaction => { grpc: get_transaction, http: get_transaction, ws: get_transaction },
+
+    get_quotes => { grpc: get_quotes, http: get_quotes, ws: get_quotes },
+    get_raydium_quotes => { grpc: get_raydium_quotes, http: get_raydium_quotes, ws: get_raydium_quotes },
+    get_raydium_clmm_quotes => { grpc: get_raydium_clmm_quotes, http: get_raydium_clmm_quotes, ws: get_raydium_clmm_quotes },
+    get_raydium_cpmm_quotes => { grpc: get_raydium_cpmm_quotes, http: get_raydium_cpmm_quotes, ws: get_raydium_cpmm_quotes },
+    get_jupiter_quotes => { grpc: get_jupiter_quotes, http: get_jupiter_quotes, ws: get_jupiter_quotes },
+    get_pump_fun_quotes => { grpc: get_pump_fun_quotes, http: get_pump_fun_quotes, ws: get_pump_fun_quotes },
+    get_raydium_prices => { grpc: get_raydium_prices, http: get_raydium_prices, ws: get_raydium_prices },
+    get_jupiter_prices => { grpc: get_jupiter_prices, http: get_jupiter_prices, ws: get_jupiter_prices },
+    get_orderbook => { grpc: get_orderbook, http: get_orderbook, ws: get_orderbook },
+
+    post_trade_swap => { grpc: post_trade_swap, http: post_trade_swap, ws: post_trade_swap },
+    post_route_trade_swap => { grpc: post_route_trade_swap, http: post_route_trade_swap, ws: post_route_trade_swap },
+    post_raydium_swap => { grpc: post_raydium_swap, http: post_raydium_swap, ws: post_raydium_swap },
+    post_raydium_route_swap => { grpc: post_raydium_route_swap, http: post_raydium_route_swap, ws: post_raydium_route_swap },
+    post_raydium_swap_instructions => { grpc: post_raydium_swap_instructions, http: post_raydium_swap_instructions, ws: post_raydium_swap_instructions },
+    post_raydium_clmm_swap => { grpc: post_raydium_clmm_swap, http: post_raydium_clmm_swap, ws: post_raydium_clmm_swap },
+    post_raydium_clmm_route_swap => { grpc: post_raydium_clmm_route_swap, http: post_raydium_clmm_route_swap, ws: post_raydium_clmm_route_swap },
+    post_raydium_cpmm_swap => { grpc: post_raydium_cpmm_swap, http: post_raydium_cpmm_swap, ws: post_raydium_cpmm_swap },
+    post_jupiter_swap => { grpc: post_jupiter_swap, http: post_jupiter_swap, ws: post_jupiter_swap },
+    post_jupiter_route_swap => { grpc: post_jupiter_route_swap, http: post_jupiter_route_swap, ws: post_jupiter_route_swap },
+    post_jupiter_swap_instructions => { grpc: post_jupiter_swap_instructions, http: post_jupiter_swap_instructions, ws: post_jupiter_swap_instructions },
+    post_pump_fun_swap => { grpc: post_pump_swap, http: post_pump_swap, ws: post_pump_swap },
+    post_submit_v2 => { grpc: submit_signed, http: submit_signed, ws: submit_signed },
+    post_submit_batch_v2 => { grpc: submit_signed, http: submit_signed, ws: submit_signed },
+
+    get_block_stream => { grpc: get_block_stream, http: (HTTP_NO_STREAM), ws: get_block_stream },
+    get_bundle_tip_stream => { grpc: get_bundle_tip_stream, http: (HTTP_NO_STREAM), ws: get_bundle_tip_stream },
+    get_prices_stream => { grpc: get_prices_stream, http: get_prices_stream, ws: get_prices_stream },
+    get_priority_fee_stream => { grpc: get_priority_fee_stream, http: get_priority_fee_stream, ws: get_priority_fee_stream },
+    get_pool_reserves_stream => { grpc: get_pool_reserves_stream, http: get_pool_reserves_stream, ws: get_pool_reserves_stream },
+    get_recent_block_hash_stream => { grpc: get_recent_block_hash_stream, http: get_recent_block_hash_stream, ws: get_recent_block_hash_stream },
+    get_orderbooks_stream => { grpc: get_orderbook_stream, http: (HTTP_NO_STREAM), ws: get_orderbook_stream },
+    get_market_depths_stream => { grpc: get_market_depths_stream, http: (HTTP_NO_STREAM), ws: get_market_depths_stream },
+    get_tickers_stream => { grpc: get_ticker_stream, http: (HTTP_NO_STREAM), ws: get_ticker_stream },
+    get_trades_stream => { grpc: get_trades_stream, http: (HTTP_NO_STREAM), ws: get_trades_stream },
+    get_swaps_stream => { grpc: get_swaps_stream, http: (HTTP_NO_STREAM), ws: get_swaps_stream },
+    get_new_raydium_pools_stream => { grpc: get_new_raydium_pools_stream, http: (HTTP_NO_STREAM), ws: get_new_raydium_pools_stream },
+    get_new_raydium_pools_by_transaction_stream => { grpc: get_new_raydium_pools_by_transaction_stream, http: (HTTP_NO_STREAM), ws: get_new_raydium_pools_by_transaction_stream },
+    get_pump_fun_new_tokens_stream => { grpc: get_pump_fun_new_tokens_stream, http: (HTTP_NO_STREAM), ws: get_pump_fun_new_tokens_stream },
+    get_pump_fun_swaps_stream => { grpc: get_pump_fun_swaps_stream, http: (HTTP_NO_STREAM), ws: get_pump_fun_swaps_stream },
+
+    get_raydium_pools => { grpc: get_raydium_pools, http: get_raydium_pools, ws: get_raydium_pools },
+    get_raydium_clmm_pools => { grpc: get_raydium_clmm_pools, http: get_raydium_clmm_pools, ws: get_raydium_clmm_pools },
+    get_raydium_pool_reserve => { grpc: get_raydium_pool_reserve, http: get_raydium_pool_reserve, ws: get_raydium_pool_reserve },
+    get_server_time => { grpc: get_server_time, http: get_server_time, ws: get_server_time },
+    post_pump_fun_swap_sol => { grpc: post_pump_fun_swap_sol, http: post_pump_fun_swap_sol, ws: post_pump_fun_swap_sol },
+}
+
+macro_rules! not_offered {
+    ($($rpc:ident => $reason:expr,)*) => {
+        // RPCs no transport wraps, with the reason.
+        pub const NOT_OFFERED: &[(&str, &str)] = &[$((stringify!($rpc), $reason),)*];
+
+        #[allow(dead_code)]
+        fn assert_not_offered_exist() {
+            $(
+                let _ = |client: &mut ApiClient<Channel>| {
+                    let _ = client.$rpc(tonic::Request::new(Default::default()));
+                };
+            )*
+        }
+    };
+}
+
+not_offered! {
+    get_markets => OPENBOOK_MARKET_DATA,
+    get_markets_v2 => OPENBOOK_MARKET_DATA,
+    get_tickers => OPENBOOK_MARKET_DATA,
+    get_tickers_v2 => OPENBOOK_MARKET_DATA,
+    get_orderbook_v2 => OPENBOOK_MARKET_DATA,
+    get_market_depth => OPENBOOK_MARKET_DATA,
+    get_market_depth_v2 => OPENBOOK_MARKET_DATA,
+    get_price => "superseded by GetRaydiumPrices and GetJupiterPrices",
+    get_pools => "superseded by GetRaydiumPools and GetRaydiumCLMMPools",
+    get_kline => INTERNAL,
+    get_trades => INTERNAL,
+    get_orders => INTERNAL,
+    get_order_by_id => INTERNAL,
+
+    post_order => OPENBOOK_ORDERS,
+    post_order_v2 => OPENBOOK_ORDERS,
+    post_cancel_order => OPENBOOK_ORDERS,
+    post_cancel_order_v2 => OPENBOOK_ORDERS,
+    post_cancel_by_client_order_id => OPENBOOK_ORDERS,
+    post_cancel_all => OPENBOOK_ORDERS,
+    post_replace_order => OPENBOOK_ORDERS,
+    post_replace_order_v2 => OPENBOOK_ORDERS,
+    post_replace_by_client_order_id => OPENBOOK_ORDERS,
+    post_settle => OPENBOOK_ORDERS,
+    post_settle_v2 => OPENBOOK_ORDERS,
+    get_open_orders => OPENBOOK_ORDERS,
+    get_open_orders_v2 => OPENBOOK_ORDERS,
+    get_unsettled => OPENBOOK_ORDERS,
+    get_unsettled_v2 => OPENBOOK_ORDERS,
+    get_order_status_stream => OPENBOOK_ORDERS,
+
+    post_submit => SUBMIT_V1,
+    post_submit_batch => SUBMIT_V1,
+    get_quotes_stream => "quote updates are polled from GetQuotes by `quote_stream` on every transport",
+    post_submit_mine_ore => "ORE mining submissions are outside the SDK's trading scope",
+    post_zeta_cross_margin_account => ZETA,
+    get_zeta_transaction_stream => ZETA,
+}
+
+macro_rules! api_rpcs {
+    ($version:literal: $($rpc:ident)*) => {
+        // The proto crate release `API_RPCS` was taken from; bumping the dependency means
+        // refreshing the list from its api.proto.
+        pub const API_RPCS_PROTO_VERSION: &str = $version;
+
+        // Every RPC of the Api service, checked in so that completeness can be verified without
+        // locating the generated sources.
+        pub const API_RPCS: &[&str] = &[$(stringify!($rpc),)*];
+
+        #[allow(dead_code)]
+        fn assert_api_rpcs_exist() {
+            $(
+                let _ = |client: &mut ApiClient<Channel>| {
+                    let _ = client.$rpc(tonic::Request::new(Default::default()));
+                };
+            )*
+        }
+    };
+}
+
+api_rpcs! {
+    "0.1.1":
+    get_rate_limit get_transaction post_submit_v2 post_submit_batch_v2
+    get_raydium_pools get_raydium_pool_reserve get_raydium_quotes get_pump_fun_quotes
+    get_raydium_cpmm_quotes get_raydium_prices get_raydium_clmm_quotes get_raydium_clmm_pools
+    post_raydium_clmm_swap post_raydium_clmm_route_swap post_raydium_swap post_raydium_route_swap
+    post_raydium_cpmm_swap get_jupiter_quotes get_jupiter_prices post_jupiter_swap
+    post_raydium_swap_instructions post_jupiter_swap_instructions post_jupiter_route_swap
+    get_markets_v2 get_tickers_v2 get_orderbook_v2 get_market_depth_v2 post_order_v2
+    post_cancel_order_v2 post_replace_order_v2 post_zeta_cross_margin_account post_settle_v2
+    get_open_orders_v2 get_unsettled_v2 get_price get_markets get_pools get_tickers get_kline
+    get_orderbook get_market_depth get_trades get_quotes get_server_time get_recent_block_hash
+    get_recent_block_hash_v2 get_priority_fee get_priority_fee_by_program get_account_balance
+    get_token_accounts get_account_balance_v2 post_order post_submit post_submit_batch
+    post_cancel_order post_cancel_by_client_order_id post_cancel_all
+    post_replace_by_client_order_id post_replace_order post_settle post_trade_swap get_orders
+    get_open_orders get_order_by_id get_unsettled post_route_trade_swap post_submit_mine_ore
+    get_orderbooks_stream get_market_depths_stream get_tickers_stream get_zeta_transaction_stream
+    get_trades_stream get_order_status_stream get_recent_block_hash_stream get_block_stream
+    get_priority_fee_stream get_bundle_tip_stream get_quotes_stream get_pool_reserves_stream
+    get_prices_stream get_new_raydium_pools_stream get_new_raydium_pools_by_transaction_stream
+    get_swaps_stream get_pump_fun_swaps_stream get_pump_fun_new_tokens_stream post_pump_fun_swap
+    post_pump_fun_swap_sol
+}
+
+pub fn endpoint(rpc: &str) -> Option<&'static Endpoint> {
+    API_PARITY.iter().find(|endpoint| endpoint.rpc == rpc)
+}
+
+// RPCs a transport opts out of, with the reason given.
+pub fn opt_outs(transport: Transport) -> Vec<(&'static str, &'static str)> {
+    API_PARITY
+        .iter()
+        .filter_map(|endpoint| match endpoint.support(transport) {
+            Support::OptOut(reason) => Some((endpoint.rpc, reason)),
+            Support::Method(_) => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_parity_table_entries() {
+        let mut seen = BTreeSet::new();
+        for endpoint in API_PARITY {
+            assert!(seen.insert(endpoint.rpc), "{} listed twice", endpoint.rpc);
+            assert!(
+                matches!(endpoint.grpc, Support::Method(_)),
+                "{} must be implemented over gRPC",
+                endpoint.rpc
+            );
+            for transport in [Transport::Grpc, Transport::Http, Transport::Ws] {
+                if let Support::OptOut(reason) = endpoint.support(transport) {
+                    assert!(
+                        !reason.is_empty(),
+                        "{} opts out without a reason",
+                        endpoint.rpc
+                    );
+                }
+            }
+        }
+        assert!(opt_outs(Transport::Ws).is_empty());
+        assert!(endpoint("get_block_stream").is_some());
+    }
+
+    #[test]
+    fn test_every_rpc_is_listed() {
+        let listed: BTreeSet<&str> = API_PARITY.iter().map(|e| e.rpc).collect();
+        let not_offered: BTreeSet<&str> = NOT_OFFERED.iter().map(|(rpc, _)| *rpc).collect();
+        assert_eq!(
+            not_offered.len(),
+            NOT_OFFERED.len(),
+            "NOT_OFFERED has duplicates"
+        );
+        assert!(NOT_OFFERED.iter().all(|(_, reason)| !reason.is_empty()));
+
+        let both: Vec<_> = listed.intersection(&not_offered).collect();
+        assert!(
+            both.is_empty(),
+            "listed as both served and not offered: {:?}",
+            both
+        );
+
+        let rpcs: BTreeSet<&str> = API_RPCS.iter().copied().collect();
+        let covered: BTreeSet<&str> = listed.union(&not_offered).copied().collect();
+        let missing: Vec<_> = rpcs.difference(&covered).collect();
+        assert!(
+            missing.is_empty(),
+            "RPCs missing from API_PARITY: {:?}",
+            missing
+        );
+        let unknown: Vec<_> = covered.difference(&rpcs).collect();
+        assert!(
+            unknown.is_empty(),
+            "rows for RPCs not in API_RPCS: {:?}",
+            unknown
+        );
+    }
+
+    #[test]
+    fn test_rpc_list_matches_proto_dependency() {
+        let manifest = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"));
+        // pinned exactly, since any release can add or drop RPCs
+        let dependency = format!("solana-trader-proto = \"={}\"", API_RPCS_PROTO_VERSION);
+        assert!(
+            manifest.lines().any(|line| line.trim() == dependency),
+            "solana-trader-proto is no longer {}; refresh API_RPCS from its api.proto",
+            API_RPCS_PROTO_VERSION
+        );
+    }
+}
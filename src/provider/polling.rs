@@ -0,0 +1,196 @@
+use std::future::Future;
+use std::time::Duration;
+
+use anyhow::Result;
+use futures_util::{stream, Stream, StreamExt};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PollInterval {
+    pub interval: Duration,
+    // when set, each poll that brings nothing new stretches the wait by `backoff_factor`
+    // up to this bound; a changed result resets it to `interval`
+    pub max_interval: Option<Duration>,
+    pub backoff_factor: f64,
+}
+
+impl PollInterval {
+    pub fn fixed(interval: Duration) -> Self {
+        Self {
+            interval,
+            max_interval: None,
+            backoff_factor: 1.0,
+        }
+    }
+
+    pub fn adaptive(interval: Duration, max_interval: Duration) -> Self {
+        Self {
+            interval,
+            max_interval: Some(max_interval),
+            backoff_factor: 2.0,
+        }
+    }
+
+    pub fn next(&self, current: Duration, changed: bool) -> Duration {
+        match self.max_interval {
+            // scaled in f64 and clamped before converting, since a huge or infinite factor
+            // would overflow Duration
+            Some(max) if !changed => {
+                let secs = current.as_secs_f64() * self.backoff_factor.max(1.0);
+                Duration::try_from_secs_f64(secs.min(max.as_secs_f64()))
+                    .map_or(max, |next| next.min(max))
+            }
+            _ => self.interval,
+        }
+    }
+}
+
+impl From<Duration> for PollInterval {
+    fn from(interval: Duration) -> Self {
+        Self::fixed(interval)
+    }
+}
+
+struct PollState<T, F> {
+    fetch: F,
+    last: Option<T>,
+    delay: Duration,
+    first: bool,
+}
+
+// Polls `fetch` on the given schedule and yields only results that differ from the last
+// one yielded. Errors are yielded without ending the stream, so callers decide when to stop.
+pub fn poll_stream<T, F, Fut>(
+    interval: PollInterval,
+    fetch: F,
+) -> impl Stream<Item = Result<T>> + Unpin
+where
+    T: PartialEq + Clone,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let state = PollState {
+        fetch,
+        last: None,
+        delay: interval.interval,
+        first: true,
+    };
+
+    Box::pin(stream::unfold(state, move |mut state| async move {
+        loop {
+            if !state.first {
+                tokio::time::sleep(state.delay).await;
+            }
+            state.first = false;
+
+            match (state.fetch)().await {
+                Ok(value) if state.last.as_ref() == Some(&value) => {
+                    state.delay = interval.next(state.delay, false);
+                }
+                Ok(value) => {
+                    state.delay = interval.next(state.delay, true);
+                    state.last = Some(value.clone());
+                    return Some((Ok(value), state));
+                }
+                Err(e) => {
+                    state.delay = interval.next(state.delay, false);
+                    return Some((Err(e), state));
+                }
+            }
+        }
+    }))
+}
+
+// Like `poll_stream` for endpoints returning a batch, yielding each item that was not in
+// the previous batch.
+pub fn poll_items<T, F, Fut>(
+    interval: PollInterval,
+    fetch: F,
+) -> impl Stream<Item = Result<T>> + Unpin
+where
+    T: PartialEq + Clone,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Vec<T>>>,
+{
+    let mut previous: Vec<T> = Vec::new();
+
+    poll_stream(interval, fetch).flat_map(move |batch| {
+        let items: Vec<Result<T>> = match batch {
+            Ok(batch) => {
+                let changed = batch
+                    .iter()
+                    .filter(|item| !previous.contains(item))
+                    .cloned()
+                    .map(Ok)
+                    .collect();
+                previous = batch;
+                changed
+            }
+            Err(e) => vec![Err(e)],
+        };
+        stream::iter(items)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[test]
+    fn test_adaptive_interval() {
+        let interval =
+            PollInterval::adaptive(Duration::from_millis(100), Duration::from_millis(300));
+
+        let slower = interval.next(Duration::from_millis(100), false);
+        assert_eq!(slower, Duration::from_millis(200));
+        assert_eq!(interval.next(slower, false), Duration::from_millis(300));
+        assert_eq!(interval.next(slower, true), Duration::from_millis(100));
+
+        let unbounded = PollInterval {
+            backoff_factor: f64::INFINITY,
+            ..PollInterval::adaptive(Duration::from_millis(100), Duration::MAX)
+        };
+        assert_eq!(
+            unbounded.next(Duration::from_secs(u64::MAX / 2), false),
+            Duration::MAX
+        );
+
+        let fixed = PollInterval::from(Duration::from_millis(100));
+        assert_eq!(
+            fixed.next(Duration::from_millis(100), false),
+            Duration::from_millis(100)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_poll_stream_skips_duplicates() {
+        let mut responses = vec![
+            Ok(1),
+            Ok(1),
+            Err(anyhow!("unavailable")),
+            Ok(2),
+            Ok(2),
+            Ok(3),
+        ]
+        .into_iter();
+        let stream = poll_stream(Duration::from_millis(1).into(), move || {
+            let next = responses.next().unwrap_or(Ok(3));
+            async move { next }
+        });
+
+        let results: Vec<Option<i32>> = stream.take(4).map(|r| r.ok()).collect().await;
+        assert_eq!(results, vec![Some(1), None, Some(2), Some(3)]);
+    }
+
+    #[tokio::test]
+    async fn test_poll_items_yields_changed_items() {
+        let mut batches = vec![vec![1, 2], vec![1, 2], vec![1, 3], vec![4]].into_iter();
+        let stream = poll_items(Duration::from_millis(1).into(), move || {
+            let next = batches.next().unwrap_or_else(|| vec![4]);
+            async move { Ok::<_, anyhow::Error>(next) }
+        });
+
+        let results: Vec<i32> = stream.take(4).map(|r| r.unwrap()).collect().await;
+        assert_eq!(results, vec![1, 2, 3, 4]);
+    }
+}
@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::{Arc, RwLock};
+
+use anyhow::{anyhow, Result};
+use futures_util::{Stream, StreamExt};
+use serde::Serialize;
+use solana_trader_proto::api;
+use tokio::task::JoinHandle;
+
+// Every Pump.fun curve starts from the same virtual reserves and charges 1% on the SOL leg.
+pub const INITIAL_VIRTUAL_SOL_RESERVES: u64 = 30_000_000_000;
+pub const INITIAL_VIRTUAL_TOKEN_RESERVES: u64 = 1_073_000_000_000_000;
+pub const PUMP_FUN_FEE_BPS: u64 = 100;
+const BPS_DENOMINATOR: u64 = 10_000;
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+const TOKEN_UNITS: f64 = 1_000_000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum PumpQuoteType {
+    Buy,
+    Sell,
+}
+
+impl std::str::FromStr for PumpQuoteType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "buy" => Ok(Self::Buy),
+            "sell" => Ok(Self::Sell),
+            _ => Err(anyhow!("Unknown Pump.fun quote type: {}", s)),
+        }
+    }
+}
+
+// Amounts follow GetPumpFunQuotesResponse: SOL for the SOL leg, whole tokens for the token leg.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PumpFunQuote {
+    pub quote_type: PumpQuoteType,
+    pub mint_address: String,
+    pub in_amount: f64,
+    pub out_amount: f64,
+    pub fee: f64,
+    pub price: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BondingCurve {
+    pub mint_address: String,
+    pub bonding_curve_address: String,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub fee_bps: u64,
+    pub slot: u64,
+}
+
+impl BondingCurve {
+    pub fn new(mint_address: &str, bonding_curve_address: &str) -> Self {
+        Self {
+            mint_address: mint_address.to_string(),
+            bonding_curve_address: bonding_curve_address.to_string(),
+            virtual_sol_reserves: INITIAL_VIRTUAL_SOL_RESERVES,
+            virtual_token_reserves: INITIAL_VIRTUAL_TOKEN_RESERVES,
+            fee_bps: PUMP_FUN_FEE_BPS,
+            slot: 0,
+        }
+    }
+
+    // SOL per whole token
+    pub fn price(&self) -> f64 {
+        (self.virtual_sol_reserves as f64 / LAMPORTS_PER_SOL)
+            / (self.virtual_token_reserves as f64 / TOKEN_UNITS)
+    }
+
+    fn fee(&self, lamports: u128) -> u128 {
+        (lamports * self.fee_bps as u128).div_ceil(BPS_DENOMINATOR as u128)
+    }
+
+    // Raw units in and out: lamports in, token base units out, fee in lamports.
+    pub fn buy(&self, lamports_in: u64) -> Result<(u64, u64)> {
+        self.check_liquidity()?;
+        let fee = self.fee(lamports_in as u128);
+        let sol_after_fee = lamports_in as u128 - fee;
+        let tokens_out = self.virtual_token_reserves as u128 * sol_after_fee
+            / (self.virtual_sol_reserves as u128 + sol_after_fee);
+
+        Ok((tokens_out as u64, fee as u64))
+    }
+
+    // Token base units in, lamports out after the fee, fee in lamports.
+    pub fn sell(&self, tokens_in: u64) -> Result<(u64, u64)> {
+        self.check_liquidity()?;
+        let gross = self.virtual_sol_reserves as u128 * tokens_in as u128
+            / (self.virtual_token_reserves as u128 + tokens_in as u128);
+        let fee = self.fee(gross);
+
+        Ok(((gross - fee) as u64, fee as u64))
+    }
+
+    pub fn quote(&self, quote_type: PumpQuoteType, amount: f64) -> Result<PumpFunQuote> {
+        let (out_amount, fee) = match quote_type {
+            PumpQuoteType::Buy => {
+                let (tokens_out, fee) = self.buy((amount * LAMPORTS_PER_SOL) as u64)?;
+                (tokens_out as f64 / TOKEN_UNITS, fee)
+            }
+            PumpQuoteType::Sell => {
+                let (lamports_out, fee) = self.sell((amount * TOKEN_UNITS) as u64)?;
+                (lamports_out as f64 / LAMPORTS_PER_SOL, fee)
+            }
+        };
+
+        Ok(PumpFunQuote {
+            quote_type,
+            mint_address: self.mint_address.clone(),
+            in_amount: amount,
+            out_amount,
+            fee: fee as f64 / LAMPORTS_PER_SOL,
+            price: self.price(),
+        })
+    }
+
+    fn check_liquidity(&self) -> Result<()> {
+        if self.virtual_sol_reserves == 0 || self.virtual_token_reserves == 0 {
+            return Err(anyhow!(
+                "Bonding curve for {} has no reserves",
+                self.mint_address
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub trait PumpFunEvent {
+    fn apply_to(&self, quoter: &PumpFunQuoter) -> Result<()>;
+}
+
+impl PumpFunEvent for api::GetPumpFunNewTokensStreamResponse {
+    fn apply_to(&self, quoter: &PumpFunQuoter) -> Result<()> {
+        quoter.apply_new_token(self)
+    }
+}
+
+impl PumpFunEvent for api::GetPumpFunSwapsStreamResponse {
+    fn apply_to(&self, quoter: &PumpFunQuoter) -> Result<()> {
+        quoter.apply_swap(self)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct PumpFunQuoter {
+    curves: RwLock<HashMap<String, BondingCurve>>,
+}
+
+impl PumpFunQuoter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn curve(&self, mint_address: &str) -> Option<BondingCurve> {
+        self.curves.read().unwrap().get(mint_address).cloned()
+    }
+
+    pub fn insert(&self, curve: BondingCurve) {
+        self.curves
+            .write()
+            .unwrap()
+            .insert(curve.mint_address.clone(), curve);
+    }
+
+    pub fn apply<E: PumpFunEvent>(&self, event: &E) -> Result<()> {
+        event.apply_to(self)
+    }
+
+    // New tokens start on the initial curve; a swap seen earlier for the mint wins.
+    fn apply_new_token(&self, event: &api::GetPumpFunNewTokensStreamResponse) -> Result<()> {
+        check_mint(&event.mint)?;
+        let mut curve = BondingCurve::new(&event.mint, &event.bonding_curve);
+        curve.slot = u64::try_from(event.slot).unwrap_or_default();
+
+        self.curves
+            .write()
+            .unwrap()
+            .entry(event.mint.clone())
+            .or_insert(curve);
+        Ok(())
+    }
+
+    // Swap events carry the curve's virtual reserves after the trade.
+    fn apply_swap(&self, event: &api::GetPumpFunSwapsStreamResponse) -> Result<()> {
+        check_mint(&event.mint_address)?;
+        let slot = u64::try_from(event.slot).unwrap_or_default();
+
+        let mut curves = self.curves.write().unwrap();
+        let curve = curves.entry(event.mint_address.clone()).or_insert_with(|| {
+            BondingCurve::new(&event.mint_address, &event.bonding_curve_address)
+        });
+        if slot >= curve.slot {
+            curve.virtual_sol_reserves = event.virtual_sol_reserves;
+            curve.virtual_token_reserves = event.virtual_token_reserves;
+            curve.slot = slot;
+        }
+        Ok(())
+    }
+
+    pub fn quote(&self, request: &api::GetPumpFunQuotesRequest) -> Result<PumpFunQuote> {
+        let quote_type = request.quote_type.parse()?;
+        self.curve(&request.mint_address)
+            .ok_or_else(|| anyhow!("No bonding curve known for {}", request.mint_address))?
+            .quote(quote_type, request.amount)
+    }
+
+    // Keeps curves current from `get_pump_fun_new_tokens_stream` or `get_pump_fun_swaps_stream`
+    // of any transport. Bad events are logged and skipped; the task ends when the stream ends.
+    pub fn watch<S, M, E>(self: &Arc<Self>, stream: S) -> JoinHandle<()>
+    where
+        S: Stream<Item = std::result::Result<M, E>> + Send + Unpin + 'static,
+        M: PumpFunEvent + Send,
+        E: Display + Send,
+    {
+        let quoter = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut stream = stream;
+            while let Some(message) = stream.next().await {
+                let result = match message {
+                    Ok(event) => quoter.apply(&event),
+                    Err(e) => Err(anyhow!("{}", e)),
+                };
+                if let Err(e) = result {
+                    tracing::warn!("Pump.fun quoter skipped event: {}", e);
+                }
+            }
+        })
+    }
+}
+
+pub fn check_against_server(
+    local: &PumpFunQuote,
+    server: &api::GetPumpFunQuotesResponse,
+    tolerance_percent: f64,
+) -> Result<()> {
+    if server.out_amount <= 0.0 {
+        return Err(anyhow!("Server quote has no out amount"));
+    }
+
+    let deviation = (local.out_amount - server.out_amount).abs() / server.out_amount * 100.0;
+    if deviation > tolerance_percent {
+        return Err(anyhow!(
+            "Local quote {} deviates {:.4}% from server quote {}",
+            local.out_amount,
+            deviation,
+            server.out_amount
+        ));
+    }
+    Ok(())
+}
+
+fn check_mint(mint: &str) -> Result<()> {
+    if mint.is_empty() {
+        return Err(anyhow!("Pump.fun event has no mint"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bonding_curve_quotes() -> Result<()> {
+        let curve = BondingCurve::new("mint", "curve");
+        assert!((curve.price() - 0.000000027958993476).abs() < 1e-15);
+
+        // 1 SOL in: fee 0.01 SOL, 1.073e15 * 0.99e9 / 30.99e9 = 34_277_831_558_567 base units
+        let (tokens_out, fee) = curve.buy(1_000_000_000)?;
+        assert_eq!(fee, 10_000_000);
+        assert_eq!(tokens_out, 34_277_831_558_567);
+
+        // selling the same tokens back into the fresh curve returns less than 0.99 SOL
+        let (lamports_out, fee) = curve.sell(tokens_out)?;
+        assert_eq!(lamports_out, 919_418_385);
+        assert_eq!(fee, 9_287_055);
+
+        let quote = curve.quote("Buy".parse()?, 1.0)?;
+        assert_eq!(quote.out_amount, 34_277_831.558567);
+        assert_eq!(quote.fee, 0.01);
+        Ok(())
+    }
+
+    #[test]
+    fn test_quoter_tracks_events_and_matches_server() -> Result<()> {
+        let quoter = PumpFunQuoter::new();
+        quoter.apply(&api::GetPumpFunNewTokensStreamResponse {
+            slot: 5,
+            mint: "mint".to_string(),
+            bonding_curve: "curve".to_string(),
+            ..Default::default()
+        })?;
+        let swap = |slot: i64, virtual_sol_reserves: u64, virtual_token_reserves: u64| {
+            api::GetPumpFunSwapsStreamResponse {
+                slot,
+                mint_address: "mint".to_string(),
+                virtual_sol_reserves,
+                virtual_token_reserves,
+                ..Default::default()
+            }
+        };
+        quoter.apply(&swap(7, 60_000_000_000, 536_500_000_000_000))?;
+        // stale swap is ignored
+        quoter.apply(&swap(6, 1, 1))?;
+        assert!(quoter
+            .apply(&api::GetPumpFunSwapsStreamResponse::default())
+            .is_err());
+
+        let request = api::GetPumpFunQuotesRequest {
+            quote_type: "sell".to_string(),
+            mint_address: "mint".to_string(),
+            bonding_curve_address: "curve".to_string(),
+            amount: 1_000_000.0,
+        };
+        let local = quoter.quote(&request)?;
+        assert_eq!(quoter.curve("mint").unwrap().bonding_curve_address, "curve");
+
+        let server = api::GetPumpFunQuotesResponse {
+            in_amount: 1_000_000.0,
+            out_amount: 0.110_507,
+            ..Default::default()
+        };
+        check_against_server(&local, &server, 0.01)?;
+
+        let server = api::GetPumpFunQuotesResponse {
+            out_amount: 0.12,
+            ..server
+        };
+        assert!(check_against_server(&local, &server, 0.01).is_err());
+        assert!(quoter
+            .quote(&api::GetPumpFunQuotesRequest::default())
+            .is_err());
+        Ok(())
+    }
+}
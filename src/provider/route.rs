@@ -0,0 +1,388 @@
+use anyhow::{anyhow, Result};
+use solana_trader_proto::api;
+
+#[derive(Debug, Clone)]
+pub struct RouteConfig {
+    pub owner_address: String,
+    pub slippage: f64,
+    pub compute_limit: u32,
+    pub compute_price: u64,
+    pub tip: Option<u64>,
+}
+
+pub trait ChainedStep {
+    fn in_token(&self) -> &str;
+    fn out_token(&self) -> &str;
+    fn in_amount(&self) -> f64;
+    fn out_amount(&self) -> f64;
+}
+
+impl ChainedStep for api::RaydiumRouteStep {
+    fn in_token(&self) -> &str {
+        &self.in_token
+    }
+
+    fn out_token(&self) -> &str {
+        &self.out_token
+    }
+
+    fn in_amount(&self) -> f64 {
+        self.in_amount
+    }
+
+    fn out_amount(&self) -> f64 {
+        self.out_amount
+    }
+}
+
+impl ChainedStep for api::RouteStep {
+    fn in_token(&self) -> &str {
+        &self.in_token
+    }
+
+    fn out_token(&self) -> &str {
+        &self.out_token
+    }
+
+    fn in_amount(&self) -> f64 {
+        self.in_amount
+    }
+
+    fn out_amount(&self) -> f64 {
+        self.out_amount
+    }
+}
+
+impl ChainedStep for api::JupiterRouteStep {
+    fn in_token(&self) -> &str {
+        &self.in_token
+    }
+
+    fn out_token(&self) -> &str {
+        &self.out_token
+    }
+
+    fn in_amount(&self) -> f64 {
+        self.in_amount
+    }
+
+    fn out_amount(&self) -> f64 {
+        self.out_amount
+    }
+}
+
+pub fn validate_route_steps<S: ChainedStep>(steps: &[S]) -> Result<()> {
+    if steps.is_empty() {
+        return Err(anyhow!("Route has no steps"));
+    }
+
+    for (i, step) in steps.iter().enumerate() {
+        if step.in_amount() <= 0.0 || step.out_amount() <= 0.0 {
+            return Err(anyhow!("Route step {} has a non-positive amount", i));
+        }
+        if step.in_token() == step.out_token() {
+            return Err(anyhow!(
+                "Route step {} swaps {} into itself",
+                i,
+                step.in_token()
+            ));
+        }
+    }
+
+    for (i, pair) in steps.windows(2).enumerate() {
+        if pair[0].out_token() != pair[1].in_token() {
+            return Err(anyhow!(
+                "Route step {} outputs {} but step {} expects {}",
+                i,
+                pair[0].out_token(),
+                i + 1,
+                pair[1].in_token()
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// Quote responses whose routes can be swapped through, with each route's steps mapped to the
+// venue-neutral RouteStep.
+pub trait QuoteRoutes {
+    type Route;
+
+    fn routes(&self) -> &[Self::Route];
+    fn route_steps(route: &Self::Route) -> Vec<api::RouteStep>;
+}
+
+impl QuoteRoutes for api::GetRaydiumQuotesResponse {
+    type Route = api::RaydiumQuoteRoute;
+
+    fn routes(&self) -> &[Self::Route] {
+        &self.routes
+    }
+
+    fn route_steps(route: &Self::Route) -> Vec<api::RouteStep> {
+        raydium_route_steps(route)
+    }
+}
+
+impl QuoteRoutes for api::GetRaydiumCpmmQuotesResponse {
+    type Route = api::RaydiumQuoteRoute;
+
+    fn routes(&self) -> &[Self::Route] {
+        &self.routes
+    }
+
+    fn route_steps(route: &Self::Route) -> Vec<api::RouteStep> {
+        raydium_route_steps(route)
+    }
+}
+
+impl QuoteRoutes for api::GetRaydiumClmmQuotesResponse {
+    type Route = api::RaydiumQuoteRoute;
+
+    fn routes(&self) -> &[Self::Route] {
+        &self.routes
+    }
+
+    fn route_steps(route: &Self::Route) -> Vec<api::RouteStep> {
+        raydium_route_steps(route)
+    }
+}
+
+impl QuoteRoutes for api::GetJupiterQuotesResponse {
+    type Route = api::JupiterQuoteRoute;
+
+    fn routes(&self) -> &[Self::Route] {
+        &self.routes
+    }
+
+    fn route_steps(route: &Self::Route) -> Vec<api::RouteStep> {
+        route
+            .steps
+            .iter()
+            .map(|step| api::RouteStep {
+                in_token: step.in_token.clone(),
+                in_amount: step.in_amount,
+                out_token: step.out_token.clone(),
+                out_amount: step.out_amount,
+                out_amount_min: step.out_amount_min,
+                project: step.project.clone(),
+                fee: step.fee.clone(),
+            })
+            .collect()
+    }
+}
+
+fn raydium_route_steps(route: &api::RaydiumQuoteRoute) -> Vec<api::RouteStep> {
+    route
+        .steps
+        .iter()
+        .map(|step| api::RouteStep {
+            in_token: step.in_token.clone(),
+            in_amount: step.in_amount,
+            out_token: step.out_token.clone(),
+            out_amount: step.out_amount,
+            out_amount_min: step.out_amount_min,
+            project: step.project.clone(),
+            fee: step.fee.clone(),
+        })
+        .collect()
+}
+
+// Works for the Raydium AMM, CPMM and CLMM quote responses, whose routes share RaydiumQuoteRoute.
+pub fn raydium_route_swap_request<Q: QuoteRoutes<Route = api::RaydiumQuoteRoute>>(
+    quotes: &Q,
+    route_index: usize,
+    config: &RouteConfig,
+) -> Result<api::PostRaydiumRouteSwapRequest> {
+    let steps: Vec<api::RaydiumRouteStep> = quote_route_steps(quotes, route_index)?
+        .into_iter()
+        .map(|step| api::RaydiumRouteStep {
+            in_token: step.in_token,
+            out_token: step.out_token,
+            in_amount: step.in_amount,
+            out_amount: step.out_amount,
+            out_amount_min: step.out_amount_min,
+            // quote steps do not name their pool
+            pool_address: String::new(),
+            project: step.project,
+        })
+        .collect();
+    validate_route_steps(&steps)?;
+
+    Ok(api::PostRaydiumRouteSwapRequest {
+        owner_address: config.owner_address.clone(),
+        slippage: config.slippage,
+        steps,
+        compute_limit: config.compute_limit,
+        compute_price: config.compute_price,
+        tip: config.tip,
+    })
+}
+
+pub fn jupiter_route_swap_request(
+    quotes: &api::GetJupiterQuotesResponse,
+    route_index: usize,
+    config: &RouteConfig,
+) -> Result<api::PostJupiterRouteSwapRequest> {
+    let steps: Vec<api::JupiterRouteStep> = quote_route_steps(quotes, route_index)?
+        .into_iter()
+        .map(|step| api::JupiterRouteStep {
+            project: step.project,
+            in_token: step.in_token,
+            out_token: step.out_token,
+            in_amount: step.in_amount,
+            out_amount: step.out_amount,
+            out_amount_min: step.out_amount_min,
+            fee: step.fee,
+        })
+        .collect();
+    validate_route_steps(&steps)?;
+
+    Ok(api::PostJupiterRouteSwapRequest {
+        owner_address: config.owner_address.clone(),
+        slippage: config.slippage,
+        steps,
+        compute_limit: config.compute_limit,
+        compute_price: config.compute_price,
+        tip: config.tip,
+    })
+}
+
+// Works for any venue's quote response; `project` picks the venue that executes the route.
+pub fn route_trade_swap_request<Q: QuoteRoutes>(
+    quotes: &Q,
+    route_index: usize,
+    project: api::Project,
+    config: &RouteConfig,
+) -> Result<api::RouteTradeSwapRequest> {
+    let steps = quote_route_steps(quotes, route_index)?;
+    validate_route_steps(&steps)?;
+
+    Ok(api::RouteTradeSwapRequest {
+        project: project as i32,
+        owner_address: config.owner_address.clone(),
+        steps,
+        slippage: config.slippage,
+        compute_limit: config.compute_limit,
+        compute_price: config.compute_price,
+        tip: config.tip,
+    })
+}
+
+fn quote_route_steps<Q: QuoteRoutes>(
+    quotes: &Q,
+    route_index: usize,
+) -> Result<Vec<api::RouteStep>> {
+    let route = quotes
+        .routes()
+        .get(route_index)
+        .ok_or_else(|| anyhow!("Quote response has no route {}", route_index))?;
+
+    Ok(Q::route_steps(route))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(in_token: &str, out_token: &str) -> api::RaydiumRouteStep {
+        api::RaydiumRouteStep {
+            in_token: in_token.to_string(),
+            out_token: out_token.to_string(),
+            in_amount: 1.0,
+            out_amount: 1.0,
+            out_amount_min: 0.9,
+            pool_address: String::new(),
+            project: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_route_steps() {
+        assert!(validate_route_steps(&[step("A", "B"), step("B", "C")]).is_ok());
+        assert!(validate_route_steps(&[step("A", "B"), step("C", "D")]).is_err());
+        assert!(validate_route_steps(&[step("A", "A")]).is_err());
+        assert!(validate_route_steps::<api::RaydiumRouteStep>(&[]).is_err());
+    }
+
+    // A two-hop SOL -> USDC -> BONK quote in the REST gateway's JSON shape.
+    const ROUTE: &str = r#"{
+        "inAmount": 1, "outAmount": 4000000, "outAmountMin": 3960000,
+        "steps": [
+            {
+                "project": {"label": "Raydium", "id": "58oQ"},
+                "inToken": "SOL", "inTokenAddress": "So11111111111111111111111111111111111111112",
+                "outToken": "USDC", "outTokenAddress": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+                "inAmount": 1, "outAmount": 150, "outAmountMin": 148.5, "slippage": 1,
+                "priceImpactPercent": {"percent": 0.01, "infinity": "NOT"},
+                "fee": {"amount": 0.0025, "mint": "So11111111111111111111111111111111111111112", "percent": 0.25}
+            },
+            {
+                "project": {"label": "Raydium", "id": "Hs97"},
+                "inToken": "USDC", "inTokenAddress": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+                "outToken": "BONK", "outTokenAddress": "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263",
+                "inAmount": 150, "outAmount": 4000000, "outAmountMin": 3960000, "slippage": 1,
+                "priceImpactPercent": {"percent": 0.02, "infinity": "NOT"},
+                "fee": {"amount": 0.375, "mint": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", "percent": 0.25}
+            }
+        ]
+    }"#;
+
+    fn quotes_json() -> String {
+        format!(
+            r#"{{"inToken": "SOL", "inTokenAddress": "So11111111111111111111111111111111111111112",
+                "outToken": "BONK", "outTokenAddress": "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263",
+                "inAmount": 1, "routes": [{}]}}"#,
+            ROUTE
+        )
+    }
+
+    #[test]
+    fn test_requests_from_quote_response() -> Result<()> {
+        let config = RouteConfig {
+            owner_address: "owner".to_string(),
+            slippage: 0.5,
+            compute_limit: 200_000,
+            compute_price: 10_000,
+            tip: Some(1_000),
+        };
+        let raydium_quotes: api::GetRaydiumQuotesResponse =
+            crate::provider::enums::from_str(&quotes_json())?;
+        let jupiter_quotes: api::GetJupiterQuotesResponse =
+            crate::provider::enums::from_str(&quotes_json())?;
+
+        let raydium = raydium_route_swap_request(&raydium_quotes, 0, &config)?;
+        assert_eq!(raydium.steps.len(), 2);
+        assert_eq!(raydium.steps[1].in_token, "USDC");
+        assert_eq!(raydium.steps[1].out_amount_min, 3960000.0);
+        assert_eq!(raydium.steps[0].project.as_ref().unwrap().id, "58oQ");
+        assert_eq!(
+            (raydium.compute_limit, raydium.compute_price, raydium.tip),
+            (200_000, 10_000, Some(1_000))
+        );
+
+        let jupiter = jupiter_route_swap_request(&jupiter_quotes, 0, &config)?;
+        assert_eq!(jupiter.steps.len(), 2);
+        assert_eq!(jupiter.steps[0].fee.as_ref().unwrap().percent, 0.25);
+        assert_eq!(jupiter.tip, Some(1_000));
+
+        let trade = route_trade_swap_request(&raydium_quotes, 0, api::Project::PRaydium, &config)?;
+        assert_eq!(trade.project, api::Project::PRaydium as i32);
+        assert_eq!(trade.owner_address, "owner");
+        assert_eq!(trade.steps[0].out_token, "USDC");
+        assert_eq!(
+            trade.steps[1].fee.as_ref().unwrap().mint,
+            "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
+        );
+        assert_eq!(
+            (trade.compute_limit, trade.compute_price, trade.tip),
+            (200_000, 10_000, Some(1_000))
+        );
+
+        assert!(
+            route_trade_swap_request(&raydium_quotes, 1, api::Project::PRaydium, &config).is_err()
+        );
+        Ok(())
+    }
+}
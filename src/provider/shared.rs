@@ -0,0 +1,389 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, Weak};
+use std::task::{Context, Poll};
+
+use anyhow::{anyhow, Result};
+use futures_util::{ready, Stream, StreamExt};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::BroadcastStream;
+
+use crate::connections::backpressure::DropCounters;
+
+const DEFAULT_CAPACITY: usize = 1024;
+
+type Item<T> = std::result::Result<T, Arc<anyhow::Error>>;
+
+// Deduplicates identical stream subscriptions: the first consumer of a key opens the upstream
+// stream, later ones receive the same items over a broadcast channel, and the upstream is dropped
+// (which unsubscribes it) as soon as the last consumer goes away.
+#[derive(Debug)]
+pub struct SharedStreams {
+    capacity: usize,
+    // One lock per key being opened, so concurrent consumers of a new key share one upstream
+    // without holding up other keys.
+    opening: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+    entries: Mutex<HashMap<String, Weak<dyn Any + Send + Sync>>>,
+}
+
+type Sender<T> = Arc<Mutex<Option<broadcast::Sender<Item<T>>>>>;
+
+struct Entry<T> {
+    // Later consumers subscribe through the sender, so no idle receiver holds on to items. The
+    // task takes it once the upstream ends, which closes the channel for every consumer.
+    sender: Sender<T>,
+    counters: Option<DropCounters>,
+    task: JoinHandle<()>,
+}
+
+impl<T> Drop for Entry<T> {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl Default for SharedStreams {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl SharedStreams {
+    // `capacity` is how many items a consumer can fall behind before it starts missing them.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            opening: Mutex::new(HashMap::new()),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Number of upstream streams currently shared.
+    pub fn active(&self) -> usize {
+        self.entries
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|entry| entry.strong_count() > 0)
+            .count()
+    }
+
+    pub async fn subscribe<T, S, F, Fut>(&self, key: String, open: F) -> Result<SharedStream<T>>
+    where
+        T: Clone + Send + Sync + 'static,
+        S: Stream<Item = Result<T>> + Send + 'static,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<S>>,
+    {
+        self.subscribe_counted(key, || async { Ok((open().await?, None)) })
+            .await
+    }
+
+    // Like `subscribe`, for upstreams that count what their backpressure policy discards; every
+    // consumer of the key sees the same counters.
+    pub async fn subscribe_counted<T, S, F, Fut>(
+        &self,
+        key: String,
+        open: F,
+    ) -> Result<SharedStream<T>>
+    where
+        T: Clone + Send + Sync + 'static,
+        S: Stream<Item = Result<T>> + Send + 'static,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<(S, Option<DropCounters>)>>,
+    {
+        let lock = self
+            .opening
+            .lock()
+            .unwrap()
+            .entry(key.clone())
+            .or_default()
+            .clone();
+        let guard = lock.lock().await;
+
+        let result = self.open(&key, open).await;
+
+        drop(guard);
+        let mut opening = self.opening.lock().unwrap();
+        if opening
+            .get(&key)
+            .is_some_and(|current| Arc::ptr_eq(current, &lock) && Arc::strong_count(&lock) == 2)
+        {
+            opening.remove(&key);
+        }
+
+        result
+    }
+
+    async fn open<T, S, F, Fut>(&self, key: &str, open: F) -> Result<SharedStream<T>>
+    where
+        T: Clone + Send + Sync + 'static,
+        S: Stream<Item = Result<T>> + Send + 'static,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<(S, Option<DropCounters>)>>,
+    {
+        if let Some(stream) = self.join::<T>(key)? {
+            return Ok(stream);
+        }
+
+        let (upstream, counters) = open().await?;
+        let (sender, receiver) = broadcast::channel(self.capacity);
+        let sender: Sender<T> = Arc::new(Mutex::new(Some(sender)));
+
+        let task = {
+            let sender = sender.clone();
+            tokio::spawn(async move {
+                let mut upstream = Box::pin(upstream);
+                while let Some(item) = upstream.next().await {
+                    if let Some(sender) = sender.lock().unwrap().as_ref() {
+                        let _ = sender.send(item.map_err(Arc::new));
+                    }
+                }
+                sender.lock().unwrap().take();
+            })
+        };
+
+        let entry = Arc::new(Entry {
+            sender,
+            counters,
+            task,
+        });
+
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, entry| entry.strong_count() > 0);
+        let shared: Arc<dyn Any + Send + Sync> = entry.clone();
+        entries.insert(key.to_string(), Arc::downgrade(&shared));
+
+        // The first consumer keeps the channel's own receiver, so it sees every item sent.
+        Ok(SharedStream::new(entry, receiver))
+    }
+
+    fn join<T: Clone + Send + Sync + 'static>(&self, key: &str) -> Result<Option<SharedStream<T>>> {
+        let Some(entry) = self
+            .entries
+            .lock()
+            .unwrap()
+            .get(key)
+            .and_then(Weak::upgrade)
+        else {
+            return Ok(None);
+        };
+
+        let entry = entry
+            .downcast::<Entry<T>>()
+            .map_err(|_| anyhow!("Shared stream {} has a different item type", key))?;
+
+        // An upstream that already ended is reopened rather than joined.
+        let receiver = entry
+            .sender
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(broadcast::Sender::subscribe);
+        Ok(receiver.map(|receiver| SharedStream::new(entry, receiver)))
+    }
+}
+
+// One consumer of a shared stream. Items are delivered to every consumer; a consumer that falls
+// more than the channel capacity behind skips the missed items, counted by `lagged`.
+pub struct SharedStream<T> {
+    entry: Arc<Entry<T>>,
+    items: BroadcastStream<Item<T>>,
+    lagged: u64,
+}
+
+impl<T: Clone + Send + 'static> SharedStream<T> {
+    fn new(entry: Arc<Entry<T>>, receiver: broadcast::Receiver<Item<T>>) -> Self {
+        Self {
+            entry,
+            items: BroadcastStream::new(receiver),
+            lagged: 0,
+        }
+    }
+}
+
+impl<T> SharedStream<T> {
+    pub fn lagged(&self) -> u64 {
+        self.lagged
+    }
+
+    // The upstream subscription's backpressure counters, for streams that have them.
+    pub fn counters(&self) -> Option<DropCounters> {
+        self.entry.counters.clone()
+    }
+}
+
+impl<T: Clone + Send + 'static> Stream for SharedStream<T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<T>>> {
+        loop {
+            match ready!(self.items.poll_next_unpin(cx)) {
+                Some(Ok(item)) => {
+                    return Poll::Ready(Some(
+                        item.map_err(|e| anyhow!("Shared stream error: {:#}", e)),
+                    ))
+                }
+                Some(Err(BroadcastStreamRecvError::Lagged(missed))) => self.lagged += missed,
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+    use tokio::time::{sleep, timeout};
+    use tokio_stream::wrappers::ReceiverStream;
+
+    struct Upstream {
+        opened: Arc<AtomicUsize>,
+        sender: Mutex<Option<mpsc::Sender<Result<u64>>>>,
+    }
+
+    impl Upstream {
+        fn new() -> Self {
+            Self {
+                opened: Arc::new(AtomicUsize::new(0)),
+                sender: Mutex::new(None),
+            }
+        }
+
+        async fn open(&self) -> Result<ReceiverStream<Result<u64>>> {
+            self.opened.fetch_add(1, Ordering::SeqCst);
+            let (sender, receiver) = mpsc::channel(16);
+            *self.sender.lock().unwrap() = Some(sender);
+            Ok(ReceiverStream::new(receiver))
+        }
+
+        async fn send(&self, value: u64) {
+            let sender = self.sender.lock().unwrap().clone().unwrap();
+            sender.send(Ok(value)).await.unwrap();
+        }
+
+        fn end(&self) {
+            self.sender.lock().unwrap().take();
+        }
+
+        fn is_closed(&self) -> bool {
+            self.sender.lock().unwrap().as_ref().unwrap().is_closed()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shared_stream_fan_out() -> Result<()> {
+        let shared = SharedStreams::new(16);
+        let upstream = Upstream::new();
+
+        let mut first = shared
+            .subscribe("prices".to_string(), || upstream.open())
+            .await?;
+        let mut second = shared
+            .subscribe("prices".to_string(), || upstream.open())
+            .await?;
+        assert_eq!(upstream.opened.load(Ordering::SeqCst), 1);
+        assert_eq!(shared.active(), 1);
+
+        upstream.send(1).await;
+        assert_eq!(first.next().await.unwrap()?, 1);
+        assert_eq!(second.next().await.unwrap()?, 1);
+
+        drop(first);
+        upstream.send(2).await;
+        assert_eq!(second.next().await.unwrap()?, 2);
+        assert!(!upstream.is_closed());
+
+        // The last consumer going away drops the upstream stream.
+        drop(second);
+        sleep(Duration::from_millis(10)).await;
+        assert!(upstream.is_closed());
+        assert_eq!(shared.active(), 0);
+
+        let _third = shared
+            .subscribe("prices".to_string(), || upstream.open())
+            .await?;
+        assert_eq!(upstream.opened.load(Ordering::SeqCst), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ended_upstream_is_reopened() -> Result<()> {
+        let shared = SharedStreams::new(16);
+        let upstream = Upstream::new();
+
+        let mut first = shared
+            .subscribe("prices".to_string(), || upstream.open())
+            .await?;
+        upstream.send(1).await;
+        upstream.end();
+        assert_eq!(first.next().await.unwrap()?, 1);
+        assert!(first.next().await.is_none());
+
+        let _second = shared
+            .subscribe("prices".to_string(), || upstream.open())
+            .await?;
+        assert_eq!(upstream.opened.load(Ordering::SeqCst), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shared_stream_lag_and_type_mismatch() -> Result<()> {
+        let shared = SharedStreams::new(2);
+        let upstream = Upstream::new();
+
+        let mut stream = shared
+            .subscribe("blocks".to_string(), || upstream.open())
+            .await?;
+        for value in 1..=4 {
+            upstream.send(value).await;
+        }
+        sleep(Duration::from_millis(10)).await;
+
+        assert_eq!(stream.next().await.unwrap()?, 3);
+        assert_eq!(stream.lagged(), 2);
+
+        let mismatch = shared
+            .subscribe("blocks".to_string(), || async {
+                Ok(futures_util::stream::empty::<Result<String>>())
+            })
+            .await;
+        assert!(mismatch.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_slow_open_does_not_block_other_keys() -> Result<()> {
+        let shared = SharedStreams::new(16);
+        let upstream = Upstream::new();
+        let (release, released) = tokio::sync::oneshot::channel::<()>();
+
+        let slow = shared.subscribe("slow".to_string(), || async {
+            let _ = released.await;
+            Ok(futures_util::stream::empty::<Result<u64>>())
+        });
+        let fast = async {
+            let stream = timeout(
+                Duration::from_secs(1),
+                shared.subscribe("fast".to_string(), || upstream.open()),
+            )
+            .await;
+            let _ = release.send(());
+            stream
+        };
+
+        let (slow, fast) = tokio::join!(slow, fast);
+        assert!(fast.is_ok(), "opening one key waited on another");
+        fast??;
+        slow?;
+        assert!(shared.opening.lock().unwrap().is_empty());
+        Ok(())
+    }
+}
@@ -1,15 +1,27 @@
 use std::{collections::HashMap, str::FromStr};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use base64::{engine::general_purpose, Engine};
-use serde_json::{json, Value};
+use serde::Serialize;
+use serde_json::Value;
 use solana_sdk::{
     address_lookup_table::AddressLookupTableAccount,
+    bs58,
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
     transaction::Transaction,
 };
-use solana_trader_proto::api::{self, Project, TransactionMessage, TransactionMessageV2};
+use solana_trader_proto::api::{self, TransactionMessage, TransactionMessageV2};
+
+use crate::common::constants::{
+    BLOXROUTE_TIP_ADDRESSES, COMPUTE_BUDGET_PROGRAM, JITO_TIP_ADDRESSES, JUPITER_PROGRAM,
+    MEMO_PROGRAM, MEMO_V1_PROGRAM, PUMP_FUN_PROGRAM, RAYDIUM_AMM_PROGRAM, RAYDIUM_CLMM_PROGRAM,
+    RAYDIUM_CPMM_PROGRAM, SYSTEM_PROGRAM, TOKEN_2022_PROGRAM, TOKEN_PROGRAM,
+    TRADER_API_MEMO_PROGRAM,
+};
+use crate::common::signing::{SignedTransaction, SubmitParams};
+use crate::common::transaction::{TraderTransaction, TransactionFormat};
+use crate::provider::codec;
 
 pub trait IntoTransactionMessage {
     fn into_transaction_message(self) -> TransactionMessage;
@@ -132,61 +144,416 @@ pub fn create_transaction_message(
     })
 }
 
-pub fn convert_string_enums(value: &mut Value) {
-    match value {
-        Value::Object(map) => {
-            for (key, val) in map {
-                match (key.as_str(), &val) {
-                    ("project", Value::String(s)) => {
-                        if let Some(project_enum) = Project::from_str_name(s) {
-                            *val = json!(project_enum as i32);
-                        }
-                    }
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedTransaction {
+    pub format: String,
+    pub is_cleanup: bool,
+    pub recent_blockhash: String,
+    pub fee_payer: Option<String>,
+    pub accounts: Vec<DecodedAccount>,
+    pub instructions: Vec<DecodedInstruction>,
+    pub compute_unit_limit: Option<u32>,
+    pub compute_unit_price: Option<u64>,
+    pub tips: Vec<DecodedTip>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedAccount {
+    pub address: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+    // set for addresses loaded through an address lookup table
+    pub lookup_table: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedInstruction {
+    pub program: String,
+    pub program_id: String,
+    pub accounts: Vec<String>,
+    pub data: String,
+    pub summary: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedTip {
+    pub provider: String,
+    pub from: String,
+    pub to: String,
+    pub lamports: u64,
+}
+
+pub fn decode_transaction<T: IntoTransactionMessage>(
+    tx: T,
+    address_lookup_tables: &HashMap<String, api::PublicKeys>,
+) -> Result<DecodedTransaction> {
+    let tx = TraderTransaction::decode(tx)?;
+    let accounts = resolve_accounts(&tx, address_lookup_tables)?;
 
-                    ("infinity", Value::String(s)) => {
-                        let mapped = match s.as_str() {
-                            "INF_NOT" => 0,
-                            "INF" => 1,
-                            "INF_NEG" => 2,
-                            _ => return,
-                        };
-                        *val = json!(mapped);
+    let mut decoded = DecodedTransaction {
+        format: match tx.format() {
+            TransactionFormat::Legacy => "legacy".to_string(),
+            TransactionFormat::V0 => "v0".to_string(),
+        },
+        is_cleanup: tx.is_cleanup(),
+        recent_blockhash: tx.recent_blockhash().to_string(),
+        fee_payer: tx.signers().first().map(|pk| pk.to_string()),
+        accounts: Vec::new(),
+        instructions: Vec::new(),
+        compute_unit_limit: None,
+        compute_unit_price: None,
+        tips: Vec::new(),
+    };
+
+    let address = |index: u8| -> Result<String> {
+        accounts
+            .get(index as usize)
+            .map(|account| account.address.clone())
+            .ok_or_else(|| anyhow!("Instruction references missing account index {}", index))
+    };
+
+    for ix in tx.instructions() {
+        let program_id = address(ix.program_id_index)?;
+        let ix_accounts = ix
+            .accounts
+            .iter()
+            .map(|index| address(*index))
+            .collect::<Result<Vec<_>>>()?;
+
+        let summary = match program_id.as_str() {
+            COMPUTE_BUDGET_PROGRAM => match ix.data.first() {
+                Some(2) => read_u32(&ix.data, 1).map(|limit| {
+                    decoded.compute_unit_limit = Some(limit);
+                    format!("SetComputeUnitLimit {}", limit)
+                }),
+                Some(3) => read_u64(&ix.data, 1).map(|price| {
+                    decoded.compute_unit_price = Some(price);
+                    format!("SetComputeUnitPrice {} micro-lamports", price)
+                }),
+                _ => None,
+            },
+            SYSTEM_PROGRAM => match (read_u32(&ix.data, 0), read_u64(&ix.data, 4)) {
+                (Some(2), Some(lamports)) if ix_accounts.len() >= 2 => {
+                    if let Some(provider) = tip_provider(&ix_accounts[1]) {
+                        decoded.tips.push(DecodedTip {
+                            provider: provider.to_string(),
+                            from: ix_accounts[0].clone(),
+                            to: ix_accounts[1].clone(),
+                            lamports,
+                        });
                     }
+                    Some(format!(
+                        "Transfer {} lamports from {} to {}",
+                        lamports, ix_accounts[0], ix_accounts[1]
+                    ))
+                }
+                (Some(4), _) => Some("AdvanceNonceAccount".to_string()),
+                _ => None,
+            },
+            TOKEN_PROGRAM | TOKEN_2022_PROGRAM => ix
+                .data
+                .first()
+                .and_then(|tag| token_instruction_name(*tag))
+                .map(String::from),
+            MEMO_PROGRAM | MEMO_V1_PROGRAM | TRADER_API_MEMO_PROGRAM => {
+                Some(format!("Memo {:?}", String::from_utf8_lossy(&ix.data)))
+            }
+            _ => None,
+        };
 
-                    _ => convert_string_enums(val),
+        decoded.instructions.push(DecodedInstruction {
+            program: program_name(&program_id).to_string(),
+            program_id,
+            accounts: ix_accounts,
+            data: bs58::encode(&ix.data).into_string(),
+            summary,
+        });
+    }
+
+    decoded.accounts = accounts;
+    Ok(decoded)
+}
+
+// Static keys first, then loaded writable addresses, then loaded readonly addresses,
+// matching the index space instructions use.
+fn resolve_accounts(
+    tx: &TraderTransaction,
+    address_lookup_tables: &HashMap<String, api::PublicKeys>,
+) -> Result<Vec<DecodedAccount>> {
+    let mut accounts: Vec<DecodedAccount> = tx
+        .static_account_keys()
+        .iter()
+        .enumerate()
+        .map(|(i, key)| DecodedAccount {
+            address: key.to_string(),
+            is_signer: i < tx.header().num_required_signatures as usize,
+            is_writable: tx.is_writable(i),
+            lookup_table: None,
+        })
+        .collect();
+
+    let mut readonly = Vec::new();
+    for lookup in tx.address_table_lookups() {
+        let table_key = lookup.account_key.to_string();
+        let table = address_lookup_tables
+            .get(&table_key)
+            .ok_or_else(|| anyhow!("Address lookup table {} was not provided", table_key))?;
+
+        let load = |index: &u8, is_writable: bool| -> Result<DecodedAccount> {
+            let address = table.pks.get(*index as usize).ok_or_else(|| {
+                anyhow!(
+                    "Index {} out of range for lookup table {}",
+                    index,
+                    table_key
+                )
+            })?;
+            Ok(DecodedAccount {
+                address: address.clone(),
+                is_signer: false,
+                is_writable,
+                lookup_table: Some(table_key.clone()),
+            })
+        };
+
+        for index in &lookup.writable_indexes {
+            accounts.push(load(index, true)?);
+        }
+        for index in &lookup.readonly_indexes {
+            readonly.push(load(index, false)?);
+        }
+    }
+    accounts.extend(readonly);
+
+    Ok(accounts)
+}
+
+pub fn program_name(program_id: &str) -> &'static str {
+    match program_id {
+        SYSTEM_PROGRAM => "System",
+        TOKEN_PROGRAM => "Token",
+        TOKEN_2022_PROGRAM => "Token-2022",
+        COMPUTE_BUDGET_PROGRAM => "ComputeBudget",
+        MEMO_PROGRAM | MEMO_V1_PROGRAM => "Memo",
+        TRADER_API_MEMO_PROGRAM => "Trader API Memo",
+        RAYDIUM_AMM_PROGRAM => "Raydium AMM",
+        RAYDIUM_CPMM_PROGRAM => "Raydium CPMM",
+        RAYDIUM_CLMM_PROGRAM => "Raydium CLMM",
+        JUPITER_PROGRAM => "Jupiter",
+        PUMP_FUN_PROGRAM => "Pump.fun",
+        _ => "Unknown",
+    }
+}
+
+fn tip_provider(address: &str) -> Option<&'static str> {
+    if BLOXROUTE_TIP_ADDRESSES.contains(&address) {
+        Some("bloXroute")
+    } else if JITO_TIP_ADDRESSES.contains(&address) {
+        Some("Jito")
+    } else {
+        None
+    }
+}
+
+fn token_instruction_name(tag: u8) -> Option<&'static str> {
+    match tag {
+        1 => Some("InitializeAccount"),
+        3 => Some("Transfer"),
+        4 => Some("Approve"),
+        6 => Some("SetAuthority"),
+        7 => Some("MintTo"),
+        8 => Some("Burn"),
+        9 => Some("CloseAccount"),
+        12 => Some("TransferChecked"),
+        17 => Some("SyncNative"),
+        18 => Some("InitializeAccount3"),
+        _ => None,
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    data.get(offset..offset + 8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+pub(crate) enum SubmitRequest {
+    Single(api::PostSubmitRequest),
+    Batch(api::PostSubmitBatchRequest),
+}
+
+impl SubmitRequest {
+    // The REST and WebSocket body. `useStakedRPCs` does not survive the generated camelCase
+    // rename, so it is put back under the proto's name.
+    pub(crate) fn to_json(&self) -> Result<Value> {
+        match self {
+            SubmitRequest::Single(request) => {
+                let mut value = codec::to_json(request)?;
+                if let Some(fields) = value.as_object_mut() {
+                    if let Some(use_staked_rpcs) = fields.remove("useStakedRpCs") {
+                        fields.insert("useStakedRPCs".to_string(), use_staked_rpcs);
+                    }
                 }
+                Ok(value)
             }
+            SubmitRequest::Batch(request) => codec::to_json(request),
         }
-        Value::Array(arr) => arr.iter_mut().for_each(convert_string_enums),
-        _ => {}
     }
 }
 
+pub(crate) fn build_submit_request(
+    signed_txs: &[SignedTransaction],
+    submit_opts: &SubmitParams,
+    use_bundle: bool,
+) -> SubmitRequest {
+    if signed_txs.len() == 1 {
+        let signed_tx = &signed_txs[0];
+
+        return SubmitRequest::Single(api::PostSubmitRequest {
+            transaction: Some(TransactionMessage {
+                content: signed_tx.content.clone(),
+                is_cleanup: signed_tx.is_cleanup,
+            }),
+            skip_pre_flight: submit_opts.skip_pre_flight,
+            front_running_protection: Some(submit_opts.front_running_protection),
+            use_staked_rp_cs: Some(submit_opts.use_staked_rpcs),
+            fast_best_effort: Some(submit_opts.fast_best_effort),
+            tip: None,
+            allow_back_run: submit_opts.allow_back_run,
+            revenue_address: submit_opts.revenue_address.clone(),
+        });
+    }
+
+    let entries = signed_txs
+        .iter()
+        .map(|signed_tx| api::PostSubmitRequestEntry {
+            transaction: Some(TransactionMessage {
+                content: signed_tx.content.clone(),
+                is_cleanup: signed_tx.is_cleanup,
+            }),
+            skip_pre_flight: submit_opts.skip_pre_flight,
+        })
+        .collect();
+
+    SubmitRequest::Batch(api::PostSubmitBatchRequest {
+        entries,
+        use_bundle: Some(use_bundle),
+        submit_strategy: submit_opts.submit_strategy.into(),
+        front_running_protection: Some(submit_opts.front_running_protection),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn test_conversions() {
-        let mut value = json!({
-            "project": "P_JUPITER",
-            "tradeFeeRate": "1000",
-            "nested": {
-                "project": "P_RAYDIUM",
-                "priceImpactPercent": {
-                    "infinity": "INF_NOT"
-                }
+    fn test_decode_transaction() -> Result<()> {
+        use solana_sdk::{
+            compute_budget::ComputeBudgetInstruction,
+            hash::Hash,
+            message::{v0, VersionedMessage},
+            system_instruction,
+            transaction::VersionedTransaction,
+        };
+
+        let payer = Pubkey::new_unique();
+        let tip_account = Pubkey::from_str(BLOXROUTE_TIP_ADDRESSES[0])?;
+        let pool = Pubkey::new_unique();
+        let lookup_table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: vec![pool],
+        };
+
+        let swap = Instruction {
+            program_id: Pubkey::from_str(RAYDIUM_AMM_PROGRAM)?,
+            accounts: vec![
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(pool, false),
+            ],
+            data: vec![9],
+        };
+        let message = v0::Message::try_compile(
+            &payer,
+            &[
+                ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+                ComputeBudgetInstruction::set_compute_unit_price(1_000),
+                swap,
+                system_instruction::transfer(&payer, &tip_account, 1_000_000),
+            ],
+            &[lookup_table.clone()],
+            Hash::new_unique(),
+        )?;
+        let tx = VersionedTransaction {
+            signatures: vec![Default::default()],
+            message: VersionedMessage::V0(message),
+        };
+        let tx_message = TransactionMessage {
+            content: general_purpose::STANDARD.encode(bincode::serialize(&tx)?),
+            is_cleanup: false,
+        };
+
+        let tables = HashMap::from([(
+            lookup_table.key.to_string(),
+            api::PublicKeys {
+                pks: vec![pool.to_string()],
             },
-            "array": [
-                {"project": "P_OPENBOOK"}
-            ]
-        });
+        )]);
+        let decoded = decode_transaction(tx_message.clone(), &tables)?;
+
+        assert_eq!(decoded.format, "v0");
+        assert_eq!(decoded.fee_payer, Some(payer.to_string()));
+        assert_eq!(decoded.compute_unit_limit, Some(200_000));
+        assert_eq!(decoded.compute_unit_price, Some(1_000));
+        assert_eq!(decoded.instructions[2].program, "Raydium AMM");
+        assert_eq!(decoded.tips.len(), 1);
+        assert_eq!(decoded.tips[0].provider, "bloXroute");
+        assert_eq!(decoded.tips[0].lamports, 1_000_000);
+
+        let loaded = decoded
+            .accounts
+            .iter()
+            .find(|account| account.address == pool.to_string())
+            .unwrap();
+        assert_eq!(loaded.lookup_table, Some(lookup_table.key.to_string()));
+        assert!(!loaded.is_writable);
+
+        assert!(decode_transaction(tx_message, &HashMap::new()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_submit_request_json() -> Result<()> {
+        let tx = |content: &str| SignedTransaction {
+            content: content.to_string(),
+            is_cleanup: false,
+        };
+        let submit_opts = SubmitParams {
+            allow_back_run: Some(true),
+            revenue_address: Some("revenue".to_string()),
+            ..Default::default()
+        };
 
-        convert_string_enums(&mut value);
+        let single = build_submit_request(&[tx("a")], &submit_opts, false).to_json()?;
+        assert_eq!(single["transaction"]["content"], "a");
+        assert_eq!(single["useStakedRPCs"], true);
+        assert_eq!(single["allowBackRun"], true);
+        assert_eq!(single["revenueAddress"], "revenue");
+        assert!(single.get("useStakedRpCs").is_none());
+        assert!(single.get("tip").is_none());
 
-        assert_eq!(value["project"], 2);
-        assert_eq!(value["nested"]["project"], 3);
-        assert_eq!(value["nested"]["priceImpactPercent"]["infinity"], 0);
-        assert_eq!(value["array"][0]["project"], 5);
+        let batch = build_submit_request(&[tx("a"), tx("b")], &submit_opts, true).to_json()?;
+        assert_eq!(batch["entries"][1]["transaction"]["content"], "b");
+        assert_eq!(batch["useBundle"], true);
+        assert_eq!(batch["frontRunningProtection"], false);
+        assert_eq!(
+            batch["submitStrategy"],
+            api::SubmitStrategy::PSubmitAll as i32
+        );
+        Ok(())
     }
 }
@@ -0,0 +1,41 @@
+use anyhow::Result;
+use serde_json::Value;
+use solana_trader_proto::api;
+
+use super::WebSocketClient;
+use crate::provider::codec::to_json;
+use crate::provider::endpoints::{through_cache, unary_endpoints};
+
+macro_rules! ws_endpoints {
+    ($(
+        $name:ident($request:ty) -> $response:ty {
+            grpc: $rpc:ident, http: $verb:ident $path:literal, ws: $method:literal $(without $omit:literal)?, $($cached:ident,)?
+        }
+    )*) => {
+        impl WebSocketClient {
+            $(
+                pub async fn $name(&self, request: &$request) -> Result<$response> {
+                    let fetch = async {
+                        let params = without(to_json(request)?, &[$($omit)?]);
+
+                        self.conn.request($method, params).await
+                    };
+
+                    through_cache!(self.quote_cache.clone(), request, fetch $(, $cached)?)
+                }
+            )*
+        }
+    };
+}
+
+// Drops fields the WebSocket method rejects even though the request type carries them.
+fn without(mut params: Value, fields: &[&str]) -> Value {
+    if let Value::Object(map) = &mut params {
+        for field in fields {
+            map.remove(*field);
+        }
+    }
+    params
+}
+
+unary_endpoints!(ws_endpoints);
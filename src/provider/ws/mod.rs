@@ -1,3 +1,4 @@
+mod endpoints;
 pub mod general;
 pub mod quote;
 pub mod stream;
@@ -7,13 +8,21 @@ use anyhow::{anyhow, Result};
 use serde_json::json;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Keypair;
-use solana_trader_proto::api::GetRecentBlockHashResponseV2;
-
-use crate::common::signing::{sign_transaction, SubmitParams};
+use solana_trader_proto::api::{self, GetRecentBlockHashResponseV2};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::common::package::SignedTransactionPackage;
+use crate::common::policy::{lookup_table_addresses, SigningPolicy};
+use crate::common::signer::TransactionSigner;
+use crate::common::signing::{sign_all_for_submit, DryRunResult, SignedTransaction, SubmitParams};
 use crate::common::{get_base_url_from_env, ws_endpoint, BaseConfig};
+use crate::connections::backpressure::Backpressure;
 use crate::connections::ws::WS;
 
-use super::utils::IntoTransactionMessage;
+use super::cache::QuoteCache;
+use super::shared::SharedStreams;
+use super::utils::{self, IntoTransactionMessage, SubmitRequest};
 
 pub struct WebSocketConfig {
     pub endpoint: String,
@@ -27,6 +36,11 @@ pub struct WebSocketClient {
     conn: WS,
     keypair: Option<Keypair>,
     pub public_key: Option<Pubkey>,
+    signer: Option<Arc<dyn TransactionSigner>>,
+    signing_policy: Option<SigningPolicy>,
+    quote_cache: Option<Arc<QuoteCache>>,
+    backpressure: HashMap<String, Backpressure>,
+    shared: SharedStreams,
 }
 
 impl WebSocketClient {
@@ -34,6 +48,39 @@ impl WebSocketClient {
         Ok(self.keypair.as_ref().unwrap())
     }
 
+    pub fn get_signer(&self) -> Result<Arc<dyn TransactionSigner>> {
+        self.signer
+            .clone()
+            .ok_or_else(|| anyhow!("No signer configured"))
+    }
+
+    pub fn with_signer(mut self, signer: Arc<dyn TransactionSigner>) -> Self {
+        self.public_key = Some(signer.public_key());
+        self.signer = Some(signer);
+        self
+    }
+
+    pub fn with_signing_policy(mut self, policy: SigningPolicy) -> Self {
+        self.signing_policy = Some(policy);
+        self
+    }
+
+    pub fn with_quote_cache(mut self, cache: Arc<QuoteCache>) -> Self {
+        self.quote_cache = Some(cache);
+        self
+    }
+
+    // Sets the backpressure policy for subscriptions to a stream method, e.g. "GetTradesStream".
+    // Streams without one block the connection when their buffer fills up.
+    pub fn with_backpressure(mut self, method: &str, backpressure: Backpressure) -> Result<Self> {
+        if !stream::STREAM_METHODS.contains(&method) {
+            return Err(anyhow!("{} is not a WebSocket stream method", method));
+        }
+
+        self.backpressure.insert(method.to_string(), backpressure);
+        Ok(self)
+    }
+
     pub async fn new(endpoint: Option<String>) -> Result<Self> {
         let base = BaseConfig::try_from_env()?;
         let (default_base_url, secure) = get_base_url_from_env();
@@ -52,6 +99,11 @@ impl WebSocketClient {
             conn,
             keypair: base.keypair,
             public_key: base.public_key,
+            signer: base.signer,
+            signing_policy: None,
+            quote_cache: None,
+            backpressure: HashMap::new(),
+            shared: SharedStreams::default(),
         })
     }
 
@@ -65,27 +117,90 @@ impl WebSocketClient {
         submit_opts: SubmitParams,
         use_bundle: bool,
     ) -> Result<Vec<String>> {
-        let keypair = self.get_keypair()?;
+        let signed_txs = self.sign_transactions(&txs, None, &submit_opts).await?;
+
+        self.submit_signed(signed_txs, submit_opts, use_bundle)
+            .await
+    }
+
+    // Like `sign_and_submit`, with the lookup tables the transactions load accounts from so that
+    // the signing policy can resolve them.
+    pub async fn sign_and_submit_with_lookup_tables<T: IntoTransactionMessage + Clone>(
+        &self,
+        txs: Vec<T>,
+        address_lookup_tables: &HashMap<String, api::PublicKeys>,
+        submit_opts: SubmitParams,
+        use_bundle: bool,
+    ) -> Result<Vec<String>> {
+        let lookup_tables = lookup_table_addresses(address_lookup_tables);
+        let signed_txs = self
+            .sign_transactions(&txs, Some(&lookup_tables), &submit_opts)
+            .await?;
+
+        self.submit_signed(signed_txs, submit_opts, use_bundle)
+            .await
+    }
 
-        let hash_res: GetRecentBlockHashResponseV2 =
-            self.conn.request("GetRecentBlockHashV2", json!({})).await?;
+    pub async fn sign_and_submit_dry_run<T: IntoTransactionMessage + Clone>(
+        &self,
+        txs: Vec<T>,
+        submit_opts: SubmitParams,
+        use_bundle: bool,
+    ) -> Result<DryRunResult> {
+        let signed_txs = self.sign_transactions(&txs, None, &submit_opts).await?;
+        let (method, request) = build_submit_request(&signed_txs, &submit_opts, use_bundle)?;
 
-        if txs.len() == 1 {
-            let signed_tx = sign_transaction(&txs[0], keypair, hash_res.block_hash).await?;
+        DryRunResult::new(signed_txs, method, request)
+    }
 
-            let request = json!({
-                "transaction": {
-                    "content": signed_tx.content,
-                    "isCleanup": signed_tx.is_cleanup
-                },
-                "skipPreFlight": submit_opts.skip_pre_flight,
-                "frontRunningProtection": submit_opts.front_running_protection,
-                "useStakedRPCs": submit_opts.use_staked_rpcs,
-                "fastBestEffort": submit_opts.fast_best_effort
-            });
+    async fn sign_transactions<T: IntoTransactionMessage + Clone>(
+        &self,
+        txs: &[T],
+        lookup_tables: Option<&HashMap<String, Vec<String>>>,
+        submit_opts: &SubmitParams,
+    ) -> Result<Vec<SignedTransaction>> {
+        let signer = self.get_signer()?;
+
+        // checked first so that a rejected batch costs no blockhash request
+        if let Some(policy) = &self.signing_policy {
+            policy.check_transactions(txs, &signer.public_key(), lookup_tables)?;
+        }
 
-            let response: serde_json::Value = self.conn.request("PostSubmitV2", request).await?;
+        let block_hash = match &submit_opts.durable_nonce {
+            Some(nonce) => nonce.nonce_hash.to_string(),
+            None => {
+                let hash_res: GetRecentBlockHashResponseV2 =
+                    self.conn.request("GetRecentBlockHashV2", json!({})).await?;
+                hash_res.block_hash
+            }
+        };
+
+        sign_all_for_submit(txs, signer.as_ref(), &block_hash, submit_opts).await
+    }
+
+    pub async fn submit_signed_package(
+        &self,
+        package: SignedTransactionPackage,
+    ) -> Result<Vec<String>> {
+        self.submit_signed(
+            package.transactions,
+            package.submit_opts,
+            package.use_bundle,
+        )
+        .await
+    }
 
+    pub async fn submit_signed(
+        &self,
+        signed_txs: Vec<SignedTransaction>,
+        submit_opts: SubmitParams,
+        use_bundle: bool,
+    ) -> Result<Vec<String>> {
+        let (method, request) = build_submit_request(&signed_txs, &submit_opts, use_bundle)?;
+
+        let response: serde_json::Value = self.conn.request(method, request).await?;
+
+        if signed_txs.len() == 1 {
             return Ok(vec![response
                 .get("signature")
                 .and_then(|s| s.as_str())
@@ -93,29 +208,6 @@ impl WebSocketClient {
                 .ok_or_else(|| anyhow!("Missing signature in response"))?]);
         }
 
-        let mut entries = Vec::with_capacity(txs.len());
-        for tx in txs {
-            let signed_tx = sign_transaction(&tx, keypair, hash_res.block_hash.clone()).await?;
-            entries.push(json!({
-                "transaction": {
-                    "content": signed_tx.content,
-                    "isCleanup": signed_tx.is_cleanup
-                },
-                "skipPreFlight": submit_opts.skip_pre_flight,
-                "frontRunningProtection": submit_opts.front_running_protection,
-                "useStakedRPCs": submit_opts.use_staked_rpcs,
-                "fastBestEffort": submit_opts.fast_best_effort
-            }));
-        }
-
-        let request = json!({
-            "entries": entries,
-            "useBundle": use_bundle,
-            "submitStrategy": submit_opts.submit_strategy
-        });
-
-        let response: serde_json::Value = self.conn.request("PostSubmitBatchV2", request).await?;
-
         let signatures = response["transactions"]
             .as_array()
             .ok_or_else(|| anyhow!("Invalid response format"))?
@@ -127,3 +219,17 @@ impl WebSocketClient {
         Ok(signatures)
     }
 }
+
+fn build_submit_request(
+    signed_txs: &[SignedTransaction],
+    submit_opts: &SubmitParams,
+    use_bundle: bool,
+) -> Result<(&'static str, serde_json::Value)> {
+    let request = utils::build_submit_request(signed_txs, submit_opts, use_bundle);
+    let endpoint = match request {
+        SubmitRequest::Single(_) => "PostSubmitV2",
+        SubmitRequest::Batch(_) => "PostSubmitBatchV2",
+    };
+
+    Ok((endpoint, request.to_json()?))
+}
@@ -1,93 +1,80 @@
 use super::WebSocketClient;
+use crate::provider::aggregator::{AggregatedQuotes, BestQuoteRequest, Venue};
+use crate::provider::polling::{poll_stream, PollInterval};
 use anyhow::Result;
-use serde_json::json;
+use futures_util::Stream;
 use solana_trader_proto::api;
+use tokio::time::timeout;
 
 impl WebSocketClient {
-    pub async fn get_raydium_quotes(
-        &self,
-        request: &api::GetRaydiumQuotesRequest,
-    ) -> Result<api::GetRaydiumQuotesResponse> {
-        let params = serde_json::to_value(request)
-            .map_err(|e| anyhow::anyhow!("Failed to serialize request: {}", e))?;
-
-        self.conn.request("GetRaydiumQuotes", params).await
-    }
-
-    pub async fn get_raydium_cpmm_quotes(
-        &self,
-        request: &api::GetRaydiumCpmmQuotesRequest,
-    ) -> Result<api::GetRaydiumCpmmQuotesResponse> {
-        let params = serde_json::to_value(request)
-            .map_err(|e| anyhow::anyhow!("Failed to serialize request: {}", e))?;
-
-        self.conn.request("GetRaydiumCPMMQuotes", params).await
-    }
-
-    pub async fn get_raydium_clmm_quotes(
-        &self,
-        request: &api::GetRaydiumClmmQuotesRequest,
-    ) -> Result<api::GetRaydiumClmmQuotesResponse> {
-        let params = serde_json::to_value(request)
-            .map_err(|e| anyhow::anyhow!("Failed to serialize request: {}", e))?;
-
-        self.conn.request("GetRaydiumCLMMQuotes", params).await
-    }
-
-    pub async fn get_pump_fun_quotes(
-        &self,
-        request: &api::GetPumpFunQuotesRequest,
-    ) -> Result<api::GetPumpFunQuotesResponse> {
-        let params = serde_json::json!({
-            "quoteType": request.quote_type,
-            "mintAddress": request.mint_address,
-            "bondingCurveAddress": request.bonding_curve_address,
-            "amount": request.amount,
-        });
-
-        self.conn.request("GetPumpFunQuotes", params).await
-    }
-
-    // NOTE: Fast mode is not used as of 11/1, breaks the endpoint.
-    pub async fn get_jupiter_quotes(
-        &self,
-        request: &api::GetJupiterQuotesRequest,
-    ) -> Result<api::GetJupiterQuotesResponse> {
-        let params = serde_json::json!({
-            "inToken": request.in_token,
-            "outToken": request.out_token,
-            "inAmount": request.in_amount,
-            "slippage": request.slippage,
-        });
-
-        self.conn.request("GetJupiterQuotes", params).await
-    }
-
     pub async fn get_quotes(
         &self,
         request: &api::GetQuotesRequest,
     ) -> Result<api::GetQuotesResponse> {
-        let params = serde_json::to_value(request)
-            .map_err(|e| anyhow::anyhow!("Failed to serialize request: {}", e))?;
-
-        self.conn.request("GetQuotes", params).await
+        self.get_quotes_request(request).await
     }
 
     pub async fn get_raydium_prices(
         &self,
         tokens: Vec<String>,
     ) -> Result<api::GetRaydiumPricesResponse> {
-        let request = api::GetRaydiumPricesRequest { tokens };
-
-        self.conn.request("GetRaydiumPrices", json!(request)).await
+        self.get_raydium_prices_request(&api::GetRaydiumPricesRequest { tokens })
+            .await
     }
 
     pub async fn get_jupiter_prices(
         &self,
         tokens: Vec<String>,
     ) -> Result<api::GetJupiterPricesResponse> {
-        let request = api::GetJupiterPricesRequest { tokens };
+        self.get_jupiter_prices_request(&api::GetJupiterPricesRequest { tokens })
+            .await
+    }
+
+    // Polls GetQuotes over the shared connection, yielding only changed responses.
+    pub fn quote_stream(
+        &self,
+        request: &api::GetQuotesRequest,
+        interval: impl Into<PollInterval>,
+    ) -> impl Stream<Item = Result<api::GetQuotesResponse>> + Unpin + '_ {
+        let request = request.clone();
+
+        poll_stream(interval.into(), move || {
+            let request = request.clone();
+            async move { self.get_quotes_request(&request).await }
+        })
+    }
 
-        self.conn.request("GetJupiterPrices", json!(request)).await
+    // Venues that fail or miss the deadline are reported in `failures` instead of
+    // failing the whole call.
+    pub async fn get_best_quotes(&self, request: &BestQuoteRequest) -> Result<AggregatedQuotes> {
+        let deadline = request.deadline;
+        let (raydium_req, cpmm_req, clmm_req, jupiter_req) = (
+            request.raydium(),
+            request.raydium_cpmm(),
+            request.raydium_clmm(),
+            request.jupiter(),
+        );
+
+        let (raydium, cpmm, clmm, jupiter, pump_fun) = tokio::join!(
+            timeout(deadline, self.get_raydium_quotes(&raydium_req)),
+            timeout(deadline, self.get_raydium_cpmm_quotes(&cpmm_req)),
+            timeout(deadline, self.get_raydium_clmm_quotes(&clmm_req)),
+            timeout(deadline, self.get_jupiter_quotes(&jupiter_req)),
+            async {
+                let req = request.pump_fun()?;
+                Some(timeout(deadline, self.get_pump_fun_quotes(&req)).await)
+            },
+        );
+
+        let mut quotes = AggregatedQuotes::default();
+        quotes.record(Venue::Raydium, raydium);
+        quotes.record(Venue::RaydiumCpmm, cpmm);
+        quotes.record(Venue::RaydiumClmm, clmm);
+        quotes.record(Venue::Jupiter, jupiter);
+        if let Some(pump_fun) = pump_fun {
+            quotes.record(Venue::PumpFun, pump_fun);
+        }
+
+        quotes.finish()
     }
 }
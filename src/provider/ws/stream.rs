@@ -1,28 +1,51 @@
 use super::WebSocketClient;
+use crate::connections::backpressure::SubscriptionStream;
+use crate::provider::codec::to_json;
+use crate::provider::shared::SharedStream;
 use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use solana_trader_proto::api;
-use tokio_stream::Stream;
+
+// The JSON-RPC methods of the streams below, which `with_backpressure` accepts.
+pub(super) const STREAM_METHODS: &[&str] = &[
+    "GetPricesStream",
+    "GetBlockStream",
+    "GetOrderbooksStream",
+    "GetMarketDepthsStream",
+    "GetTickersStream",
+    "GetTradesStream",
+    "GetSwapsStream",
+    "GetNewRaydiumPoolsStream",
+    "GetNewRaydiumPoolsByTransactionStream",
+    "GetRecentBlockHashStream",
+    "GetPoolReservesStream",
+    "GetPriorityFeeStream",
+    "GetBundleTipStream",
+    "GetPumpFunNewTokensStream",
+    "GetPumpFunSwapsStream",
+];
 
 impl WebSocketClient {
     pub async fn get_prices_stream(
         &self,
         projects: Vec<api::Project>,
         tokens: Vec<String>,
-    ) -> Result<impl Stream<Item = Result<api::GetPricesStreamResponse>>> {
+    ) -> Result<SubscriptionStream<api::GetPricesStreamResponse>> {
         let request = api::GetPricesStreamRequest {
             projects: projects.iter().map(|&p| p as i32).collect(),
             tokens,
         };
 
-        self.conn.stream_proto("GetPricesStream", &request).await
+        self.subscribe("GetPricesStream", &request).await
     }
 
     pub async fn get_block_stream(
         &self,
-    ) -> Result<impl Stream<Item = Result<api::GetBlockStreamResponse>>> {
+    ) -> Result<SubscriptionStream<api::GetBlockStreamResponse>> {
         let request = api::GetBlockStreamRequest {};
 
-        self.conn.stream_proto("GetBlockStream", &request).await
+        self.subscribe("GetBlockStream", &request).await
     }
 
     pub async fn get_orderbook_stream(
@@ -30,16 +53,14 @@ impl WebSocketClient {
         markets: Vec<String>,
         limit: u32,
         project: api::Project,
-    ) -> Result<impl Stream<Item = Result<api::GetOrderbooksStreamResponse>>> {
+    ) -> Result<SubscriptionStream<api::GetOrderbooksStreamResponse>> {
         let request = api::GetOrderbooksRequest {
             markets,
             limit,
             project: project as i32,
         };
 
-        self.conn
-            .stream_proto("GetOrderbooksStream", &request)
-            .await
+        self.subscribe("GetOrderbooksStream", &request).await
     }
 
     pub async fn get_market_depths_stream(
@@ -47,29 +68,27 @@ impl WebSocketClient {
         markets: Vec<String>,
         limit: u32,
         project: api::Project,
-    ) -> Result<impl Stream<Item = Result<api::GetMarketDepthsStreamResponse>>> {
+    ) -> Result<SubscriptionStream<api::GetMarketDepthsStreamResponse>> {
         let request = api::GetMarketDepthsRequest {
             markets,
             limit,
             project: project as i32,
         };
 
-        self.conn
-            .stream_proto("GetMarketDepthsStream", &request)
-            .await
+        self.subscribe("GetMarketDepthsStream", &request).await
     }
 
     pub async fn get_ticker_stream(
         &self,
         markets: Vec<String>,
         project: api::Project,
-    ) -> Result<impl Stream<Item = Result<api::GetTickersStreamResponse>>> {
+    ) -> Result<SubscriptionStream<api::GetTickersStreamResponse>> {
         let request = api::GetTickersStreamRequest {
             markets,
             project: project as i32,
         };
 
-        self.conn.stream_proto("GetTickersStream", &request).await
+        self.subscribe("GetTickersStream", &request).await
     }
 
     pub async fn get_trades_stream(
@@ -77,14 +96,14 @@ impl WebSocketClient {
         market: String,
         limit: u32,
         project: api::Project,
-    ) -> Result<impl Stream<Item = Result<api::GetTradesStreamResponse>>> {
+    ) -> Result<SubscriptionStream<api::GetTradesStreamResponse>> {
         let request = api::GetTradesRequest {
             market,
             limit,
             project: project as i32,
         };
 
-        self.conn.stream_proto("GetTradesStream", &request).await
+        self.subscribe("GetTradesStream", &request).await
     }
 
     pub async fn get_swaps_stream(
@@ -92,105 +111,150 @@ impl WebSocketClient {
         projects: Vec<api::Project>,
         pools: Vec<String>,
         include_failed: bool,
-    ) -> Result<impl Stream<Item = Result<api::GetSwapsStreamResponse>>> {
+    ) -> Result<SubscriptionStream<api::GetSwapsStreamResponse>> {
         let request = api::GetSwapsStreamRequest {
             projects: projects.iter().map(|&p| p as i32).collect(),
             pools,
             include_failed,
         };
 
-        self.conn.stream_proto("GetSwapsStream", &request).await
+        self.subscribe("GetSwapsStream", &request).await
     }
 
     pub async fn get_new_raydium_pools_stream(
         &self,
         include_cpmm: bool,
-    ) -> Result<impl Stream<Item = Result<api::GetNewRaydiumPoolsResponse>>> {
+    ) -> Result<SubscriptionStream<api::GetNewRaydiumPoolsResponse>> {
         let request = api::GetNewRaydiumPoolsRequest {
             include_cpmm: Some(include_cpmm),
         };
 
-        self.conn
-            .stream_proto("GetNewRaydiumPoolsStream", &request)
-            .await
+        self.subscribe("GetNewRaydiumPoolsStream", &request).await
     }
 
     pub async fn get_new_raydium_pools_by_transaction_stream(
         &self,
-    ) -> Result<impl Stream<Item = Result<api::GetNewRaydiumPoolsByTransactionResponse>>> {
+    ) -> Result<SubscriptionStream<api::GetNewRaydiumPoolsByTransactionResponse>> {
         let request = api::GetNewRaydiumPoolsByTransactionRequest {};
 
-        self.conn
-            .stream_proto("GetNewRaydiumPoolsByTransactionStream", &request)
+        self.subscribe("GetNewRaydiumPoolsByTransactionStream", &request)
             .await
     }
 
     pub async fn get_recent_block_hash_stream(
         &self,
-    ) -> Result<impl Stream<Item = Result<api::GetRecentBlockHashResponse>>> {
+    ) -> Result<SubscriptionStream<api::GetRecentBlockHashResponse>> {
         let request = api::GetRecentBlockHashRequest {};
 
-        self.conn
-            .stream_proto("GetRecentBlockHashStream", &request)
-            .await
+        self.subscribe("GetRecentBlockHashStream", &request).await
     }
 
     pub async fn get_pool_reserves_stream(
         &self,
         projects: Vec<api::Project>,
         pools: Vec<String>,
-    ) -> Result<impl Stream<Item = Result<api::GetPoolReservesStreamResponse>>> {
+    ) -> Result<SubscriptionStream<api::GetPoolReservesStreamResponse>> {
         let request = api::GetPoolReservesStreamRequest {
             projects: projects.iter().map(|&p| p as i32).collect(),
             pools,
         };
 
-        self.conn
-            .stream_proto("GetPoolReservesStream", &request)
-            .await
+        self.subscribe("GetPoolReservesStream", &request).await
     }
 
     pub async fn get_priority_fee_stream(
         &self,
         project: api::Project,
         percentile: Option<f64>,
-    ) -> Result<impl Stream<Item = Result<api::GetPriorityFeeResponse>>> {
+    ) -> Result<SubscriptionStream<api::GetPriorityFeeResponse>> {
         let request = api::GetPriorityFeeRequest {
             project: project as i32,
             percentile,
         };
 
-        self.conn
-            .stream_proto("GetPriorityFeeStream", &request)
-            .await
+        self.subscribe("GetPriorityFeeStream", &request).await
     }
 
     pub async fn get_bundle_tip_stream(
         &self,
-    ) -> Result<impl Stream<Item = Result<api::GetBundleTipResponse>>> {
+    ) -> Result<SubscriptionStream<api::GetBundleTipResponse>> {
         let request = api::GetBundleTipRequest {};
 
-        self.conn.stream_proto("GetBundleTipStream", &request).await
+        self.subscribe("GetBundleTipStream", &request).await
     }
 
     pub async fn get_pump_fun_new_tokens_stream(
         &self,
-    ) -> Result<impl Stream<Item = Result<api::GetPumpFunNewTokensStreamResponse>>> {
+    ) -> Result<SubscriptionStream<api::GetPumpFunNewTokensStreamResponse>> {
         let request = api::GetPumpFunNewTokensStreamRequest {};
 
-        self.conn
-            .stream_proto("GetPumpFunNewTokensStream", &request)
-            .await
+        self.subscribe("GetPumpFunNewTokensStream", &request).await
     }
 
     pub async fn get_pump_fun_swaps_stream(
         &self,
         tokens: Vec<String>,
-    ) -> Result<impl Stream<Item = Result<api::GetPumpFunSwapsStreamResponse>>> {
+    ) -> Result<SubscriptionStream<api::GetPumpFunSwapsStreamResponse>> {
         let request = api::GetPumpFunSwapsStreamRequest { tokens };
 
+        self.subscribe("GetPumpFunSwapsStream", &request).await
+    }
+
+    // Like `get_prices_stream`, but identical requests on this client share one server
+    // subscription, which is unsubscribed once the last `SharedStream` is dropped.
+    pub async fn get_prices_stream_shared(
+        &self,
+        projects: Vec<api::Project>,
+        tokens: Vec<String>,
+    ) -> Result<SharedStream<api::GetPricesStreamResponse>> {
+        let request = api::GetPricesStreamRequest {
+            projects: projects.iter().map(|&p| p as i32).collect(),
+            tokens,
+        };
+
+        self.shared_stream("GetPricesStream", &request).await
+    }
+
+    pub async fn get_block_stream_shared(
+        &self,
+    ) -> Result<SharedStream<api::GetBlockStreamResponse>> {
+        let request = api::GetBlockStreamRequest {};
+
+        self.shared_stream("GetBlockStream", &request).await
+    }
+
+    // Shares any stream by its JSON-RPC method name; the response type must match the method.
+    pub async fn shared_stream<Req, Resp>(
+        &self,
+        method: &str,
+        request: &Req,
+    ) -> Result<SharedStream<Resp>>
+    where
+        Req: prost::Message + Serialize,
+        Resp: prost::Message + Default + DeserializeOwned + Send + Sync + Clone + 'static,
+    {
+        let key = format!("{}:{}", method, to_json(request)?);
+        self.shared
+            .subscribe_counted(key, || async {
+                let stream = self.subscribe(method, request).await?;
+                let counters = stream.counters();
+                Ok((stream, Some(counters)))
+            })
+            .await
+    }
+
+    async fn subscribe<Req, Resp>(
+        &self,
+        method: &str,
+        request: &Req,
+    ) -> Result<SubscriptionStream<Resp>>
+    where
+        Req: prost::Message + Serialize,
+        Resp: prost::Message + Default + DeserializeOwned + Send + Clone + 'static,
+    {
+        let backpressure = self.backpressure.get(method).copied().unwrap_or_default();
         self.conn
-            .stream_proto("GetPumpFunSwapsStream", &request)
+            .stream_proto_with(method, request, backpressure)
             .await
     }
 }
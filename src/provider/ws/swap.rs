@@ -1,77 +1,26 @@
 use anyhow::Result;
 use base64::{engine::general_purpose, Engine};
 use serde_json::json;
-use solana_sdk::{
-    message::{v0, VersionedMessage},
-    transaction::VersionedTransaction,
-};
+use solana_sdk::message::{v0, VersionedMessage};
 use solana_trader_proto::api;
 
 use crate::{
-    common::signing::SubmitParams,
-    provider::utils::{
-        convert_address_lookup_table, convert_jupiter_instructions, convert_raydium_instructions,
-        create_transaction_message,
+    common::{signer::new_versioned_transaction, signing::SubmitParams},
+    provider::{
+        guard::{
+            best_quoted_out_amount, jupiter_quotes_request, quotes_request, raydium_quotes_request,
+            verify_swap_output, SwapTolerance,
+        },
+        utils::{
+            convert_address_lookup_table, convert_jupiter_instructions,
+            convert_raydium_instructions, create_transaction_message,
+        },
     },
 };
 
 use super::WebSocketClient;
 
 impl WebSocketClient {
-    pub async fn post_raydium_swap(
-        &self,
-        request: &api::PostRaydiumSwapRequest,
-    ) -> Result<api::PostRaydiumSwapResponse> {
-        let params = json!({
-            "ownerAddress": request.owner_address,
-            "inToken": request.in_token,
-            "outToken": request.out_token,
-            "inAmount": request.in_amount,
-            "slippage": request.slippage,
-            "computeLimit": request.compute_limit,
-            "computePrice": request.compute_price,
-            "tip": request.tip,
-        });
-
-        self.conn.request("PostRaydiumSwap", params).await
-    }
-
-    pub async fn post_raydium_route_swap(
-        &self,
-        request: &api::PostRaydiumRouteSwapRequest,
-    ) -> Result<api::PostRaydiumRouteSwapResponse> {
-        let params = json!({
-            "ownerAddress": request.owner_address,
-            "slippage": request.slippage,
-            "steps": request.steps,
-            "computeLimit": request.compute_limit,
-            "computePrice": request.compute_price,
-            "tip": request.tip,
-        });
-
-        self.conn.request("PostRaydiumRouteSwap", params).await
-    }
-
-    pub async fn post_raydium_swap_instructions(
-        &self,
-        request: &api::PostRaydiumSwapInstructionsRequest,
-    ) -> Result<api::PostRaydiumSwapInstructionsResponse> {
-        let params = json!({
-            "ownerAddress": request.owner_address,
-            "inToken": request.in_token,
-            "outToken": request.out_token,
-            "inAmount": request.in_amount,
-            "slippage": request.slippage,
-            "computeLimit": request.compute_limit,
-            "computePrice": request.compute_price,
-            "tip": request.tip,
-        });
-
-        self.conn
-            .request("PostRaydiumSwapInstructions", params)
-            .await
-    }
-
     pub async fn submit_raydium_swap_instructions(
         &self,
         request: api::PostRaydiumSwapInstructionsRequest,
@@ -91,117 +40,13 @@ impl WebSocketClient {
             .await
     }
 
-    pub async fn post_raydium_cpmm_swap(
-        &self,
-        request: &api::PostRaydiumCpmmSwapRequest,
-    ) -> Result<api::PostRaydiumCpmmSwapResponse> {
-        let params = json!({
-            "ownerAddress": request.owner_address,
-            "poolAddress": request.pool_address,
-            "inToken": request.in_token,
-            "outToken": request.out_token,
-            "inAmount": request.in_amount,
-            "slippage": request.slippage,
-            "computeLimit": request.compute_limit,
-            "computePrice": request.compute_price,
-            "tip": request.tip,
-        });
-
-        self.conn.request("PostRaydiumCPMMSwap", params).await
-    }
-
-    pub async fn post_raydium_clmm_swap(
-        &self,
-        request: &api::PostRaydiumSwapRequest,
-    ) -> Result<api::PostRaydiumSwapResponse> {
-        let params = json!({
-            "ownerAddress": request.owner_address,
-            "inToken": request.in_token,
-            "outToken": request.out_token,
-            "inAmount": request.in_amount,
-            "slippage": request.slippage,
-            "computeLimit": request.compute_limit,
-            "computePrice": request.compute_price,
-            "tip": request.tip,
-        });
-
-        self.conn.request("PostRaydiumCLMMSwap", params).await
-    }
-
-    pub async fn post_raydium_clmm_route_swap(
-        &self,
-        request: &api::PostRaydiumRouteSwapRequest,
-    ) -> Result<api::PostRaydiumRouteSwapResponse> {
-        let params = json!({
-            "ownerAddress": request.owner_address,
-            "slippage": request.slippage,
-            "steps": request.steps,
-        });
-
-        self.conn.request("PostRaydiumCLMMRouteSwap", params).await
-    }
-
-    // NOTE: Fast mode is not used as of 11/1, breaks the endpoint.
-    pub async fn post_jupiter_swap(
-        &self,
-        request: &api::PostJupiterSwapRequest,
-    ) -> Result<api::PostJupiterSwapResponse> {
-        let modified_request = json!({
-            "ownerAddress": request.owner_address,
-            "inToken": request.in_token,
-            "outToken": request.out_token,
-            "inAmount": request.in_amount,
-            "slippage": request.slippage,
-            "computeLimit": request.compute_limit,
-            "computePrice": request.compute_price,
-            "tip": request.tip,
-        });
-
-        self.conn.request("PostJupiterSwap", modified_request).await
-    }
-
-    pub async fn post_jupiter_route_swap(
-        &self,
-        request: &api::PostJupiterRouteSwapRequest,
-    ) -> Result<api::PostJupiterRouteSwapResponse> {
-        let params = json!({
-            "ownerAddress": request.owner_address,
-            "slippage": request.slippage,
-            "steps": request.steps,
-            "computeLimit": request.compute_limit,
-            "computePrice": request.compute_price,
-            "tip": request.tip,
-        });
-
-        self.conn.request("PostJupiterRouteSwap", params).await
-    }
-
-    pub async fn post_jupiter_swap_instructions(
-        &self,
-        request: &api::PostJupiterSwapInstructionsRequest,
-    ) -> Result<api::PostJupiterSwapInstructionsResponse> {
-        let params = json!({
-            "ownerAddress": request.owner_address,
-            "inToken": request.in_token,
-            "outToken": request.out_token,
-            "inAmount": request.in_amount,
-            "slippage": request.slippage,
-            "computePrice": request.compute_price,
-            "tip": request.tip,
-        });
-
-        self.conn
-            .request("PostJupiterSwapInstructions", params)
-            .await
-    }
-
     pub async fn submit_jupiter_swap_instructions(
         &self,
         request: api::PostJupiterSwapInstructionsRequest,
         submit_opts: SubmitParams,
         use_bundle: bool,
     ) -> Result<Vec<String>> {
-        let keypair = self.get_keypair()?;
+        let signer = self.get_signer()?;
 
         let swap_instructions = self.post_jupiter_swap_instructions(&request).await?;
 
@@ -220,47 +65,93 @@ impl WebSocketClient {
             hash_res.block_hash.parse()?,
         )?);
 
-        let tx = VersionedTransaction::try_new(message, &[keypair])?;
+        let tx = new_versioned_transaction(message, signer.as_ref()).await?;
 
         let tx_message = api::TransactionMessage {
             content: general_purpose::STANDARD.encode(bincode::serialize(&tx)?),
             is_cleanup: false,
         };
 
-        self.sign_and_submit(vec![tx_message], submit_opts, use_bundle)
-            .await
+        self.sign_and_submit_with_lookup_tables(
+            vec![tx_message],
+            &swap_instructions.address_lookup_table_addresses,
+            submit_opts,
+            use_bundle,
+        )
+        .await
+    }
+
+    pub async fn submit_pump_swap(
+        &self,
+        request: &api::PostPumpFunSwapRequest,
+        submit_opts: SubmitParams,
+        use_bundle: bool,
+    ) -> Result<Vec<String>> {
+        let response = self.post_pump_swap(request).await?;
+        let txs: Vec<api::TransactionMessage> = response.transaction.into_iter().collect();
+        if txs.is_empty() {
+            return Err(anyhow::anyhow!("PostPumpFunSwap returned no transaction"));
+        }
+
+        self.sign_and_submit(txs, submit_opts, use_bundle).await
     }
 
-    pub async fn post_trade_swap(
+    // Fetches a quote, requests the swap and only signs it if the swap's output
+    // stays within `tolerance` of the quote.
+    pub async fn quote_then_swap(
         &self,
         request: &api::TradeSwapRequest,
-    ) -> Result<api::TradeSwapResponse> {
-        let params = json!({
-            "ownerAddress": request.owner_address,
-            "inToken": request.in_token,
-            "outToken": request.out_token,
-            "inAmount": request.in_amount,
-            "slippage": request.slippage,
-            "project": request.project,
-            "computeLimit": request.compute_limit,
-            "computePrice": request.compute_price,
-            "tip": request.tip,
-        });
+        tolerance: &SwapTolerance,
+        submit_opts: SubmitParams,
+        use_bundle: bool,
+    ) -> Result<Vec<String>> {
+        let quotes = self.get_quotes_request(&quotes_request(request)).await?;
+        let quoted_out_amount = best_quoted_out_amount(&quotes)?;
 
-        self.conn.request("PostTradeSwap", params).await
+        let swap = self.post_trade_swap(request).await?;
+        verify_swap_output(quoted_out_amount, &swap, tolerance)?;
+
+        self.sign_and_submit(swap.transactions, submit_opts, use_bundle)
+            .await
+    }
+
+    // Like `quote_then_swap`, against a Raydium quote.
+    pub async fn quote_then_raydium_swap(
+        &self,
+        request: &api::PostRaydiumSwapRequest,
+        tolerance: &SwapTolerance,
+        submit_opts: SubmitParams,
+        use_bundle: bool,
+    ) -> Result<Vec<String>> {
+        let quotes = self
+            .get_raydium_quotes(&raydium_quotes_request(request))
+            .await?;
+        let quoted_out_amount = best_quoted_out_amount(&quotes)?;
+
+        let swap = self.post_raydium_swap(request).await?;
+        verify_swap_output(quoted_out_amount, &swap, tolerance)?;
+
+        self.sign_and_submit(swap.transactions, submit_opts, use_bundle)
+            .await
     }
 
-    pub async fn post_route_trade_swap(
+    // Like `quote_then_swap`, against a Jupiter quote.
+    pub async fn quote_then_jupiter_swap(
         &self,
-        request: &api::RouteTradeSwapRequest,
-    ) -> Result<api::TradeSwapResponse> {
-        let params = json!({
-            "ownerAddress": request.owner_address,
-            "project": request.project,
-            "slippage": request.slippage,
-            "steps": request.steps,
-        });
+        request: &api::PostJupiterSwapRequest,
+        tolerance: &SwapTolerance,
+        submit_opts: SubmitParams,
+        use_bundle: bool,
+    ) -> Result<Vec<String>> {
+        let quotes = self
+            .get_jupiter_quotes(&jupiter_quotes_request(request))
+            .await?;
+        let quoted_out_amount = best_quoted_out_amount(&quotes)?;
 
-        self.conn.request("PostRouteTradeSwap", params).await
+        let swap = self.post_jupiter_swap(request).await?;
+        verify_swap_output(quoted_out_amount, &swap, tolerance)?;
+
+        self.sign_and_submit(swap.transactions, submit_opts, use_bundle)
+            .await
     }
 }
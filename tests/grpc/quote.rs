@@ -1,10 +1,14 @@
 use anyhow::Result;
 use solana_trader_client_rust::{
     common::constants::{MAINNET_PUMP_NY, USDC, WRAPPED_SOL},
-    provider::grpc::GrpcClient,
+    provider::{
+        grpc::GrpcClient,
+        pump::{check_against_server, PumpFunQuoter},
+    },
 };
 use solana_trader_proto::api;
 use test_case::test_case;
+use tokio_stream::StreamExt;
 
 #[test_case(
     WRAPPED_SOL,
@@ -155,6 +159,51 @@ async fn test_pump_fun_quotes_grpc(
     Ok(())
 }
 
+#[tokio::test]
+#[ignore]
+async fn test_pump_fun_local_quotes_grpc() -> Result<()> {
+    let mut client = GrpcClient::new(Some(MAINNET_PUMP_NY.to_string())).await?;
+
+    let mut tokens_stream = client.get_pump_fun_new_tokens_stream().await?;
+    let new_token = tokens_stream
+        .next()
+        .await
+        .ok_or_else(|| anyhow::anyhow!("Tokens stream ended without data"))?
+        .map_err(|e| anyhow::anyhow!("Tokens stream error: {}", e))?;
+
+    let quoter = PumpFunQuoter::new();
+    quoter.apply(&new_token)?;
+
+    let mut swaps_stream = client
+        .get_pump_fun_swaps_stream(vec![new_token.mint.clone()])
+        .await?;
+    let swap = swaps_stream
+        .next()
+        .await
+        .ok_or_else(|| anyhow::anyhow!("Swaps stream ended without data"))?
+        .map_err(|e| anyhow::anyhow!("Swaps stream error: {}", e))?;
+    quoter.apply(&swap)?;
+
+    let curve = quoter
+        .curve(&new_token.mint)
+        .ok_or_else(|| anyhow::anyhow!("No curve tracked for {}", new_token.mint))?;
+    let request = api::GetPumpFunQuotesRequest {
+        quote_type: "buy".to_string(),
+        mint_address: curve.mint_address.clone(),
+        bonding_curve_address: curve.bonding_curve_address.clone(),
+        amount: 0.01,
+    };
+
+    let local = quoter.quote(&request)?;
+    let server = client.get_pump_fun_quotes(&request).await?;
+    println!("local: {:?}, server: {:?}", local, server);
+
+    // the curve may move between the swap event and the server quote
+    check_against_server(&local, &server, 1.0)?;
+
+    Ok(())
+}
+
 #[test_case(
     WRAPPED_SOL,
     USDC,
@@ -1,7 +1,10 @@
 use anyhow::Result;
 use solana_trader_client_rust::{
     common::constants::{MAINNET_PUMP_NY, WRAPPED_SOL},
-    provider::grpc::GrpcClient,
+    provider::grpc::{
+        resilient::{ResubscribeConfig, StreamEvent},
+        GrpcClient,
+    },
 };
 use solana_trader_proto::api;
 use test_case::test_case;
@@ -58,6 +61,34 @@ async fn test_block_stream_grpc(expected_blocks: usize) -> Result<()> {
     Ok(())
 }
 
+#[test_case(3 ; "three blocks")]
+#[tokio::test]
+#[ignore]
+async fn test_block_stream_resilient_grpc(expected_blocks: usize) -> Result<()> {
+    let client = GrpcClient::new(None).await?;
+    let mut stream = client
+        .get_block_stream_resilient(api::GetBlockStreamRequest {}, ResubscribeConfig::default());
+
+    let mut blocks = 0;
+    while blocks < expected_blocks {
+        match stream
+            .next()
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Stream ended without data"))??
+        {
+            StreamEvent::Message(response) => {
+                println!("Block received: {:#?}", response);
+                blocks += 1;
+            }
+            StreamEvent::Reconnected { attempts, reason } => {
+                println!("Reconnected after {} attempts: {}", attempts, reason);
+            }
+        }
+    }
+
+    Ok(())
+}
+
 // TODO:
 // rust SDK error
 // Error: GetOrderbooksStream error: status: Cancelled, message: "Timeout expired", details: [], metadata: MetadataMap { headers: {} }
@@ -520,3 +520,47 @@ async fn test_pumpfun_swap_grpc(in_amount: f64, slippage: f64) -> Result<()> {
     println!("signature : {:#?}", s?);
     Ok(())
 }
+
+#[test_case(
+    0.0001,
+    10.0;
+    "Pumpfun submit swap"
+)]
+#[tokio::test]
+#[ignore]
+async fn test_submit_pumpfun_swap_grpc(in_amount: f64, slippage: f64) -> Result<()> {
+    let bonding_curve_address = "Fh8fnZUVEpPStJ2hKFNNjMAyuyvoJLMouENawg4DYCBc";
+    let mint_address = "2DEsbYgW94AtZxgUfYXoL8DqJAorsLrEWZdSfriipump";
+    let mut client = GrpcClient::new(Some(MAINNET_PUMP_NY.to_string())).await?;
+
+    let request = api::GetPumpFunQuotesRequest {
+        quote_type: "buy".to_string(),
+        bonding_curve_address: bonding_curve_address.to_string(),
+        amount: in_amount,
+        mint_address: mint_address.to_string(),
+    };
+
+    let pump_quote_response = client.get_pump_fun_quotes(&request).await?;
+
+    let request = api::PostPumpFunSwapRequest {
+        user_address: client
+            .public_key
+            .unwrap_or_else(|| panic!("Public key is required for pump fun swap"))
+            .to_string(),
+        bonding_curve_address: bonding_curve_address.to_string(),
+        token_address: mint_address.to_string(),
+        token_amount: pump_quote_response.out_amount,
+        sol_threshold: pump_quote_response.in_amount,
+        compute_limit: 300000,
+        compute_price: 2000,
+        tip: Some(2000001),
+        is_buy: true,
+        slippage,
+    };
+
+    let signatures = client
+        .submit_pump_swap(&request, SubmitParams::default(), false)
+        .await?;
+    println!("signatures : {:#?}", signatures);
+    Ok(())
+}
@@ -1,4 +1,5 @@
 pub mod general;
 pub mod memo;
 pub mod quote;
+pub mod stream;
 pub mod swap;
@@ -0,0 +1,92 @@
+use anyhow::Result;
+use solana_trader_client_rust::{common::constants::WRAPPED_SOL, provider::http::HTTPClient};
+use solana_trader_proto::api;
+use std::time::Duration;
+use test_case::test_case;
+use tokio_stream::StreamExt;
+
+#[test_case(
+    vec![api::Project::PRaydium],
+    vec![WRAPPED_SOL.to_string()] ;
+    "raydium SOL price stream"
+)]
+#[tokio::test]
+#[ignore]
+async fn test_price_stream_http(projects: Vec<api::Project>, tokens: Vec<String>) -> Result<()> {
+    let client = HTTPClient::new(None)?.with_poll_interval(Duration::from_millis(500));
+    let mut stream = client.get_prices_stream(projects, tokens).await?;
+
+    println!("starting price stream");
+
+    let response = stream
+        .next()
+        .await
+        .ok_or_else(|| anyhow::anyhow!("Stream ended without data"))??;
+
+    println!("Response received: {:#?}", response);
+
+    let price = response
+        .price
+        .ok_or_else(|| anyhow::anyhow!("Missing price"))?;
+
+    assert!(price.buy > 0.0, "Price should be positive");
+    Ok(())
+}
+
+#[test_case(2 ; "two block hashes")]
+#[tokio::test]
+#[ignore]
+async fn test_recent_block_hash_stream_http(expected_hashes: usize) -> Result<()> {
+    let client = HTTPClient::new(None)?.with_poll_interval(Duration::from_millis(400));
+    let mut stream = client.get_recent_block_hash_stream().await?;
+
+    println!("starting recent block hash stream");
+
+    let mut previous = String::new();
+    for hash_num in 1..=expected_hashes {
+        let response = stream
+            .next()
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Stream ended without data"))??;
+
+        println!("Block hash {} received: {:#?}", hash_num, response);
+
+        assert!(
+            !response.block_hash.is_empty(),
+            "Block hash should not be empty"
+        );
+        assert_ne!(
+            response.block_hash, previous,
+            "Duplicate block hash yielded"
+        );
+        previous = response.block_hash;
+    }
+
+    Ok(())
+}
+
+#[test_case(
+    api::Project::PRaydium,
+    None ;
+    "Raydium priority fee stream"
+)]
+#[tokio::test]
+#[ignore]
+async fn test_priority_fee_stream_http(
+    project: api::Project,
+    percentile: Option<f64>,
+) -> Result<()> {
+    let client = HTTPClient::new(None)?;
+    let mut stream = client.get_priority_fee_stream(project, percentile).await?;
+
+    println!("starting priority fee stream");
+
+    let response = stream
+        .next()
+        .await
+        .ok_or_else(|| anyhow::anyhow!("Stream ended without data"))??;
+
+    println!("Response received: {:#?}", response);
+
+    Ok(())
+}
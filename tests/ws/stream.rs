@@ -2,6 +2,7 @@ use anyhow::Result;
 use futures_util::StreamExt;
 use solana_trader_client_rust::{
     common::constants::{MAINNET_PUMP_NY, WRAPPED_SOL},
+    connections::backpressure::Backpressure,
     provider::ws::WebSocketClient,
 };
 use solana_trader_proto::api;
@@ -33,6 +34,46 @@ async fn test_price_stream_ws(projects: Vec<api::Project>, tokens: Vec<String>)
     Ok(())
 }
 
+#[tokio::test]
+#[ignore]
+async fn test_shared_block_stream_ws() -> Result<()> {
+    let ws = WebSocketClient::new(None).await?;
+    let mut first = ws
+        .shared_stream::<_, api::GetBlockStreamResponse>(
+            "GetBlockStream",
+            &api::GetBlockStreamRequest {},
+        )
+        .await?;
+    let mut second = ws
+        .shared_stream::<_, api::GetBlockStreamResponse>(
+            "GetBlockStream",
+            &api::GetBlockStreamRequest {},
+        )
+        .await?;
+
+    let first_block = first
+        .next()
+        .await
+        .ok_or_else(|| anyhow::anyhow!("Stream ended without data"))??;
+    let second_block = second
+        .next()
+        .await
+        .ok_or_else(|| anyhow::anyhow!("Stream ended without data"))??;
+    println!("Blocks received: {:#?} {:#?}", first_block, second_block);
+
+    // A block can arrive between the two subscriptions, so the second consumer may start later.
+    let slot = |response: &api::GetBlockStreamResponse| response.block.as_ref().map(|b| b.slot);
+    assert!(
+        slot(&second_block) >= slot(&first_block),
+        "The second consumer should not see an earlier block"
+    );
+
+    drop(first);
+    drop(second);
+    ws.close().await?;
+    Ok(())
+}
+
 #[test_case(1 ; "single block")]
 #[tokio::test]
 #[ignore]
@@ -288,6 +329,44 @@ async fn test_pool_reserves_stream_ws(
     Ok(())
 }
 
+#[test_case(
+    Backpressure::ConflateLatest { key: &["reserves", "poolAddress"], capacity: 16 } ;
+    "conflated pool reserves stream"
+)]
+#[test_case(Backpressure::DropOldest { capacity: 1 } ; "drop oldest pool reserves stream")]
+#[tokio::test]
+#[ignore]
+async fn test_pool_reserves_stream_backpressure_ws(backpressure: Backpressure) -> Result<()> {
+    let ws = WebSocketClient::new(None)
+        .await?
+        .with_backpressure("GetPoolReservesStream", backpressure)?;
+    let mut stream = ws
+        .get_pool_reserves_stream(
+            vec![api::Project::PRaydium],
+            vec!["58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2".to_string()],
+        )
+        .await?;
+    assert_eq!(stream.backpressure(), backpressure);
+
+    // Fall behind so the policy has to act.
+    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+    let response = stream
+        .next()
+        .await
+        .ok_or_else(|| anyhow::anyhow!("Stream ended without data"))??;
+
+    let counters = stream.counters();
+    println!(
+        "Response received: {:#?}, dropped {}, conflated {}",
+        response,
+        counters.dropped(),
+        counters.conflated()
+    );
+
+    ws.close().await?;
+    Ok(())
+}
+
 #[test_case(
     api::Project::PRaydium,
     None ;
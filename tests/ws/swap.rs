@@ -1,7 +1,7 @@
 use anyhow::Result;
 use solana_trader_client_rust::{
     common::{
-        constants::{USDC, WRAPPED_SOL},
+        constants::{MAINNET_PUMP_NY, USDC, WRAPPED_SOL},
         signing::SubmitParams,
     },
     provider::ws::WebSocketClient,
@@ -483,3 +483,57 @@ async fn test_jupiter_swap_instructions_ws(
     client.close().await?;
     Ok(())
 }
+
+#[test_case(
+    0.0001,
+    10.0;
+    "Pumpfun swap"
+)]
+#[tokio::test]
+#[ignore]
+async fn test_pumpfun_swap_ws(in_amount: f64, slippage: f64) -> Result<()> {
+    let bonding_curve_address = "Fh8fnZUVEpPStJ2hKFNNjMAyuyvoJLMouENawg4DYCBc";
+    let mint_address = "2DEsbYgW94AtZxgUfYXoL8DqJAorsLrEWZdSfriipump";
+    let client = WebSocketClient::new(Some(MAINNET_PUMP_NY.to_string())).await?;
+
+    let request = api::GetPumpFunQuotesRequest {
+        quote_type: "buy".to_string(),
+        bonding_curve_address: bonding_curve_address.to_string(),
+        amount: in_amount,
+        mint_address: mint_address.to_string(),
+    };
+
+    let pump_quote_response = client.get_pump_fun_quotes(&request).await?;
+
+    let request = api::PostPumpFunSwapRequest {
+        user_address: client
+            .public_key
+            .unwrap_or_else(|| panic!("Public key is required for pump fun swap"))
+            .to_string(),
+        bonding_curve_address: bonding_curve_address.to_string(),
+        token_address: mint_address.to_string(),
+        token_amount: pump_quote_response.out_amount,
+        sol_threshold: pump_quote_response.in_amount,
+        compute_limit: 300000,
+        compute_price: 2000,
+        tip: Some(2000001),
+        is_buy: true,
+        slippage,
+    };
+
+    let response = client.post_pump_swap(&request).await?;
+    println!(
+        "pumpfun Quote: {}",
+        serde_json::to_string_pretty(&response)?
+    );
+
+    let txs = response.transaction.as_slice();
+    let submit_opts = SubmitParams::default();
+    let s = client
+        .sign_and_submit(txs.to_vec(), submit_opts, false)
+        .await;
+    println!("signature : {:#?}", s?);
+
+    client.close().await?;
+    Ok(())
+}